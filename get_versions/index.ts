@@ -70,7 +70,7 @@ const file = `#![allow(non_camel_case_types)]
 use crate::data_types::VarInt;
 
 /// Enum of all versions of the game after the Netty rewrite.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Version {
     ${str.join("\n    ")}
 }
@@ -0,0 +1,305 @@
+use std::{
+    fmt::Debug,
+    ops::{BitOr, BitOrAssign},
+    str::FromStr,
+};
+
+use super::{varint_enum, Boolean, BoundedString, Codec, DataResult};
+
+/// Which chat messages the client displays.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ChatMode {
+    #[default]
+    Enabled,
+    /// Only the results of commands.
+    CommandsOnly,
+    Hidden,
+}
+
+varint_enum!(ChatMode {
+    Enabled = 0,
+    CommandsOnly = 1,
+    Hidden = 2,
+});
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum MainHand {
+    Left,
+    #[default]
+    Right,
+}
+
+varint_enum!(MainHand {
+    Left = 0,
+    Right = 1,
+});
+
+/// The parts of the skin the client displays, sent as a bit mask.
+///
+/// ```
+/// # use redstone_signal::data_types::client_information::SkinParts;
+/// let parts = SkinParts::CAPE | SkinParts::HAT;
+/// assert!(parts.contains(SkinParts::HAT));
+/// assert_eq!(parts.bits(), 0x41);
+/// ```
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct SkinParts(u8);
+
+impl SkinParts {
+    pub const CAPE: SkinParts = SkinParts(0x01);
+    pub const JACKET: SkinParts = SkinParts(0x02);
+    pub const LEFT_SLEEVE: SkinParts = SkinParts(0x04);
+    pub const RIGHT_SLEEVE: SkinParts = SkinParts(0x08);
+    pub const LEFT_PANTS_LEG: SkinParts = SkinParts(0x10);
+    pub const RIGHT_PANTS_LEG: SkinParts = SkinParts(0x20);
+    pub const HAT: SkinParts = SkinParts(0x40);
+
+    pub const NONE: SkinParts = SkinParts(0);
+    pub const ALL: SkinParts = SkinParts(0x7F);
+
+    const NAMES: [(SkinParts, &'static str); 7] = [
+        (SkinParts::CAPE, "CAPE"),
+        (SkinParts::JACKET, "JACKET"),
+        (SkinParts::LEFT_SLEEVE, "LEFT_SLEEVE"),
+        (SkinParts::RIGHT_SLEEVE, "RIGHT_SLEEVE"),
+        (SkinParts::LEFT_PANTS_LEG, "LEFT_PANTS_LEG"),
+        (SkinParts::RIGHT_PANTS_LEG, "RIGHT_PANTS_LEG"),
+        (SkinParts::HAT, "HAT"),
+    ];
+
+    /// Creates the set from a bit mask. The unused high bit is kept, so it is sent back as received.
+    #[must_use]
+    pub fn from_bits(bits: u8) -> Self {
+        SkinParts(bits)
+    }
+
+    #[must_use]
+    pub fn bits(self) -> u8 {
+        self.0
+    }
+
+    /// Whether every part of `parts` is displayed.
+    #[must_use]
+    pub fn contains(self, parts: SkinParts) -> bool {
+        self.0 & parts.0 == parts.0
+    }
+
+    pub fn insert(&mut self, parts: SkinParts) {
+        self.0 |= parts.0;
+    }
+
+    pub fn remove(&mut self, parts: SkinParts) {
+        self.0 &= !parts.0;
+    }
+}
+
+impl BitOr for SkinParts {
+    type Output = SkinParts;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        SkinParts(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for SkinParts {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.insert(rhs);
+    }
+}
+
+impl Debug for SkinParts {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set()
+            .entries(
+                Self::NAMES
+                    .iter()
+                    .filter(|(part, _)| self.contains(*part))
+                    .map(|(_, name)| name),
+            )
+            .finish()
+    }
+}
+
+impl Codec for SkinParts {
+    fn write(&self, bytes: &mut Vec<u8>) -> DataResult<()> {
+        self.0.write(bytes)
+    }
+
+    fn read(data: &[u8]) -> DataResult<(Self, usize)> {
+        let (bits, len) = u8::read(data)?;
+        Ok((SkinParts(bits), len))
+    }
+}
+
+/// The client's settings, sent in configuration when joining, and in play whenever they change.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientInformation {
+    /// e.g. `en_us`
+    pub locale: BoundedString<16>,
+    /// Client-side render distance, in chunks.
+    pub view_distance: i8,
+    pub chat_mode: ChatMode,
+    pub chat_colors: Boolean,
+    pub displayed_skin_parts: SkinParts,
+    pub main_hand: MainHand,
+    /// Enables filtering of text on signs and written book titles.
+    pub enable_text_filtering: Boolean,
+    /// Whether the player can be shown in the player sample of the server list.
+    pub allow_server_listings: Boolean,
+}
+
+impl Default for ClientInformation {
+    /// The settings of a new Notchian client.
+    fn default() -> Self {
+        ClientInformation {
+            locale: BoundedString::from_str("en_us").expect("en_us is a valid locale"),
+            view_distance: 12,
+            chat_mode: ChatMode::Enabled,
+            chat_colors: Boolean::from(true),
+            displayed_skin_parts: SkinParts::ALL,
+            main_hand: MainHand::Right,
+            enable_text_filtering: Boolean::from(false),
+            allow_server_listings: Boolean::from(true),
+        }
+    }
+}
+
+impl ClientInformation {
+    /// Minimum view distance of the Notchian server, whatever the client sends.
+    pub const MIN_VIEW_DISTANCE: u8 = 2;
+
+    /// The locale in lower case, as the Notchian client sends it, e.g. `en_us`.
+    ///
+    /// Older clients and some mods send `en_US`.
+    #[must_use]
+    pub fn locale(&self) -> String {
+        self.locale.to_ascii_lowercase()
+    }
+
+    /// The view distance to use for this player: the client's, capped by the server's `max`,
+    /// and at least [`ClientInformation::MIN_VIEW_DISTANCE`], even when `max` is lower.
+    #[must_use]
+    pub fn view_distance(&self, max: u8) -> u8 {
+        let requested = u8::try_from(self.view_distance).unwrap_or(0);
+
+        requested.clamp(Self::MIN_VIEW_DISTANCE, max.max(Self::MIN_VIEW_DISTANCE))
+    }
+}
+
+impl Codec for ClientInformation {
+    fn write(&self, bytes: &mut Vec<u8>) -> DataResult<()> {
+        self.locale.write(bytes)?;
+        self.view_distance.write(bytes)?;
+        self.chat_mode.write(bytes)?;
+        self.chat_colors.write(bytes)?;
+        self.displayed_skin_parts.write(bytes)?;
+        self.main_hand.write(bytes)?;
+        self.enable_text_filtering.write(bytes)?;
+        self.allow_server_listings.write(bytes)
+    }
+
+    fn read(data: &[u8]) -> DataResult<(Self, usize)> {
+        let (locale, mut position) = BoundedString::read(data)?;
+        let (view_distance, len) = i8::read(&data[position..])?;
+        position += len;
+        let (chat_mode, len) = ChatMode::read(&data[position..])?;
+        position += len;
+        let (chat_colors, len) = Boolean::read(&data[position..])?;
+        position += len;
+        let (displayed_skin_parts, len) = SkinParts::read(&data[position..])?;
+        position += len;
+        let (main_hand, len) = MainHand::read(&data[position..])?;
+        position += len;
+        let (enable_text_filtering, len) = Boolean::read(&data[position..])?;
+        position += len;
+        let (allow_server_listings, len) = Boolean::read(&data[position..])?;
+        position += len;
+
+        Ok((
+            ClientInformation {
+                locale,
+                view_distance,
+                chat_mode,
+                chat_colors,
+                displayed_skin_parts,
+                main_hand,
+                enable_text_filtering,
+                allow_server_listings,
+            },
+            position,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use crate::data_types::{BoundedString, Codec, Errors, GenericError};
+
+    use super::{ChatMode, ClientInformation, MainHand, SkinParts};
+
+    #[test]
+    fn codec() {
+        let information = ClientInformation {
+            locale: BoundedString::from_str("en_US").unwrap(),
+            chat_mode: ChatMode::CommandsOnly,
+            displayed_skin_parts: SkinParts::ALL,
+            main_hand: MainHand::Left,
+            ..ClientInformation::default()
+        };
+
+        let mut bytes = Vec::new();
+        information.write(&mut bytes).unwrap();
+        assert_eq!(
+            bytes,
+            [5, b'e', b'n', b'_', b'U', b'S', 12, 1, 1, 0x7F, 0, 0, 1]
+        );
+        assert_eq!(
+            ClientInformation::read(&bytes).unwrap(),
+            (information, bytes.len())
+        );
+
+        assert_eq!(
+            ClientInformation::read(&[5, b'e', b'n', b'_', b'u', b's', 12]),
+            Err(Errors::GenericError(GenericError::UnexpectedEnd))
+        );
+        assert_eq!(
+            ClientInformation::read(&[5, b'e', b'n', b'_', b'u', b's', 12, 3]),
+            Err(Errors::GenericError(GenericError::UnknownVariant))
+        );
+    }
+
+    #[test]
+    fn skin_parts() {
+        let mut parts = SkinParts::JACKET | SkinParts::HAT;
+        assert!(parts.contains(SkinParts::HAT));
+        assert!(!parts.contains(SkinParts::HAT | SkinParts::CAPE));
+
+        parts |= SkinParts::CAPE;
+        parts.remove(SkinParts::JACKET);
+        assert_eq!(parts.bits(), 0x41);
+        assert_eq!(format!("{parts:?}"), r#"{"CAPE", "HAT"}"#);
+
+        assert!(SkinParts::ALL.contains(SkinParts::RIGHT_PANTS_LEG));
+        assert_eq!(SkinParts::from_bits(0xFF).bits(), 0xFF);
+    }
+
+    #[test]
+    fn settings() {
+        let mut information = ClientInformation {
+            locale: BoundedString::from_str("fr_FR").unwrap(),
+            ..ClientInformation::default()
+        };
+        assert_eq!(information.locale(), "fr_fr");
+
+        assert_eq!(information.view_distance(10), 10);
+        information.view_distance = 8;
+        assert_eq!(information.view_distance(10), 8);
+        information.view_distance = -1;
+        assert_eq!(information.view_distance(10), 2);
+
+        information.view_distance = 8;
+        assert_eq!(information.view_distance(1), 2);
+    }
+}
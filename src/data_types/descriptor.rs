@@ -0,0 +1,679 @@
+use std::str::FromStr;
+
+use thiserror::Error;
+use uuid::Uuid;
+
+use super::{Boolean, Codec, DataResult, DataTypes, GenericError, Nbt, Position, SerDe, VarInt};
+use crate::values::Version;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Error)]
+pub enum DescriptorError {
+    #[error("The value does not match the type descriptor")]
+    TypeMismatch,
+    #[error("Elements of an array must take at least a byte")]
+    EmptyElement,
+}
+
+/// Describes the layout of a protocol value at runtime, so it can be decoded into a [`DataTypes`] tree and back
+/// without a Rust definition. See [`TypeDescriptor::decode`] and [`TypeDescriptor::encode`].
+///
+/// Leaves are the [`DataTypes`] kinds with a codec, and they are composed with [`TypeDescriptor::Optional`],
+/// the arrays, [`TypeDescriptor::Enum`] and [`TypeDescriptor::Container`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TypeDescriptor {
+    /// Nothing, e.g. the fields of a fieldless enum variant. Decoded as [`DataTypes::Void`].
+    Void,
+    Boolean,
+    Byte,
+    UnsignedByte,
+    Short,
+    UnsignedShort,
+    Int,
+    UnsignedInt,
+    Long,
+    Float,
+    Double,
+    String,
+    Identifier,
+    VarInt,
+    VarLong,
+    NBTTag,
+    Position,
+    Angle,
+    UUID,
+    /// A byte array prefixed by its length.
+    ByteArray(CountKind),
+    /// The rest of the packet, as a byte array without a length prefix.
+    RestBuffer,
+    BitSet,
+    /// A [`Boolean`] prefixed optional value, see [`Optional`](super::Optional).
+    Optional(Box<TypeDescriptor>),
+    /// A list prefixed by its length, see [`Array`](super::Array) for a [`VarInt`] length.
+    Array(CountKind, Box<TypeDescriptor>),
+    /// A list taking the rest of the packet, see [`InferredArray`](super::InferredArray).
+    InferredArray(Box<TypeDescriptor>),
+    Enum(EnumDescriptor),
+    /// Named fields, one after the other.
+    Container(Vec<(std::string::String, TypeDescriptor)>),
+}
+
+/// How the variant of an [`EnumDescriptor`] is sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiscriminantKind {
+    /// The [`VariantDescriptor::id`] as a [`VarInt`].
+    VarInt,
+    /// The [`VariantDescriptor::id`] as an unsigned byte.
+    Byte,
+    /// The [`VariantDescriptor::name`] as a [`String`](super::String).
+    String,
+}
+
+/// How the length of a [`TypeDescriptor::ByteArray`] or a [`TypeDescriptor::Array`] is sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CountKind {
+    VarInt,
+    Byte,
+    UnsignedByte,
+    Short,
+    Int,
+}
+
+impl CountKind {
+    /// Reads a length, returning it and the amount of bytes read.
+    fn read(self, data: &[u8]) -> DataResult<(usize, usize)> {
+        let (len, read) = match self {
+            CountKind::VarInt => {
+                let (len, read) = VarInt::read(data)?;
+                (len.value(), read)
+            }
+            CountKind::Byte => {
+                let (len, read) = i8::read(data)?;
+                (len.into(), read)
+            }
+            CountKind::UnsignedByte => {
+                let (len, read) = u8::read(data)?;
+                (len.into(), read)
+            }
+            CountKind::Short => {
+                let (len, read) = i16::read(data)?;
+                (len.into(), read)
+            }
+            CountKind::Int => i32::read(data)?,
+        };
+
+        Ok((
+            usize::try_from(len).map_err(|_| GenericError::NegativeLength)?,
+            read,
+        ))
+    }
+
+    fn write(self, len: usize, bytes: &mut Vec<u8>) -> DataResult<()> {
+        let len = i32::try_from(len).map_err(|_| GenericError::TooLong)?;
+        match self {
+            CountKind::VarInt => VarInt::new(len).write(bytes),
+            CountKind::Byte => i8::try_from(len)
+                .map_err(|_| GenericError::TooLong)?
+                .write(bytes),
+            CountKind::UnsignedByte => u8::try_from(len)
+                .map_err(|_| GenericError::TooLong)?
+                .write(bytes),
+            CountKind::Short => i16::try_from(len)
+                .map_err(|_| GenericError::TooLong)?
+                .write(bytes),
+            CountKind::Int => len.write(bytes),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnumDescriptor {
+    pub discriminant: DiscriminantKind,
+    pub variants: Vec<VariantDescriptor>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VariantDescriptor {
+    pub id: i32,
+    pub name: std::string::String,
+    /// What follows the discriminant, [`TypeDescriptor::Void`] if nothing.
+    pub fields: TypeDescriptor,
+}
+
+impl EnumDescriptor {
+    fn by_id(&self, id: i32) -> DataResult<&VariantDescriptor> {
+        Ok(self
+            .variants
+            .iter()
+            .find(|variant| variant.id == id)
+            .ok_or(GenericError::UnknownVariant)?)
+    }
+
+    fn by_name(&self, name: &str) -> DataResult<&VariantDescriptor> {
+        Ok(self
+            .variants
+            .iter()
+            .find(|variant| variant.name == name)
+            .ok_or(GenericError::UnknownVariant)?)
+    }
+
+    fn decode(&self, data: &[u8], version: Version) -> DataResult<(DataTypes, usize)> {
+        let (variant, position) = match self.discriminant {
+            DiscriminantKind::VarInt => {
+                let (id, len) = VarInt::read(data)?;
+                (self.by_id(id.value())?, len)
+            }
+            DiscriminantKind::Byte => {
+                let (id, len) = u8::read(data)?;
+                (self.by_id(id.into())?, len)
+            }
+            DiscriminantKind::String => {
+                let (name, len) = super::String::read(data)?;
+                (self.by_name(&name.data)?, len)
+            }
+        };
+
+        let (value, len) = variant.fields.decode(&data[position..], version)?;
+
+        Ok((
+            DataTypes::Enum {
+                variant: variant.name.clone(),
+                value: Box::new(value),
+            },
+            position + len,
+        ))
+    }
+}
+
+/// Reads a value with its [`Codec`] and wraps it into a [`DataTypes`] variant.
+fn read<T: Codec>(
+    data: &[u8],
+    wrap: impl FnOnce(T) -> DataTypes,
+) -> DataResult<(DataTypes, usize)> {
+    let (value, len) = T::read(data)?;
+    Ok((wrap(value), len))
+}
+
+impl TypeDescriptor {
+    /// Decodes a value of this type from the start of `data`, in the form used by `version`.
+    ///
+    /// Returns the value and the amount of bytes read.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`DescriptorError::EmptyElement`] if the elements of a non-empty array take no bytes,
+    /// [`GenericError::UnknownVariant`] if an enum discriminant has no variant,
+    /// or the error of the codec of any value in the tree.
+    pub fn decode(&self, data: &[u8], version: Version) -> DataResult<(DataTypes, usize)> {
+        match self {
+            TypeDescriptor::Void => Ok((DataTypes::Void, 0)),
+            TypeDescriptor::Boolean => read(data, DataTypes::Boolean),
+            TypeDescriptor::Byte => read(data, DataTypes::Byte),
+            TypeDescriptor::UnsignedByte => read(data, DataTypes::UnsignedByte),
+            TypeDescriptor::Short => read(data, DataTypes::Short),
+            TypeDescriptor::UnsignedShort => read(data, DataTypes::UnsignedShort),
+            TypeDescriptor::Int => read(data, DataTypes::Int),
+            TypeDescriptor::UnsignedInt => read(data, DataTypes::UnsinedInt),
+            TypeDescriptor::Long => read(data, DataTypes::Long),
+            TypeDescriptor::Float => read(data, DataTypes::Float),
+            TypeDescriptor::Double => read(data, DataTypes::Double),
+            TypeDescriptor::String => read(data, DataTypes::String),
+            TypeDescriptor::Identifier => read(data, DataTypes::Identifier),
+            TypeDescriptor::VarInt => read(data, DataTypes::VarInt),
+            TypeDescriptor::VarLong => read(data, DataTypes::VarLong),
+            TypeDescriptor::Angle => read(data, DataTypes::Angle),
+            TypeDescriptor::UUID => read::<Uuid>(data, DataTypes::UUID),
+            TypeDescriptor::BitSet => read(data, DataTypes::BitSet),
+            TypeDescriptor::NBTTag => {
+                let (nbt, len) = Nbt::decode(data, version)?;
+                Ok((DataTypes::NBTTag(nbt), len))
+            }
+            TypeDescriptor::Position => {
+                let (value, len) = i64::read(data)?;
+                Ok((
+                    DataTypes::Position(Position::decode_for(value, version)?),
+                    len,
+                ))
+            }
+            TypeDescriptor::ByteArray(count) => {
+                let (len, position) = count.read(data)?;
+                let bytes = data
+                    .get(position..position + len)
+                    .ok_or(GenericError::UnexpectedEnd)?;
+                Ok((DataTypes::ByteArray(bytes.to_vec()), position + len))
+            }
+            TypeDescriptor::RestBuffer => Ok((DataTypes::ByteArray(data.to_vec()), data.len())),
+            TypeDescriptor::Optional(inner) => {
+                let (present, position) = Boolean::read(data)?;
+                if bool::from(present) {
+                    let (value, len) = inner.decode(&data[position..], version)?;
+                    Ok((DataTypes::Optional(Some(Box::new(value))), position + len))
+                } else {
+                    Ok((DataTypes::Optional(None), position))
+                }
+            }
+            TypeDescriptor::Array(count, inner) => {
+                let (len, mut position) = count.read(data)?;
+
+                let mut values = Vec::with_capacity(len.min(data.len() - position));
+                for _ in 0..len {
+                    let (value, read) = inner.decode(&data[position..], version)?;
+                    if read == 0 {
+                        // Nothing would bound the length, which comes from the network
+                        return Err(DescriptorError::EmptyElement)?;
+                    }
+                    position += read;
+                    values.push(value);
+                }
+
+                Ok((DataTypes::Array(values), position))
+            }
+            TypeDescriptor::InferredArray(inner) => {
+                let mut values = Vec::new();
+                let mut position = 0;
+
+                while position < data.len() {
+                    let (value, read) = inner.decode(&data[position..], version)?;
+                    if read == 0 {
+                        // A type without bytes would never reach the end
+                        return Err(DescriptorError::EmptyElement)?;
+                    }
+                    position += read;
+                    values.push(value);
+                }
+
+                Ok((DataTypes::Array(values), position))
+            }
+            TypeDescriptor::Enum(descriptor) => descriptor.decode(data, version),
+            TypeDescriptor::Container(fields) => {
+                let mut values = Vec::with_capacity(fields.len());
+                let mut position = 0;
+
+                for (name, descriptor) in fields {
+                    let (value, read) = descriptor.decode(&data[position..], version)?;
+                    position += read;
+                    values.push((name.clone(), value));
+                }
+
+                Ok((DataTypes::Container(values), position))
+            }
+        }
+    }
+
+    /// Encodes `value` as this type, in the form used by `version`.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`DescriptorError::TypeMismatch`] if `value` does not have the shape of this type,
+    /// including container fields with other names or in another order,
+    /// [`GenericError::UnknownVariant`] if an enum variant is not described,
+    /// or the error of the codec of any value in the tree.
+    pub fn encode(&self, value: &DataTypes, version: Version) -> DataResult<Vec<u8>> {
+        let mut bytes = Vec::new();
+        self.write(value, version, &mut bytes)?;
+        Ok(bytes)
+    }
+
+    fn write(&self, value: &DataTypes, version: Version, bytes: &mut Vec<u8>) -> DataResult<()> {
+        match (self, value) {
+            (TypeDescriptor::Void, DataTypes::Void) => Ok(()),
+            (TypeDescriptor::Boolean, DataTypes::Boolean(value)) => value.write(bytes),
+            (TypeDescriptor::Byte, DataTypes::Byte(value)) => value.write(bytes),
+            (TypeDescriptor::UnsignedByte, DataTypes::UnsignedByte(value)) => value.write(bytes),
+            (TypeDescriptor::Short, DataTypes::Short(value)) => value.write(bytes),
+            (TypeDescriptor::UnsignedShort, DataTypes::UnsignedShort(value)) => value.write(bytes),
+            (TypeDescriptor::Int, DataTypes::Int(value)) => value.write(bytes),
+            (TypeDescriptor::UnsignedInt, DataTypes::UnsinedInt(value)) => value.write(bytes),
+            (TypeDescriptor::Long, DataTypes::Long(value)) => value.write(bytes),
+            (TypeDescriptor::Float, DataTypes::Float(value)) => value.write(bytes),
+            (TypeDescriptor::Double, DataTypes::Double(value)) => value.write(bytes),
+            (TypeDescriptor::String, DataTypes::String(value)) => value.write(bytes),
+            (TypeDescriptor::Identifier, DataTypes::Identifier(value)) => value.write(bytes),
+            (TypeDescriptor::VarInt, DataTypes::VarInt(value)) => value.write(bytes),
+            (TypeDescriptor::VarLong, DataTypes::VarLong(value)) => value.write(bytes),
+            (TypeDescriptor::Angle, DataTypes::Angle(value)) => value.write(bytes),
+            (TypeDescriptor::UUID, DataTypes::UUID(value)) => value.write(bytes),
+            (TypeDescriptor::BitSet, DataTypes::BitSet(value)) => value.write(bytes),
+            (TypeDescriptor::NBTTag, DataTypes::NBTTag(value)) => {
+                bytes.append(&mut value.encode(version)?);
+                Ok(())
+            }
+            (TypeDescriptor::Position, DataTypes::Position(value)) => {
+                value.encode_for(version).write(bytes)
+            }
+            (TypeDescriptor::ByteArray(count), DataTypes::ByteArray(value)) => {
+                count.write(value.len(), bytes)?;
+                bytes.extend_from_slice(value);
+                Ok(())
+            }
+            (TypeDescriptor::RestBuffer, DataTypes::ByteArray(value)) => {
+                bytes.extend_from_slice(value);
+                Ok(())
+            }
+            (TypeDescriptor::Optional(inner), DataTypes::Optional(value)) => {
+                Boolean::from(value.is_some()).write(bytes)?;
+                match value {
+                    Some(value) => inner.write(value, version, bytes),
+                    None => Ok(()),
+                }
+            }
+            (TypeDescriptor::Array(count, inner), DataTypes::Array(values)) => {
+                count.write(values.len(), bytes)?;
+                values
+                    .iter()
+                    .try_for_each(|value| inner.write(value, version, bytes))
+            }
+            (TypeDescriptor::InferredArray(inner), DataTypes::Array(values)) => values
+                .iter()
+                .try_for_each(|value| inner.write(value, version, bytes)),
+            (TypeDescriptor::Enum(descriptor), DataTypes::Enum { variant, value }) => {
+                let variant = descriptor.by_name(variant)?;
+
+                match descriptor.discriminant {
+                    DiscriminantKind::VarInt => VarInt::new(variant.id).write(bytes)?,
+                    DiscriminantKind::Byte => u8::try_from(variant.id)
+                        .map_err(|_| DescriptorError::TypeMismatch)?
+                        .write(bytes)?,
+                    DiscriminantKind::String => {
+                        bytes.append(&mut super::String::from_str(&variant.name)?.encode()?);
+                    }
+                }
+
+                variant.fields.write(value, version, bytes)
+            }
+            (TypeDescriptor::Container(fields), DataTypes::Container(values)) => {
+                if fields.len() != values.len() {
+                    return Err(DescriptorError::TypeMismatch)?;
+                }
+
+                fields.iter().zip(values).try_for_each(
+                    |((name, descriptor), (value_name, value))| {
+                        if name != value_name {
+                            return Err(DescriptorError::TypeMismatch)?;
+                        }
+                        descriptor.write(value, version, bytes)
+                    },
+                )
+            }
+            _ => Err(DescriptorError::TypeMismatch)?,
+        }
+    }
+}
+
+impl DataTypes {
+    /// Returns the field called `name` if this is a [`DataTypes::Container`].
+    #[must_use]
+    pub fn field(&self, name: &str) -> Option<&DataTypes> {
+        match self {
+            DataTypes::Container(fields) => fields
+                .iter()
+                .find(|(field, _)| field == name)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use uuid::Uuid;
+
+    use crate::{
+        data_types::{DataTypes, Errors, GenericError, Identifier, Position, VarInt},
+        values::Version,
+    };
+
+    use super::{
+        CountKind, DescriptorError, DiscriminantKind, EnumDescriptor, TypeDescriptor,
+        VariantDescriptor,
+    };
+
+    const VERSION: Version = Version::Release1_20_4;
+
+    fn hand() -> TypeDescriptor {
+        TypeDescriptor::Enum(EnumDescriptor {
+            discriminant: DiscriminantKind::VarInt,
+            variants: vec![
+                VariantDescriptor {
+                    id: 0,
+                    name: "main_hand".into(),
+                    fields: TypeDescriptor::Void,
+                },
+                VariantDescriptor {
+                    id: 1,
+                    name: "off_hand".into(),
+                    fields: TypeDescriptor::Container(vec![(
+                        "slot".into(),
+                        TypeDescriptor::UnsignedByte,
+                    )]),
+                },
+            ],
+        })
+    }
+
+    /// A decoded string, which has the default maximum length.
+    fn string(value: &str) -> DataTypes {
+        let mut string = crate::data_types::String::new(None).unwrap();
+        string.insert(value.into()).unwrap();
+        DataTypes::String(string)
+    }
+
+    fn packet() -> TypeDescriptor {
+        TypeDescriptor::Container(vec![
+            ("id".into(), TypeDescriptor::UUID),
+            ("name".into(), TypeDescriptor::String),
+            ("dimension".into(), TypeDescriptor::Identifier),
+            (
+                "location".into(),
+                TypeDescriptor::Optional(Box::new(TypeDescriptor::Position)),
+            ),
+            (
+                "hands".into(),
+                TypeDescriptor::Array(CountKind::VarInt, Box::new(hand())),
+            ),
+            ("rest".into(), TypeDescriptor::RestBuffer),
+        ])
+    }
+
+    fn value() -> DataTypes {
+        DataTypes::Container(vec![
+            ("id".into(), DataTypes::UUID(Uuid::from_u128(1))),
+            ("name".into(), string("Notch")),
+            (
+                "dimension".into(),
+                DataTypes::Identifier(Identifier::from_str("overworld").unwrap()),
+            ),
+            (
+                "location".into(),
+                DataTypes::Optional(Some(Box::new(DataTypes::Position(
+                    Position::new(1, 2, 3).unwrap(),
+                )))),
+            ),
+            (
+                "hands".into(),
+                DataTypes::Array(vec![
+                    DataTypes::Enum {
+                        variant: "main_hand".into(),
+                        value: Box::new(DataTypes::Void),
+                    },
+                    DataTypes::Enum {
+                        variant: "off_hand".into(),
+                        value: Box::new(DataTypes::Container(vec![(
+                            "slot".into(),
+                            DataTypes::UnsignedByte(40),
+                        )])),
+                    },
+                ]),
+            ),
+            ("rest".into(), DataTypes::ByteArray(vec![0xCA, 0xFE])),
+        ])
+    }
+
+    #[test]
+    fn round_trip() {
+        let bytes = packet().encode(&value(), VERSION).unwrap();
+        assert_eq!(&bytes[..16], Uuid::from_u128(1).as_bytes());
+        assert_eq!(
+            &bytes[bytes.len() - 6..],
+            [0x02, 0x00, 0x01, 40, 0xCA, 0xFE]
+        );
+
+        let (decoded, len) = packet().decode(&bytes, VERSION).unwrap();
+        assert_eq!(decoded, value());
+        assert_eq!(len, bytes.len());
+        assert_eq!(decoded.field("name"), Some(&string("Notch")));
+    }
+
+    #[test]
+    fn version_aware() {
+        let position = TypeDescriptor::Position;
+        let value = DataTypes::Position(Position::new(1, 2, 3).unwrap());
+        assert_ne!(
+            position.encode(&value, VERSION).unwrap(),
+            position.encode(&value, Version::Release1_12_2).unwrap()
+        );
+        let bytes = position.encode(&value, Version::Release1_12_2).unwrap();
+        assert_eq!(
+            position.decode(&bytes, Version::Release1_12_2).unwrap(),
+            (value, 8)
+        );
+    }
+
+    #[test]
+    fn enums() {
+        let by_byte = TypeDescriptor::Enum(EnumDescriptor {
+            discriminant: DiscriminantKind::Byte,
+            variants: vec![VariantDescriptor {
+                id: 3,
+                name: "three".into(),
+                fields: TypeDescriptor::Void,
+            }],
+        });
+        let three = DataTypes::Enum {
+            variant: "three".into(),
+            value: Box::new(DataTypes::Void),
+        };
+        assert_eq!(by_byte.encode(&three, VERSION).unwrap(), [3]);
+        assert_eq!(by_byte.decode(&[3], VERSION).unwrap(), (three, 1));
+        assert_eq!(
+            by_byte.decode(&[4], VERSION),
+            Err(Errors::GenericError(GenericError::UnknownVariant))
+        );
+
+        let by_name = TypeDescriptor::Enum(EnumDescriptor {
+            discriminant: DiscriminantKind::String,
+            variants: vec![VariantDescriptor {
+                id: 0,
+                name: "minecraft:brand".into(),
+                fields: TypeDescriptor::String,
+            }],
+        });
+        let bytes = [
+            vec![15],
+            b"minecraft:brand".to_vec(),
+            vec![7],
+            b"vanilla".to_vec(),
+        ]
+        .concat();
+        let (value, len) = by_name.decode(&bytes, VERSION).unwrap();
+        assert_eq!(len, bytes.len());
+        assert_eq!(by_name.encode(&value, VERSION).unwrap(), bytes);
+    }
+
+    #[test]
+    fn inferred_array() {
+        let descriptor = TypeDescriptor::InferredArray(Box::new(TypeDescriptor::VarInt));
+        let (value, len) = descriptor.decode(&[0x01, 0x80, 0x01], VERSION).unwrap();
+        assert_eq!(
+            value,
+            DataTypes::Array(vec![
+                DataTypes::VarInt(VarInt::new(1)),
+                DataTypes::VarInt(VarInt::new(128))
+            ])
+        );
+        assert_eq!(len, 3);
+        assert_eq!(
+            descriptor.encode(&value, VERSION).unwrap(),
+            [0x01, 0x80, 0x01]
+        );
+    }
+
+    #[test]
+    fn count_kinds() {
+        let descriptor = TypeDescriptor::ByteArray(CountKind::Short);
+        let value = DataTypes::ByteArray(vec![0xCA, 0xFE]);
+        assert_eq!(
+            descriptor.encode(&value, VERSION).unwrap(),
+            [0x00, 0x02, 0xCA, 0xFE]
+        );
+        assert_eq!(
+            descriptor
+                .decode(&[0x00, 0x02, 0xCA, 0xFE], VERSION)
+                .unwrap(),
+            (value, 4)
+        );
+
+        assert_eq!(
+            TypeDescriptor::ByteArray(CountKind::Byte).decode(&[0xFF], VERSION),
+            Err(Errors::GenericError(GenericError::NegativeLength))
+        );
+        assert_eq!(
+            TypeDescriptor::ByteArray(CountKind::UnsignedByte)
+                .encode(&DataTypes::ByteArray(vec![0; 256]), VERSION),
+            Err(Errors::GenericError(GenericError::TooLong))
+        );
+    }
+
+    #[test]
+    fn empty_elements() {
+        let descriptor = TypeDescriptor::Array(CountKind::VarInt, Box::new(TypeDescriptor::Void));
+        assert_eq!(
+            descriptor.decode(&[0xFF, 0xFF, 0xFF, 0xFF, 0x07], VERSION),
+            Err(Errors::DescriptorError(DescriptorError::EmptyElement))
+        );
+        assert_eq!(
+            descriptor.decode(&[0x00], VERSION).unwrap(),
+            (DataTypes::Array(vec![]), 1)
+        );
+
+        assert_eq!(
+            TypeDescriptor::InferredArray(Box::new(TypeDescriptor::Void)).decode(&[0x00], VERSION),
+            Err(Errors::DescriptorError(DescriptorError::EmptyElement))
+        );
+    }
+
+    #[test]
+    fn mismatch() {
+        assert_eq!(
+            TypeDescriptor::Int.encode(&DataTypes::Long(0), VERSION),
+            Err(Errors::DescriptorError(DescriptorError::TypeMismatch))
+        );
+
+        let renamed = DataTypes::Container(vec![("other".into(), DataTypes::Void)]);
+        assert_eq!(
+            TypeDescriptor::Container(vec![("field".into(), TypeDescriptor::Void)])
+                .encode(&renamed, VERSION),
+            Err(Errors::DescriptorError(DescriptorError::TypeMismatch))
+        );
+
+        assert_eq!(
+            hand().encode(
+                &DataTypes::Enum {
+                    variant: "foot".into(),
+                    value: Box::new(DataTypes::Void)
+                },
+                VERSION
+            ),
+            Err(Errors::GenericError(GenericError::UnknownVariant))
+        );
+    }
+
+    #[test]
+    fn truncated() {
+        assert_eq!(
+            packet().decode(&[0; 10], VERSION),
+            Err(Errors::GenericError(GenericError::UnexpectedEnd))
+        );
+    }
+}
@@ -1,5 +1,6 @@
+#[derive(Debug, Clone, PartialEq)]
 pub struct EntityMetadata;
-use crate::data_types::{Chat, Position, Slot, String, VarInt, VarLong};
+use crate::data_types::{Chat, Position, Rotation, Slot, String, VarInt, VarLong};
 
 #[allow(clippy::module_name_repetitions)]
 pub enum EntityMetadataType {
@@ -12,7 +13,7 @@ pub enum EntityMetadataType {
     OptChat(Option<Chat>),
     Slot(Slot),
     Boolean(bool),
-    Rotation,
+    Rotation(Rotation),
     Position(Position),
     OptPosition(Option<Position>),
     Direction,
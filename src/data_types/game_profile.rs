@@ -0,0 +1,373 @@
+use std::str::FromStr;
+
+use md5::{Digest, Md5};
+use rsa::RsaPublicKey;
+use thiserror::Error;
+use uuid::{Builder, Uuid};
+
+use super::{
+    property::Property, textures::Textures, BoundedString, Codec, DataResult, Errors, GenericError,
+    SerDe, Username, VarInt,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Error)]
+pub enum GameProfileError {
+    #[error("Not enough bytes to decode the game profile")]
+    UnexpectedEnd,
+    #[error("A game profile can't have more than 16 properties")]
+    TooManyProperties,
+    #[error("A username must be 3 to 16 letters, digits or underscores")]
+    InvalidUsername,
+}
+
+/// Checks that `name` is a username vanilla accepts: 3 to 16 characters among `A-Z`, `a-z`, `0-9` and `_`.
+///
+/// ## Errors
+///
+/// Returns [`GameProfileError::InvalidUsername`] otherwise.
+pub fn validate_username(name: &str) -> DataResult<()> {
+    if !(3..=16).contains(&name.len())
+        || !name
+            .bytes()
+            .all(|byte| byte.is_ascii_alphanumeric() || byte == b'_')
+    {
+        return Err(GameProfileError::InvalidUsername)?;
+    }
+
+    Ok(())
+}
+
+/// Returns the UUID an offline mode server gives to `name`.
+///
+/// Like Java's `UUID.nameUUIDFromBytes`, this is the MD5 hash of `OfflinePlayer:<name>`
+/// with the version set to 3, without the namespace of RFC 4122 v3 UUIDs.
+#[must_use]
+pub fn offline_uuid(name: &str) -> Uuid {
+    let hash = Md5::digest(format!("OfflinePlayer:{name}"));
+
+    Builder::from_md5_bytes(hash.into()).into_uuid()
+}
+
+/// A player's identity, as sent by [`LoginSuccess`](crate::packet::ServerPacket::LoginSuccess)
+/// and the player info packets.
+///
+/// Encoded as the UUID (16 bytes), the name and a [`VarInt`] prefixed list of [`Property`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GameProfile {
+    pub uuid: Uuid,
+    pub name: Username,
+    pub properties: Vec<Property>,
+}
+
+impl GameProfile {
+    /// Maximum amount of properties a profile can have.
+    pub const MAX_PROPERTIES: usize = 16;
+
+    /// Creates a new [`GameProfile`] without properties.
+    #[must_use]
+    pub fn new(uuid: Uuid, name: Username) -> Self {
+        GameProfile {
+            uuid,
+            name,
+            properties: Vec::new(),
+        }
+    }
+
+    /// Creates the profile of `name` on an offline mode server, with its [`offline_uuid`] and without properties.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`GameProfileError::InvalidUsername`] if `name` is not valid, see [`validate_username`].
+    pub fn offline(name: &str) -> DataResult<Self> {
+        validate_username(name)?;
+
+        Ok(GameProfile::new(
+            offline_uuid(name),
+            Username::from_str(name)?,
+        ))
+    }
+
+    /// Returns the first property called `name`.
+    #[must_use]
+    pub fn property(&self, name: &str) -> Option<&Property> {
+        self.properties
+            .iter()
+            .find(|property| property.name.as_str() == name)
+    }
+
+    /// Checks the signature of every property with the given Yggdrasil public key, see [`Property::verify`].
+    ///
+    /// ## Errors
+    ///
+    /// Returns the [`SignatureError`](super::property::SignatureError) of the first property that is unsigned or not valid.
+    pub fn verify(&self, key: &RsaPublicKey) -> DataResult<()> {
+        self.properties
+            .iter()
+            .try_for_each(|property| property.verify(key))
+    }
+
+    /// Decodes the [`Property::TEXTURES`] property, if any.
+    ///
+    /// The signature is not checked.
+    ///
+    /// ## Errors
+    ///
+    /// Returns a [`TexturesError`](super::textures::TexturesError) if the value is not valid base64 encoded JSON.
+    pub fn textures(&self) -> DataResult<Option<Textures>> {
+        self.property(Property::TEXTURES)
+            .map(|property| Textures::decode(&property.value))
+            .transpose()
+    }
+
+    /// Replaces the [`Property::TEXTURES`] property with `textures`, unsigned.
+    ///
+    /// ## Errors
+    ///
+    /// Returns a [`TexturesError`](super::textures::TexturesError) if the textures can't be serialized.
+    pub fn set_textures(&mut self, textures: &Textures) -> DataResult<()> {
+        let value = BoundedString::new(textures.encode()?)?;
+
+        self.properties
+            .retain(|property| property.name.as_str() != Property::TEXTURES);
+        self.properties.push(Property {
+            name: BoundedString::new(Property::TEXTURES.to_owned())?,
+            value,
+            signature: None,
+        });
+
+        Ok(())
+    }
+}
+
+impl<'a> SerDe<'a> for GameProfile {
+    type Input = &'a [u8];
+    type Serialized = DataResult<Vec<u8>>;
+    type Deserialized = DataResult<(Self, usize)>;
+
+    /// Encodes a [`GameProfile`] into bytes.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`GameProfileError::TooManyProperties`] if there are more than 16 properties,
+    /// or an error if there is a problem encoding any property.
+    fn encode(&self) -> Self::Serialized {
+        if self.properties.len() > Self::MAX_PROPERTIES {
+            return Err(GameProfileError::TooManyProperties)?;
+        }
+
+        let mut bytes = self.uuid.as_bytes().to_vec();
+        bytes.append(&mut self.name.encode()?);
+        #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+        bytes.append(&mut VarInt::new(self.properties.len() as i32).encode()?);
+
+        for property in &self.properties {
+            bytes.append(&mut property.encode()?);
+        }
+
+        Ok(bytes)
+    }
+
+    /// Decodes a [`GameProfile`] from the start of `data`.
+    ///
+    /// Returns the profile and the amount of bytes read.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`GameProfileError::UnexpectedEnd`] if there are not enough bytes,
+    /// [`GameProfileError::TooManyProperties`] if there are more than 16 properties,
+    /// or an error if the name or any property is not valid.
+    fn decode(data: Self::Input) -> Self::Deserialized {
+        let uuid = data
+            .get(..16)
+            .and_then(|bytes| Uuid::from_slice(bytes).ok())
+            .ok_or(GameProfileError::UnexpectedEnd)?;
+        let mut position = 16;
+
+        let (name, len) = Username::decode(&data[position..])?;
+        position += len;
+
+        let (count, len) = match VarInt::read(&data[position..]) {
+            Err(Errors::GenericError(GenericError::UnexpectedEnd)) => {
+                return Err(GameProfileError::UnexpectedEnd)?
+            }
+            result => result?,
+        };
+        position += len;
+
+        let count = usize::try_from(count.value())
+            .ok()
+            .filter(|count| *count <= Self::MAX_PROPERTIES)
+            .ok_or(GameProfileError::TooManyProperties)?;
+
+        let mut properties = Vec::with_capacity(count);
+        for _ in 0..count {
+            let (property, len) = Property::decode(&data[position..])?;
+            position += len;
+            properties.push(property);
+        }
+
+        Ok((
+            GameProfile {
+                uuid,
+                name,
+                properties,
+            },
+            position,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use rsa::RsaPublicKey;
+    use uuid::Uuid;
+
+    use crate::{
+        data_types::{
+            property::{Property, SignatureError},
+            textures::{SkinModel, Textures},
+            Errors, SerDe, Username,
+        },
+        test_fixtures::KEY,
+    };
+
+    use super::{offline_uuid, validate_username, GameProfile, GameProfileError};
+
+    fn notch() -> GameProfile {
+        GameProfile::new(
+            Uuid::from_str("069a79f4-44e9-4726-a5be-fca90e38aaf5").unwrap(),
+            Username::from_str("Notch").unwrap(),
+        )
+    }
+
+    #[test]
+    fn encode_decode() {
+        let mut profile = notch();
+        profile
+            .properties
+            .push(Property::new("textures", "e30=", Some("c2ln")).unwrap());
+
+        let bytes = profile.encode().unwrap();
+        assert_eq!(&bytes[..16], profile.uuid.as_bytes());
+        assert_eq!(&bytes[16..22], b"\x05Notch");
+        assert_eq!(bytes[22], 1);
+        assert_eq!(GameProfile::decode(&bytes).unwrap(), (profile, bytes.len()));
+    }
+
+    #[test]
+    fn decode_errors() {
+        assert_eq!(
+            GameProfile::decode(&[0; 15]),
+            Err(Errors::GameProfileError(GameProfileError::UnexpectedEnd))
+        );
+
+        // Ends right after the name
+        let mut bytes = notch().encode().unwrap();
+        bytes.pop();
+        assert_eq!(
+            GameProfile::decode(&bytes),
+            Err(Errors::GameProfileError(GameProfileError::UnexpectedEnd))
+        );
+
+        let mut bytes = notch().encode().unwrap();
+        *bytes.last_mut().unwrap() = 17;
+        assert_eq!(
+            GameProfile::decode(&bytes),
+            Err(Errors::GameProfileError(
+                GameProfileError::TooManyProperties
+            ))
+        );
+    }
+
+    #[test]
+    fn too_many_properties() {
+        let mut profile = notch();
+        profile.properties = vec![Property::new("a", "b", None).unwrap(); 17];
+        assert_eq!(
+            profile.encode(),
+            Err(Errors::GameProfileError(
+                GameProfileError::TooManyProperties
+            ))
+        );
+    }
+
+    #[test]
+    fn textures() {
+        let mut profile = notch();
+        assert_eq!(profile.textures(), Ok(None));
+
+        let mut textures = Textures::new(1_700_000_000_000, profile.uuid, "Notch");
+        textures.set_skin("http://textures.minecraft.net/texture/abc", SkinModel::Slim);
+        profile.set_textures(&textures).unwrap();
+        profile.set_textures(&textures).unwrap();
+
+        assert_eq!(profile.properties.len(), 1);
+        assert_eq!(profile.textures(), Ok(Some(textures)));
+    }
+
+    #[test]
+    fn verify() {
+        let public = RsaPublicKey::from(KEY.private_key());
+
+        let mut profile = notch();
+        assert_eq!(profile.verify(&public), Ok(()));
+
+        profile
+            .properties
+            .push(Property::new("textures", "e30=", None).unwrap());
+        assert_eq!(
+            profile.verify(&public),
+            Err(Errors::SignatureError(SignatureError::Missing))
+        );
+
+        profile.properties[0].sign(KEY.private_key()).unwrap();
+        assert_eq!(profile.verify(&public), Ok(()));
+    }
+
+    #[test]
+    fn offline() {
+        assert_eq!(
+            offline_uuid("Notch"),
+            Uuid::from_str("b50ad385-829d-3141-a216-7e7d7539ba7f").unwrap()
+        );
+        assert_eq!(
+            offline_uuid("jeb_"),
+            Uuid::from_str("a762f560-4fce-3236-812a-b80efff0b62b").unwrap()
+        );
+        assert_eq!(offline_uuid("Notch").get_version_num(), 3);
+
+        let profile = GameProfile::offline("Notch").unwrap();
+        assert_eq!(profile.uuid, offline_uuid("Notch"));
+        assert_eq!(profile.name, Username::from_str("Notch").unwrap());
+        assert!(profile.properties.is_empty());
+    }
+
+    #[test]
+    fn usernames() {
+        for name in ["Notch", "jeb_", "abc", "A_1", &"a".repeat(16)] {
+            assert_eq!(validate_username(name), Ok(()), "{name}");
+        }
+
+        for name in [
+            "",
+            "ab",
+            &"a".repeat(17),
+            "Not ch",
+            "Notch!",
+            "Nötch",
+            "🚀🚀🚀",
+        ] {
+            assert_eq!(
+                validate_username(name),
+                Err(Errors::GameProfileError(GameProfileError::InvalidUsername)),
+                "{name}"
+            );
+        }
+        assert_eq!(
+            GameProfile::offline("a-b"),
+            Err(Errors::GameProfileError(GameProfileError::InvalidUsername))
+        );
+    }
+}
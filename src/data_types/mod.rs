@@ -1,8 +1,16 @@
+pub mod client_information;
+pub mod descriptor;
 pub mod entity_metadata;
+pub mod game_profile;
 pub mod handshake_next_state;
 pub mod primitives;
 pub mod property;
+pub mod registries;
+pub mod registry_data;
 pub mod registry_data_entry;
+pub mod status;
+pub mod tags;
+pub mod textures;
 
 pub use entity_metadata::*;
 pub use primitives::*;
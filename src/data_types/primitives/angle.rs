@@ -1,6 +1,74 @@
+use std::f32::consts::TAU;
+
+use crate::data_types::SerDe;
+
 /// A rotation angle in steps of 1/256 of a full turn
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Angle(u8);
 
+impl Angle {
+    /// Creates an [`Angle`] from degrees, wrapping around a full turn.
+    ///
+    /// Like the Notchian implementation, the value is rounded down to the previous step,
+    /// so `-1.0` becomes `255` and `360.0` becomes `0`.
+    #[must_use]
+    pub fn from_degrees(degrees: f32) -> Self {
+        Self::from_turns(degrees / 360.0)
+    }
+
+    /// Creates an [`Angle`] from radians, wrapping around a full turn.
+    ///
+    /// See [`Angle::from_degrees`].
+    #[must_use]
+    pub fn from_radians(radians: f32) -> Self {
+        Self::from_turns(radians / TAU)
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn from_turns(turns: f32) -> Self {
+        if !turns.is_finite() {
+            return Angle(0);
+        }
+
+        // `rem_euclid` keeps the value in [0, 256], the cast saturates and the mask wraps 256 back to 0
+        Angle(((turns * 256.0).floor().rem_euclid(256.0) as u16 & 0xFF) as u8)
+    }
+
+    /// Returns the angle in degrees, in the range `[0, 360)`.
+    #[must_use]
+    pub fn to_degrees(self) -> f32 {
+        f32::from(self.0) * 360.0 / 256.0
+    }
+
+    /// Returns the angle in radians, in the range `[0, 2π)`.
+    #[must_use]
+    pub fn to_radians(self) -> f32 {
+        f32::from(self.0) * TAU / 256.0
+    }
+
+    /// Returns the angle in degrees, in the range `[-180, 180)`, as the Notchian client reads it.
+    #[must_use]
+    pub fn to_signed_degrees(self) -> f32 {
+        f32::from(i8::from_be_bytes([self.0])) * 360.0 / 256.0
+    }
+}
+
+impl SerDe<'_> for Angle {
+    type Input = u8;
+    type Serialized = u8;
+    type Deserialized = Self;
+
+    /// Encodes an [`Angle`] into a single byte.
+    fn encode(&self) -> Self::Serialized {
+        self.0
+    }
+
+    /// Decodes an [`Angle`] from a single byte. Every value is valid.
+    fn decode(data: Self::Input) -> Self::Deserialized {
+        Angle(data)
+    }
+}
+
 impl From<u8> for Angle {
     fn from(value: u8) -> Self {
         Angle(value)
@@ -12,3 +80,52 @@ impl From<Angle> for u8 {
         value.0
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::f32::consts::PI;
+
+    use super::Angle;
+
+    #[test]
+    fn from_degrees() {
+        assert_eq!(Angle::from_degrees(0.0), Angle(0));
+        assert_eq!(Angle::from_degrees(90.0), Angle(64));
+        assert_eq!(Angle::from_degrees(180.0), Angle(128));
+        assert_eq!(Angle::from_degrees(359.0), Angle(255));
+        assert_eq!(Angle::from_degrees(360.0), Angle(0));
+        assert_eq!(Angle::from_degrees(-90.0), Angle(192));
+        assert_eq!(Angle::from_degrees(-1.0), Angle(255));
+        assert_eq!(Angle::from_degrees(720.0 + 45.0), Angle(32));
+        assert_eq!(Angle::from_degrees(f32::NAN), Angle(0));
+    }
+
+    #[test]
+    fn from_radians() {
+        assert_eq!(Angle::from_radians(PI), Angle(128));
+        assert_eq!(Angle::from_radians(-PI / 2.0), Angle(192));
+        assert_eq!(Angle::from_radians(PI * 4.0), Angle(0));
+    }
+
+    #[test]
+    fn to_degrees() {
+        assert!((Angle(64).to_degrees() - 90.0).abs() < f32::EPSILON);
+        assert!((Angle(192).to_degrees() - 270.0).abs() < f32::EPSILON);
+        assert!((Angle(192).to_signed_degrees() + 90.0).abs() < f32::EPSILON);
+        assert!((Angle(128).to_signed_degrees() + 180.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn to_radians() {
+        assert!((Angle(128).to_radians() - PI).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn round_trip() {
+        for step in 0..=u8::MAX {
+            let angle = Angle(step);
+            assert_eq!(Angle::from_degrees(angle.to_degrees()), angle);
+            assert_eq!(Angle::from_degrees(angle.to_signed_degrees()), angle);
+        }
+    }
+}
@@ -9,6 +9,10 @@ use std::{
     },
 };
 
+use thiserror::Error;
+
+use crate::data_types::{Codec, DataResult, Errors, GenericError, SerDe, VarInt};
+
 /// **`IndexRange`** is implemented by Rust's built-in range types, produced
 /// by range syntax like `..`, `a..`, `..b`, `c..d`, `..=e` or `f..=g`.
 pub trait IndexRange<T = usize> {
@@ -1045,14 +1049,207 @@ impl PartialEq for BitSet {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Error)]
+pub enum BitSetError {
+    #[error("Not enough bytes to decode the bit set")]
+    UnexpectedEnd,
+    #[error("Negative bit set length")]
+    NegativeLength,
+    #[error("Bit set is larger than its fixed size")]
+    TooLarge,
+}
+
+impl BitSet {
+    /// Returns the bits packed into longs, the same way as Java's `BitSet.toLongArray()`.
+    ///
+    /// Bit `n` is stored in long `n / 64`, at bit `n % 64`. Trailing zero longs are omitted.
+    ///
+    /// ---
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use crate::redstone_signal::data_types::BitSet;
+    ///
+    /// let mut bitset = BitSet::new();
+    /// bitset.insert(0);
+    /// bitset.insert(65);
+    /// assert_eq!(bitset.to_longs(), vec![1, 2]);
+    /// ```
+    #[must_use]
+    pub fn to_longs(&self) -> Vec<i64> {
+        let mut longs = vec![0; self.capacity().div_ceil(64)];
+
+        for bit in (0..self.capacity()).filter(|bit| self.get(*bit)) {
+            longs[bit / 64] |= 1 << (bit % 64);
+        }
+
+        while longs.last() == Some(&0) {
+            longs.pop();
+        }
+
+        longs
+    }
+
+    /// Creates a set from longs, the same way as Java's `BitSet.valueOf(long[])`.
+    ///
+    /// See [`BitSet::to_longs`].
+    #[must_use]
+    pub fn from_longs(longs: &[i64]) -> Self {
+        let mut bitset = BitSet::with_capacity(longs.len() * 64);
+
+        for (index, long) in longs.iter().enumerate() {
+            for bit in (0..64).filter(|bit| (long >> bit) & 1 == 1) {
+                bitset.insert(index * 64 + bit);
+            }
+        }
+
+        bitset
+    }
+
+    /// Returns the first `nbits` bits packed into `ceil(nbits / 8)` bytes,
+    /// the same way as Java's `BitSet.toByteArray()`, but padded to the full size.
+    fn to_fixed_bytes(&self, nbits: usize) -> Vec<u8> {
+        let mut bytes = vec![0; nbits.div_ceil(8)];
+
+        for bit in (0..nbits).filter(|bit| self.get(*bit)) {
+            bytes[bit / 8] |= 1 << (bit % 8);
+        }
+
+        bytes
+    }
+
+    /// Whether any bit at or above `nbits` is set.
+    fn has_bits_from(&self, nbits: usize) -> bool {
+        (nbits..self.capacity()).any(|bit| self.get(bit))
+    }
+}
+
+impl<'a> SerDe<'a> for BitSet {
+    type Input = &'a [u8];
+    type Serialized = DataResult<Vec<u8>>;
+    type Deserialized = DataResult<(Self, usize)>;
+
+    /// Encodes a [`BitSet`] as a [`VarInt`] amount of longs, followed by the longs themselves.
+    ///
+    /// See [`BitSet::to_longs`].
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`VarIntError::EncodeOverflow`](super::VarIntError::EncodeOverflow) if there is a problem encoding the [`VarInt`].
+    fn encode(&self) -> Self::Serialized {
+        let longs = self.to_longs();
+
+        let len = longs.len().try_into().map_err(|_| BitSetError::TooLarge)?;
+        let mut bytes = VarInt::new(len).encode()?;
+
+        for long in longs {
+            bytes.extend_from_slice(&long.to_be_bytes());
+        }
+
+        Ok(bytes)
+    }
+
+    /// Decodes a [`BitSet`] from a [`VarInt`] amount of longs, followed by the longs themselves.
+    ///
+    /// Returns the set and the amount of bytes read.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`BitSetError::NegativeLength`] if the amount of longs is negative,
+    /// [`BitSetError::UnexpectedEnd`] if there are not enough bytes
+    /// or [`VarIntError::DecodeOverflow`](super::VarIntError::DecodeOverflow) if the [`VarInt`] is too big.
+    fn decode(data: Self::Input) -> Self::Deserialized {
+        let (len, mut position) = match VarInt::read(data) {
+            Err(Errors::GenericError(GenericError::UnexpectedEnd)) => {
+                return Err(BitSetError::UnexpectedEnd)?
+            }
+            result => result?,
+        };
+
+        let len = usize::try_from(len.value()).map_err(|_| BitSetError::NegativeLength)?;
+
+        if len > (data.len() - position) / 8 {
+            return Err(BitSetError::UnexpectedEnd)?;
+        }
+
+        let mut longs = Vec::with_capacity(len);
+        for _ in 0..len {
+            let mut long = [0; 8];
+            long.copy_from_slice(&data[position..position + 8]);
+            longs.push(i64::from_be_bytes(long));
+            position += 8;
+        }
+
+        Ok((BitSet::from_longs(&longs), position))
+    }
+}
+
+/// A [`BitSet`] of exactly `N` bits, sent as `ceil(N / 8)` bytes without a length prefix.
+///
+/// Bit `n` is stored in byte `n / 8`, at bit `n % 8`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct FixedBitSet<const N: usize>(pub BitSet);
+
+impl<const N: usize> FixedBitSet<N> {
+    /// Amount of bytes used to encode the set.
+    pub const BYTES: usize = N.div_ceil(8);
+}
+
+impl<'a, const N: usize> SerDe<'a> for FixedBitSet<N> {
+    type Input = &'a [u8];
+    type Serialized = DataResult<Vec<u8>>;
+    type Deserialized = DataResult<(Self, usize)>;
+
+    /// Encodes a [`FixedBitSet`] into `ceil(N / 8)` bytes.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`BitSetError::TooLarge`] if a bit at or above `N` is set.
+    fn encode(&self) -> Self::Serialized {
+        if self.0.has_bits_from(N) {
+            return Err(BitSetError::TooLarge)?;
+        }
+
+        Ok(self.0.to_fixed_bytes(N))
+    }
+
+    /// Decodes a [`FixedBitSet`] from `ceil(N / 8)` bytes.
+    ///
+    /// Returns the set and the amount of bytes read.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`BitSetError::UnexpectedEnd`] if there are not enough bytes.
+    fn decode(data: Self::Input) -> Self::Deserialized {
+        let bytes = data.get(..Self::BYTES).ok_or(BitSetError::UnexpectedEnd)?;
+
+        let mut bitset = BitSet::with_capacity(N);
+        for bit in (0..N).filter(|bit| (bytes[bit / 8] >> (bit % 8)) & 1 == 1) {
+            bitset.insert(bit);
+        }
+
+        Ok((FixedBitSet(bitset), Self::BYTES))
+    }
+}
+
+impl<const N: usize> From<BitSet> for FixedBitSet<N> {
+    fn from(value: BitSet) -> Self {
+        FixedBitSet(value)
+    }
+}
+
 #[cfg(test)]
+#[allow(clippy::decimal_bitwise_operands)]
 mod test {
-    use super::{BitSet, Word};
+    use crate::data_types::{Errors, SerDe};
+
+    use super::{BitSet, BitSetError, FixedBitSet, Word};
     #[test]
     fn initial_state() {
         let bitset = BitSet::default();
         assert_eq!(bitset.len(), 0);
-        assert_eq!(bitset.data, vec![]);
+        assert_eq!(bitset.data, Vec::<u8>::new());
     }
 
     #[test]
@@ -1397,4 +1594,136 @@ mod test {
 
         assert_eq!(result_bitset, BitSet::from(2645 & !4568));
     }
+
+    // Sky light mask with sections 0, 2 and 65 lit, as sent by a vanilla server:
+    // `BitSet.valueOf(new long[] { 0b101, 0b10 })`
+    static LIGHT_MASK_BYTES: [u8; 17] = [
+        0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x02,
+    ];
+
+    fn light_mask() -> BitSet {
+        let mut bitset = BitSet::new();
+        bitset.insert(0);
+        bitset.insert(2);
+        bitset.insert(65);
+        bitset
+    }
+
+    #[test]
+    fn to_longs() {
+        assert_eq!(light_mask().to_longs(), vec![0b101, 0b10]);
+        assert_eq!(BitSet::new().to_longs(), Vec::<i64>::new());
+        assert_eq!(BitSet::with_capacity(200).to_longs(), Vec::<i64>::new());
+
+        let mut bitset = BitSet::new();
+        bitset.insert(63);
+        assert_eq!(bitset.to_longs(), vec![i64::MIN]);
+    }
+
+    #[test]
+    fn from_longs() {
+        assert_eq!(BitSet::from_longs(&[0b101, 0b10]), light_mask());
+        assert_eq!(BitSet::from_longs(&[-1]), BitSet::from(u64::MAX));
+    }
+
+    #[test]
+    fn encode_longs() {
+        assert_eq!(light_mask().encode().unwrap(), LIGHT_MASK_BYTES.to_vec());
+        assert_eq!(BitSet::new().encode().unwrap(), vec![0x00]);
+    }
+
+    #[test]
+    fn decode_longs() {
+        assert_eq!(
+            BitSet::decode(&LIGHT_MASK_BYTES).unwrap(),
+            (light_mask(), LIGHT_MASK_BYTES.len())
+        );
+        assert_eq!(BitSet::decode(&[0x00, 0xFF]).unwrap(), (BitSet::new(), 1));
+        assert_eq!(
+            BitSet::decode(&[]),
+            Err(Errors::BitSetError(BitSetError::UnexpectedEnd))
+        );
+        assert_eq!(
+            BitSet::decode(&[0x80]),
+            Err(Errors::BitSetError(BitSetError::UnexpectedEnd))
+        );
+        assert_eq!(
+            BitSet::decode(&LIGHT_MASK_BYTES[..16]),
+            Err(Errors::BitSetError(BitSetError::UnexpectedEnd))
+        );
+        assert_eq!(
+            BitSet::decode(&[0xFF, 0xFF, 0xFF, 0xFF, 0x0F]),
+            Err(Errors::BitSetError(BitSetError::NegativeLength))
+        );
+        assert_eq!(
+            BitSet::decode(&[0xFF, 0xFF, 0xFF, 0xFF, 0x07]),
+            Err(Errors::BitSetError(BitSetError::UnexpectedEnd))
+        );
+    }
+
+    // Chat acknowledgements for messages 0, 1 and 19, as sent by a vanilla client:
+    // `FriendlyByteBuf.writeFixedBitSet(bitSet, 20)`
+    static ACKNOWLEDGED_BYTES: [u8; 3] = [0x03, 0x00, 0x08];
+
+    fn acknowledged() -> FixedBitSet<20> {
+        let mut bitset = BitSet::new();
+        bitset.insert(0..2);
+        bitset.insert(19);
+        FixedBitSet(bitset)
+    }
+
+    #[test]
+    fn encode_fixed() {
+        assert_eq!(
+            acknowledged().encode().unwrap(),
+            ACKNOWLEDGED_BYTES.to_vec()
+        );
+        assert_eq!(
+            FixedBitSet::<20>::default().encode().unwrap(),
+            vec![0x00; 3]
+        );
+        assert_eq!(
+            FixedBitSet::<0>::default().encode().unwrap(),
+            Vec::<u8>::new()
+        );
+
+        let mut bitset = BitSet::new();
+        bitset.insert(20);
+        assert_eq!(
+            FixedBitSet::<20>(bitset).encode(),
+            Err(Errors::BitSetError(BitSetError::TooLarge))
+        );
+    }
+
+    #[test]
+    fn decode_fixed() {
+        assert_eq!(
+            FixedBitSet::<20>::decode(&[0x03, 0x00, 0x08, 0xFF]).unwrap(),
+            (acknowledged(), 3)
+        );
+        assert_eq!(
+            FixedBitSet::<20>::decode(&[0x03, 0x00]),
+            Err(Errors::BitSetError(BitSetError::UnexpectedEnd))
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut bitset = BitSet::new();
+        bitset.insert(3..70);
+        bitset.insert(127);
+        bitset.insert(300);
+
+        let bytes = bitset.encode().unwrap();
+        assert_eq!(
+            BitSet::decode(&bytes).unwrap(),
+            (bitset.clone(), bytes.len())
+        );
+
+        let fixed = FixedBitSet::<301>(bitset);
+        let bytes = fixed.encode().unwrap();
+        assert_eq!(bytes.len(), 38);
+        assert_eq!(FixedBitSet::<301>::decode(&bytes).unwrap(), (fixed, 38));
+    }
 }
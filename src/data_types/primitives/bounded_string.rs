@@ -0,0 +1,244 @@
+use std::{fmt::Display, ops::Deref, str::FromStr};
+
+use crate::data_types::{DataResult, Errors, SerDe, StringError, VarInt};
+
+use super::string::{check_bounds, read_size, utf16_len};
+
+/// A protocol string whose maximum length `N` is part of its type.
+///
+/// Like [`String`](super::String), the length is counted in UTF-16 code units and the encoding is limited to `N × 3` bytes,
+/// but the limit is enforced on both encode and decode, so a packet field can't hold or accept a longer string.
+///
+/// `N` can't be greater than 32767.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BoundedString<const N: usize>(std::string::String);
+
+/// A player's username, up to 16 characters.
+pub type Username = BoundedString<16>;
+
+/// The server address sent in the handshake, up to 255 characters.
+pub type ServerAddress = BoundedString<255>;
+
+impl<const N: usize> BoundedString<N> {
+    /// Maximum length of the string, in UTF-16 code units.
+    pub const MAX_LENGTH: usize = {
+        assert!(N <= 32767, "protocol strings can't be longer than 32767");
+        N
+    };
+
+    /// Creates a new [`BoundedString`].
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`StringError::OutOfBoundsEncoding`] if the UTF-8 encoding of `string` is greater than `N × 3` bytes,
+    /// or [`StringError::OutOfBoundsLength`] if `string` is greater than `N` UTF-16 code units.
+    pub fn new(string: std::string::String) -> DataResult<Self> {
+        check_bounds(&string, Self::MAX_LENGTH)?;
+
+        Ok(BoundedString(string))
+    }
+
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    #[must_use]
+    pub fn into_inner(self) -> std::string::String {
+        self.0
+    }
+}
+
+impl<'a, const N: usize> SerDe<'a> for BoundedString<N> {
+    type Input = &'a [u8];
+    type Serialized = DataResult<Vec<u8>>;
+    type Deserialized = DataResult<(Self, usize)>;
+
+    /// Encodes a [`BoundedString`] into bytes.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`VarIntError::EncodeOverflow`](super::VarIntError::EncodeOverflow) if there is a problem encoding the [`VarInt`].
+    fn encode(&self) -> Self::Serialized {
+        let len = self
+            .0
+            .len()
+            .try_into()
+            .map_err(|_| StringError::OutOfBoundsLength)?;
+        let mut bytes = VarInt::new(len).encode()?;
+        bytes.extend_from_slice(self.0.as_bytes());
+        Ok(bytes)
+    }
+
+    /// Decodes a [`BoundedString`] from the start of `data`, ignoring anything after it.
+    ///
+    /// Returns the string and the amount of bytes read.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`StringError::OutOfBoundsEncoding`] if the length prefix is greater than `N × 3` bytes,
+    /// before reading the string itself,
+    /// [`StringError::InvalidLength`] if the length is negative or there are not enough bytes,
+    /// [`StringError::InvalidUTF8`] if the bytes are not valid UTF-8,
+    /// or [`StringError::OutOfBoundsLength`] if the string is greater than `N` UTF-16 code units.
+    fn decode(data: Self::Input) -> Self::Deserialized {
+        let (size, position) = read_size(data)?;
+
+        let len = usize::try_from(size.value()).map_err(|_| StringError::InvalidLength)?;
+
+        if len > Self::MAX_LENGTH * 3 {
+            return Err(StringError::OutOfBoundsEncoding)?;
+        }
+
+        let bytes = data
+            .get(position..position + len)
+            .ok_or(StringError::InvalidLength)?;
+
+        let string = std::str::from_utf8(bytes).map_err(|_| StringError::InvalidUTF8)?;
+
+        if utf16_len(string) > Self::MAX_LENGTH {
+            return Err(StringError::OutOfBoundsLength)?;
+        }
+
+        Ok((BoundedString(string.to_owned()), position + len))
+    }
+}
+
+impl<const N: usize> FromStr for BoundedString<N> {
+    type Err = Errors;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(s.to_owned())
+    }
+}
+
+impl<const N: usize> TryFrom<std::string::String> for BoundedString<N> {
+    type Error = Errors;
+
+    fn try_from(value: std::string::String) -> Result<Self, Self::Error> {
+        Self::new(value)
+    }
+}
+
+impl<const N: usize> From<BoundedString<N>> for std::string::String {
+    fn from(value: BoundedString<N>) -> Self {
+        value.0
+    }
+}
+
+impl<const N: usize> Deref for BoundedString<N> {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<const N: usize> Display for BoundedString<N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use crate::data_types::{Errors, SerDe, StringError};
+
+    use super::{BoundedString, ServerAddress, Username};
+
+    #[test]
+    fn new() {
+        assert!(Username::from_str("Notch").is_ok());
+        assert!(Username::from_str(&"a".repeat(16)).is_ok());
+        assert!(Username::from_str(&"🚀".repeat(8)).is_ok());
+        assert_eq!(
+            Username::from_str(&"a".repeat(17)),
+            Err(Errors::StringError(StringError::OutOfBoundsLength))
+        );
+        assert_eq!(
+            Username::from_str(&"🚀".repeat(9)),
+            Err(Errors::StringError(StringError::OutOfBoundsLength))
+        );
+        assert!(ServerAddress::from_str(&"a".repeat(255)).is_ok());
+        assert!(ServerAddress::from_str(&"a".repeat(256)).is_err());
+    }
+
+    #[test]
+    fn encode() {
+        assert_eq!(
+            Username::from_str("Notch").unwrap().encode().unwrap(),
+            [vec![5], b"Notch".to_vec()].concat()
+        );
+    }
+
+    #[test]
+    fn decode() {
+        let bytes = [vec![5], b"Notch".to_vec(), vec![0xFF]].concat();
+        assert_eq!(
+            Username::decode(&bytes).unwrap(),
+            (Username::from_str("Notch").unwrap(), 6)
+        );
+    }
+
+    #[test]
+    fn decode_too_long() {
+        let bytes = [vec![17], b"a".repeat(17)].concat();
+        assert_eq!(
+            Username::decode(&bytes),
+            Err(Errors::StringError(StringError::OutOfBoundsLength))
+        );
+
+        // 9 emoji are 36 bytes, within 16 × 3, but 18 UTF-16 code units
+        let emoji = "🚀".repeat(9);
+        let bytes = [vec![36], emoji.as_bytes().to_vec()].concat();
+        assert_eq!(
+            Username::decode(&bytes),
+            Err(Errors::StringError(StringError::OutOfBoundsLength))
+        );
+    }
+
+    #[test]
+    fn decode_prefix_too_long() {
+        // Rejected from the length alone, even if the bytes are missing
+        assert_eq!(
+            Username::decode(&[49]),
+            Err(Errors::StringError(StringError::OutOfBoundsEncoding))
+        );
+        assert_eq!(
+            Username::decode(&[48]),
+            Err(Errors::StringError(StringError::InvalidLength))
+        );
+    }
+
+    #[test]
+    fn decode_invalid() {
+        assert_eq!(
+            Username::decode(&[0xFF, 0xFF, 0xFF, 0xFF, 0x0F]),
+            Err(Errors::StringError(StringError::InvalidLength))
+        );
+        assert_eq!(
+            Username::decode(&[0x02, 0xC0, 0xAF]),
+            Err(Errors::StringError(StringError::InvalidUTF8))
+        );
+        assert_eq!(
+            Username::decode(&[]),
+            Err(Errors::StringError(StringError::InvalidLength))
+        );
+        assert_eq!(
+            Username::decode(&[0x80]),
+            Err(Errors::StringError(StringError::InvalidLength))
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let string = BoundedString::<256>::from_str("Hello, 你好 🚀").unwrap();
+        let bytes = string.encode().unwrap();
+        assert_eq!(
+            BoundedString::<256>::decode(&bytes).unwrap(),
+            (string, bytes.len())
+        );
+    }
+}
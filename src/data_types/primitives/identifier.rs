@@ -1,16 +1,18 @@
-use once_cell::sync::Lazy;
 use regex::Regex;
-use std::{fmt::Display, str::FromStr};
+use std::{fmt::Display, str::FromStr, sync::LazyLock};
 use thiserror::Error;
 
-static NS_RE: Lazy<Regex> = Lazy::new(|| Regex::new("[a-z0-9.-_]").unwrap());
-static VAL_RE: Lazy<Regex> = Lazy::new(|| Regex::new("[a-z0-9.-_/]").unwrap());
+use crate::data_types::{self, DataResult, SerDe};
+
+static NS_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new("^[a-z0-9._-]+$").unwrap());
+static VAL_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new("^[a-z0-9._/-]+$").unwrap());
 
 /// Identifiers are a namespaced location, in the form of `minecraft:thing`.
 /// If the namespace is not provided, it defaults to `minecraft` (i.e. `thing` is `minecraft:thing`).
 /// Custom content should always be in its own namespace, not the default one.
 /// Both the namespace and value can use all lowercase alphanumeric characters (a-z and 0-9), dot (`.`), dash (`-`), and underscore (`_`).
 /// In addition, values can use slash (`/`)
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Identifier {
     pub namespace: String,
     pub value: String,
@@ -19,17 +21,22 @@ pub struct Identifier {
 /// Represents an error that can occur when parsing an identifier.
 /// This is returned from [`Identifier::new`] and [`Identifier::from_str`].
 ///
-/// [`IdentifierError::NameSpace`]: The namespace does not match the following regex: `[a-z0-9.-_]`
+/// [`IdentifierError::NameSpace`]: The namespace does not match the following regex: `^[a-z0-9._-]+$`
+///
+/// [`IdentifierError::Value`]: The value does not match the following regex: `^[a-z0-9._/-]+$`
 ///
-/// [`IdentifierError::Value`]: The value does not match the following regex: `[a-z0-9.-_/]`
+/// [`IdentifierError::MissingTagPrefix`]: A [`TagIdentifier`] does not start with `#`
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Error)]
 pub enum IdentifierError {
-    /// The namespace does not match the following regex: `[a-z0-9.-_]`
-    #[error("The namespace does not match the following regex: [a-z0-9.-_]")]
+    /// The namespace does not match the following regex: `^[a-z0-9._-]+$`
+    #[error("The namespace does not match the following regex: ^[a-z0-9._-]+$")]
     NameSpace,
-    /// The value does not match the following regex: `[a-z0-9.-_/]`
-    #[error("The namespace does not match the following regex: [a-z0-9.-_]")]
+    /// The value does not match the following regex: `^[a-z0-9._/-]+$`
+    #[error("The value does not match the following regex: ^[a-z0-9._/-]+$")]
     Value,
+    /// A [`TagIdentifier`] does not start with `#`
+    #[error("Tag identifiers must start with #")]
+    MissingTagPrefix,
 }
 
 impl Identifier {
@@ -37,9 +44,9 @@ impl Identifier {
     ///
     /// # Errors
     ///
-    /// Returns [`IdentifierError::NameSpace`] if the namespace does not match the following regex: `[a-z0-9.-_]`
+    /// Returns [`IdentifierError::NameSpace`] if the namespace does not match the following regex: `^[a-z0-9._-]+$`
     ///
-    /// Returns [`IdentifierError::Value`] if the value does not match the following regex: `[a-z0-9.-_/]`
+    /// Returns [`IdentifierError::Value`] if the value does not match the following regex: `^[a-z0-9._/-]+$`
     ///
     pub fn new(namespace: String, value: String) -> Result<Identifier, IdentifierError> {
         if !NS_RE.is_match(&namespace) {
@@ -57,27 +64,247 @@ impl Identifier {
 impl FromStr for Identifier {
     type Err = IdentifierError;
 
+    /// Parses an identifier in the form of `namespace:value`.
+    ///
+    /// A missing or empty namespace (`value` or `:value`) defaults to `minecraft`.
     fn from_str(str: &str) -> Result<Identifier, IdentifierError> {
-        let (namespace, value) = str.split_once(':').unwrap_or(("minecraft", str));
+        let (namespace, value) = match str.split_once(':') {
+            Some(("", value)) => ("minecraft", value),
+            Some(pair) => pair,
+            None => ("minecraft", str),
+        };
 
-        if !NS_RE.is_match(namespace) {
-            return Err(IdentifierError::NameSpace);
-        }
+        Identifier::new(namespace.into(), value.into())
+    }
+}
 
-        if !VAL_RE.is_match(value) {
-            return Err(IdentifierError::Value);
-        }
+impl Display for Identifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}:{}", self.namespace, self.value)
+    }
+}
 
-        Ok(Identifier {
-            namespace: namespace.into(),
+impl<'a> SerDe<'a> for Identifier {
+    type Input = &'a [u8];
+    type Serialized = DataResult<Vec<u8>>;
+    type Deserialized = DataResult<(Self, usize)>;
 
-            value: value.into(),
-        })
+    /// Encodes an [`Identifier`] as a [`String`](data_types::String) of up to 32767 characters.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`StringError::OutOfBoundsLength`](data_types::StringError::OutOfBoundsLength) if the identifier is too long.
+    fn encode(&self) -> Self::Serialized {
+        data_types::String::from_str(&self.to_string())?.encode()
+    }
+
+    /// Decodes an [`Identifier`] from a [`String`](data_types::String) of up to 32767 characters.
+    ///
+    /// Returns the identifier and the amount of bytes read.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`StringError::OutOfBoundsLength`](data_types::StringError::OutOfBoundsLength) if the identifier is too long,
+    /// any [`IdentifierError`] if it is not valid,
+    /// or any error from [`String::decode_prefixed`](data_types::String::decode_prefixed).
+    fn decode(data: Self::Input) -> Self::Deserialized {
+        let (string, len) = data_types::String::decode_prefixed(data)?;
+
+        let identifier = Identifier::from_str(&string.data)?;
+        // Checks the length limit
+        data_types::String::from_str(&string.data)?;
+
+        Ok((identifier, len))
     }
 }
 
-impl Display for Identifier {
+/// A reference to a tag, written as `#namespace:value` (e.g. `#minecraft:logs`).
+///
+/// On the network, tag and holder set fields send the [`Identifier`] alone, without the `#`.
+/// The prefix is only used in the textual form, see [`TagIdentifier::from_str`] and [`Display`].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TagIdentifier(pub Identifier);
+
+impl FromStr for TagIdentifier {
+    type Err = IdentifierError;
+
+    /// Parses a tag identifier in the form of `#namespace:value`.
+    fn from_str(str: &str) -> Result<TagIdentifier, IdentifierError> {
+        let identifier = str
+            .strip_prefix('#')
+            .ok_or(IdentifierError::MissingTagPrefix)?;
+
+        Ok(TagIdentifier(Identifier::from_str(identifier)?))
+    }
+}
+
+impl Display for TagIdentifier {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "{}:{}", self.namespace, self.value)
+        write!(f, "#{}", self.0)
+    }
+}
+
+impl From<Identifier> for TagIdentifier {
+    fn from(value: Identifier) -> Self {
+        TagIdentifier(value)
+    }
+}
+
+impl From<TagIdentifier> for Identifier {
+    fn from(value: TagIdentifier) -> Self {
+        value.0
+    }
+}
+
+impl<'a> SerDe<'a> for TagIdentifier {
+    type Input = &'a [u8];
+    type Serialized = DataResult<Vec<u8>>;
+    type Deserialized = DataResult<(Self, usize)>;
+
+    /// Encodes a [`TagIdentifier`] as its [`Identifier`], without the `#`.
+    ///
+    /// ## Errors
+    ///
+    /// See [`Identifier::encode`].
+    fn encode(&self) -> Self::Serialized {
+        self.0.encode()
+    }
+
+    /// Decodes a [`TagIdentifier`] from an [`Identifier`], without the `#`.
+    ///
+    /// ## Errors
+    ///
+    /// See [`Identifier::decode`].
+    fn decode(data: Self::Input) -> Self::Deserialized {
+        Identifier::decode(data).map(|(identifier, len)| (TagIdentifier(identifier), len))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use crate::data_types::{Errors, SerDe, StringError};
+
+    use super::{Identifier, IdentifierError, TagIdentifier};
+
+    fn identifier(namespace: &str, value: &str) -> Identifier {
+        Identifier {
+            namespace: namespace.into(),
+            value: value.into(),
+        }
+    }
+
+    #[test]
+    fn valid() {
+        assert_eq!(
+            Identifier::from_str("minecraft:stone"),
+            Ok(identifier("minecraft", "stone"))
+        );
+        assert_eq!(
+            Identifier::from_str("stone"),
+            Ok(identifier("minecraft", "stone"))
+        );
+        assert_eq!(
+            Identifier::from_str(":stone"),
+            Ok(identifier("minecraft", "stone"))
+        );
+        assert_eq!(
+            Identifier::from_str("my-mod_1.0:textures/block/a.b-c_d"),
+            Ok(identifier("my-mod_1.0", "textures/block/a.b-c_d"))
+        );
+    }
+
+    #[test]
+    fn invalid_namespace() {
+        for namespace in ["Foo", "foo bar", "a/b", "é", "+", "a,b"] {
+            assert_eq!(
+                Identifier::new(namespace.into(), "stone".into()),
+                Err(IdentifierError::NameSpace),
+                "{namespace}"
+            );
+        }
+        assert_eq!(
+            Identifier::new(String::new(), "stone".into()),
+            Err(IdentifierError::NameSpace)
+        );
+    }
+
+    #[test]
+    fn invalid_value() {
+        for value in ["Foo Bar", "foo bar", "a:b", "stone!", "+", "a,b", ""] {
+            assert_eq!(
+                Identifier::new("minecraft".into(), value.into()),
+                Err(IdentifierError::Value),
+                "{value}"
+            );
+        }
+        assert_eq!(
+            Identifier::from_str("minecraft:a:b"),
+            Err(IdentifierError::Value)
+        );
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(
+            identifier("minecraft", "stone").to_string(),
+            "minecraft:stone"
+        );
+    }
+
+    #[test]
+    fn encode() {
+        assert_eq!(
+            identifier("minecraft", "stone").encode().unwrap(),
+            [vec![15], b"minecraft:stone".to_vec()].concat()
+        );
+    }
+
+    #[test]
+    fn decode() {
+        let bytes = [vec![15], b"minecraft:stone".to_vec(), vec![0xFF]].concat();
+        assert_eq!(
+            Identifier::decode(&bytes).unwrap(),
+            (identifier("minecraft", "stone"), 16)
+        );
+
+        let bytes = [vec![7], b"Foo Bar".to_vec()].concat();
+        assert_eq!(
+            Identifier::decode(&bytes),
+            Err(Errors::IdentifierError(IdentifierError::Value))
+        );
+    }
+
+    #[test]
+    fn too_long() {
+        let long = identifier("minecraft", &"a".repeat(32767));
+        assert_eq!(
+            long.encode(),
+            Err(Errors::StringError(StringError::OutOfBoundsLength))
+        );
+    }
+
+    #[test]
+    fn tag() {
+        let tag = TagIdentifier::from_str("#minecraft:logs").unwrap();
+        assert_eq!(tag, TagIdentifier(identifier("minecraft", "logs")));
+        assert_eq!(tag.to_string(), "#minecraft:logs");
+        assert_eq!(
+            TagIdentifier::from_str("#logs").unwrap(),
+            TagIdentifier(identifier("minecraft", "logs"))
+        );
+        assert_eq!(
+            TagIdentifier::from_str("minecraft:logs"),
+            Err(IdentifierError::MissingTagPrefix)
+        );
+    }
+
+    #[test]
+    fn tag_codec() {
+        let tag = TagIdentifier::from_str("#minecraft:logs").unwrap();
+        let bytes = [vec![14], b"minecraft:logs".to_vec()].concat();
+        assert_eq!(tag.encode().unwrap(), bytes);
+        assert_eq!(TagIdentifier::decode(&bytes).unwrap(), (tag, 15));
     }
 }
@@ -1,9 +1,11 @@
 pub mod angle;
 pub mod bitset;
 pub mod boolean;
+pub mod bounded_string;
 pub mod chat;
 pub mod identifier;
 pub mod json_chat;
+pub mod nbt;
 pub mod position;
 pub mod rotation;
 pub mod slot;
@@ -14,14 +16,21 @@ pub mod x;
 
 use std::fmt::Debug;
 
+use super::{
+    descriptor::DescriptorError, entity_metadata::EntityMetadata, game_profile::GameProfileError,
+    property::SignatureError, registry_data::RegistryDataError, status::StatusError,
+    tags::TagsError, textures::TexturesError,
+};
 pub use angle::*;
 pub use bitset::*;
 pub use boolean::*;
+pub use bounded_string::*;
 pub use chat::*;
-pub use entity_metadata::*;
 pub use identifier::*;
 pub use json_chat::*;
+pub use nbt::*;
 pub use position::*;
+pub use rotation::*;
 pub use slot::*;
 pub use string::*;
 pub use var_int::*;
@@ -35,6 +44,9 @@ use thiserror::Error;
 /// This enum is used to represent all data types in the Minecraft protocol.
 ///
 /// This enum uses the same names as the [unofficial protocol documentation](https://wiki.vg/Protocol).
+///
+/// The last variants hold values composed at runtime, see [`TypeDescriptor`](super::descriptor::TypeDescriptor).
+#[derive(Debug, Clone, PartialEq)]
 pub enum DataTypes {
     Boolean(Boolean),
     Byte(i8),
@@ -54,12 +66,20 @@ pub enum DataTypes {
     VarLong(VarLong),
     EntityMetadata(EntityMetadata),
     Slot(Slot),
-    NBTTag(quartz_nbt::NbtTag),
+    NBTTag(Nbt),
     Position(Position),
     Angle(Angle),
     UUID(uuid::Uuid),
     ByteArray(Vec<u8>),
     BitSet(BitSet),
+    Void,
+    Optional(Option<Box<DataTypes>>),
+    Array(Vec<DataTypes>),
+    Enum {
+        variant: std::string::String,
+        value: Box<DataTypes>,
+    },
+    Container(Vec<(std::string::String, DataTypes)>),
 }
 
 /// Generic data types
@@ -71,9 +91,36 @@ pub enum DataTypes {
 pub enum GenericDataTypes<X> {
     Optional(Optional<X>),
     Array(Array<X>),
+    InferredArray(InferredArray<X>),
     Enum(Enum<X>),
 }
 
+impl<X> GenericDataTypes<X>
+where
+    Optional<X>: Codec,
+    Array<X>: Codec,
+    InferredArray<X>: Codec,
+    Enum<X>: Codec,
+{
+    /// Encodes the wrapped value, see [`Codec::write`].
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error if the value can't be encoded.
+    pub fn encode(&self) -> DataResult<Vec<u8>> {
+        let mut bytes = Vec::new();
+
+        match self {
+            GenericDataTypes::Optional(value) => value.write(&mut bytes)?,
+            GenericDataTypes::Array(value) => value.write(&mut bytes)?,
+            GenericDataTypes::InferredArray(value) => value.write(&mut bytes)?,
+            GenericDataTypes::Enum(value) => value.write(&mut bytes)?,
+        }
+
+        Ok(bytes)
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Error)]
 pub enum Errors {
     #[error("VarIntError: {0}")]
@@ -90,6 +137,42 @@ pub enum Errors {
     BooleanError(#[from] BooleanError),
     #[error("PositionError: {0}")]
     PositionError(#[from] PositionError),
+    #[error("NbtError: {0}")]
+    NbtError(#[from] NbtError),
+    #[error("RotationError: {0}")]
+    RotationError(#[from] RotationError),
+    #[error("BitSetError: {0}")]
+    BitSetError(#[from] BitSetError),
+    #[error("GameProfileError: {0}")]
+    GameProfileError(#[from] GameProfileError),
+    #[error("TexturesError: {0}")]
+    TexturesError(#[from] TexturesError),
+    #[error("SignatureError: {0}")]
+    SignatureError(#[from] SignatureError),
+    #[error("GenericError: {0}")]
+    GenericError(#[from] GenericError),
+    #[error("DescriptorError: {0}")]
+    DescriptorError(#[from] DescriptorError),
+    #[error("SchemaError: {0}")]
+    SchemaError(#[from] crate::packet::schema::SchemaError),
+    #[error("RegistryDataError: {0}")]
+    RegistryDataError(#[from] RegistryDataError),
+    #[error("TagsError: {0}")]
+    TagsError(#[from] TagsError),
+    #[error("StatusError: {0}")]
+    StatusError(#[from] StatusError),
+    #[error("LegacyPingError: {0}")]
+    LegacyPingError(#[from] crate::packet::legacy_ping::LegacyPingError),
+    #[error("EncryptionError: {0}")]
+    EncryptionError(#[from] crate::packet::encryption::EncryptionError),
+    #[error("SessionError: {0}")]
+    SessionError(#[from] crate::packet::session::SessionError),
+    #[error("ConfigurationError: {0}")]
+    ConfigurationError(#[from] crate::packet::configuration::ConfigurationError),
+    #[error("KnownPacksError: {0}")]
+    KnownPacksError(#[from] crate::packet::known_packs::KnownPacksError),
+    #[error("CookieError: {0}")]
+    CookieError(#[from] crate::packet::cookies::CookieError),
 }
 
 impl Errors {
@@ -0,0 +1,705 @@
+use quartz_nbt::{NbtCompound, NbtList, NbtTag};
+use thiserror::Error;
+
+use crate::{
+    data_types::{DataResult, Errors},
+    values::Version,
+};
+
+const TAG_END: u8 = 0;
+const TAG_BYTE: u8 = 1;
+const TAG_SHORT: u8 = 2;
+const TAG_INT: u8 = 3;
+const TAG_LONG: u8 = 4;
+const TAG_FLOAT: u8 = 5;
+const TAG_DOUBLE: u8 = 6;
+const TAG_BYTE_ARRAY: u8 = 7;
+const TAG_STRING: u8 = 8;
+const TAG_LIST: u8 = 9;
+const TAG_COMPOUND: u8 = 10;
+const TAG_INT_ARRAY: u8 = 11;
+const TAG_LONG_ARRAY: u8 = 12;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Error)]
+pub enum NbtError {
+    #[error("Unexpected end of NBT data")]
+    UnexpectedEnd,
+    #[error("Invalid NBT tag type: {0}")]
+    InvalidTagType(u8),
+    #[error("Root tag must be a compound")]
+    RootNotCompound,
+    #[error("Invalid modified UTF-8 string")]
+    InvalidString,
+    #[error("String is too long to be encoded as NBT")]
+    StringTooLong,
+    #[error("Negative length")]
+    NegativeLength,
+    #[error("Length does not fit into an i32")]
+    LengthOverflow,
+    #[error("List elements must all have the same type")]
+    ListTypeMismatch,
+    #[error("Non-empty list is missing its element type")]
+    MissingListType,
+    #[error("NBT is nested too deeply")]
+    DepthLimitExceeded,
+    #[error("NBT is too big")]
+    SizeLimitExceeded,
+}
+
+/// Limits applied while decoding [`Nbt`], protecting against maliciously nested or oversized data.
+///
+/// The defaults match the Notchian network limits: 512 levels of nesting and 2 MiB of data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NbtLimits {
+    /// Maximum amount of nested lists and compounds, the root included.
+    pub max_depth: usize,
+    /// Maximum amount of bytes that can be read.
+    pub max_size: usize,
+}
+
+impl Default for NbtLimits {
+    fn default() -> Self {
+        NbtLimits {
+            max_depth: 512,
+            max_size: 2 * 1024 * 1024,
+        }
+    }
+}
+
+/// NBT as sent over the network.
+///
+/// Before 1.20.2 (snapshot 23w31a) the root tag is always a named compound.
+/// Since then, the root name is omitted and the root can be any tag.
+/// In both forms, a single `TAG_End` means there is no NBT at all, which is represented as `None`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Nbt(pub Option<NbtTag>);
+
+impl Nbt {
+    /// Whether the root tag is sent without a name on the given version.
+    #[must_use]
+    pub fn is_nameless(version: Version) -> bool {
+        version >= Version::Snapshot23W31A
+    }
+
+    /// Returns the root compound, if any.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`NbtError::RootNotCompound`] if the root is present but is not a compound.
+    pub fn into_compound(self) -> DataResult<Option<NbtCompound>> {
+        match self.0 {
+            None => Ok(None),
+            Some(NbtTag::Compound(compound)) => Ok(Some(compound)),
+            Some(_) => Errors::err(NbtError::RootNotCompound),
+        }
+    }
+
+    /// Encodes the NBT in the form used by `version`.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`NbtError::RootNotCompound`] if the root is not a compound on a version that requires a named root,
+    /// [`NbtError::ListTypeMismatch`] if a list contains different tag types,
+    /// or [`NbtError::StringTooLong`] if a string or a key does not fit into 65535 bytes.
+    pub fn encode(&self, version: Version) -> DataResult<Vec<u8>> {
+        let mut bytes = Vec::new();
+
+        let Some(tag) = &self.0 else {
+            bytes.push(TAG_END);
+            return Ok(bytes);
+        };
+
+        bytes.push(tag_type(tag));
+
+        if !Self::is_nameless(version) {
+            if !matches!(tag, NbtTag::Compound(_)) {
+                return Err(NbtError::RootNotCompound)?;
+            }
+
+            write_string(&mut bytes, "")?;
+        }
+
+        write_payload(&mut bytes, tag)?;
+
+        Ok(bytes)
+    }
+
+    /// Decodes NBT in the form used by `version`, using the default [`NbtLimits`].
+    ///
+    /// Returns the NBT and the amount of bytes read.
+    ///
+    /// ## Errors
+    ///
+    /// See [`Nbt::decode_with_limits`].
+    pub fn decode(data: &[u8], version: Version) -> DataResult<(Self, usize)> {
+        Self::decode_with_limits(data, version, NbtLimits::default())
+    }
+
+    /// Decodes NBT in the form used by `version`.
+    ///
+    /// Returns the NBT and the amount of bytes read.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`NbtError::UnexpectedEnd`] if `data` is truncated,
+    /// [`NbtError::RootNotCompound`] if a named root is not a compound,
+    /// [`NbtError::DepthLimitExceeded`] or [`NbtError::SizeLimitExceeded`] if the data goes over `limits`,
+    /// or any other [`NbtError`] if the data is malformed.
+    pub fn decode_with_limits(
+        data: &[u8],
+        version: Version,
+        limits: NbtLimits,
+    ) -> DataResult<(Self, usize)> {
+        let mut reader = Reader {
+            data,
+            position: 0,
+            depth: 0,
+            limits,
+        };
+
+        let tag_type = reader.read_u8()?;
+
+        if tag_type == TAG_END {
+            return Ok((Nbt(None), reader.position));
+        }
+
+        if !Self::is_nameless(version) {
+            if tag_type != TAG_COMPOUND {
+                return Err(NbtError::RootNotCompound)?;
+            }
+
+            reader.read_string()?;
+        }
+
+        let tag = reader.read_payload(tag_type)?;
+
+        Ok((Nbt(Some(tag)), reader.position))
+    }
+}
+
+impl From<NbtTag> for Nbt {
+    fn from(value: NbtTag) -> Self {
+        Nbt(Some(value))
+    }
+}
+
+impl From<NbtCompound> for Nbt {
+    fn from(value: NbtCompound) -> Self {
+        Nbt(Some(NbtTag::Compound(value)))
+    }
+}
+
+impl From<Option<NbtCompound>> for Nbt {
+    fn from(value: Option<NbtCompound>) -> Self {
+        Nbt(value.map(NbtTag::Compound))
+    }
+}
+
+fn tag_type(tag: &NbtTag) -> u8 {
+    match tag {
+        NbtTag::Byte(_) => TAG_BYTE,
+        NbtTag::Short(_) => TAG_SHORT,
+        NbtTag::Int(_) => TAG_INT,
+        NbtTag::Long(_) => TAG_LONG,
+        NbtTag::Float(_) => TAG_FLOAT,
+        NbtTag::Double(_) => TAG_DOUBLE,
+        NbtTag::ByteArray(_) => TAG_BYTE_ARRAY,
+        NbtTag::String(_) => TAG_STRING,
+        NbtTag::List(_) => TAG_LIST,
+        NbtTag::Compound(_) => TAG_COMPOUND,
+        NbtTag::IntArray(_) => TAG_INT_ARRAY,
+        NbtTag::LongArray(_) => TAG_LONG_ARRAY,
+    }
+}
+
+fn write_length(bytes: &mut Vec<u8>, len: usize) -> DataResult<()> {
+    let len = i32::try_from(len).map_err(|_| NbtError::LengthOverflow)?;
+    bytes.extend_from_slice(&len.to_be_bytes());
+    Ok(())
+}
+
+/// Writes a string as Java's modified UTF-8, prefixed with its length in bytes as an unsigned short.
+fn write_string(bytes: &mut Vec<u8>, string: &str) -> DataResult<()> {
+    let mut encoded = Vec::with_capacity(string.len());
+
+    for unit in string.encode_utf16() {
+        match unit {
+            0x0001..=0x007F => encoded.push(unit.to_be_bytes()[1]),
+            // Null is encoded in two bytes, so the string never contains a zero byte
+            0x0000 | 0x0080..=0x07FF => encoded.extend_from_slice(&[
+                0xC0 | ((unit >> 6) & 0x1F).to_be_bytes()[1],
+                0x80 | (unit & 0x3F).to_be_bytes()[1],
+            ]),
+            // Surrogate pairs are encoded separately, as 3 bytes each
+            _ => encoded.extend_from_slice(&[
+                0xE0 | ((unit >> 12) & 0x0F).to_be_bytes()[1],
+                0x80 | ((unit >> 6) & 0x3F).to_be_bytes()[1],
+                0x80 | (unit & 0x3F).to_be_bytes()[1],
+            ]),
+        }
+    }
+
+    let len = u16::try_from(encoded.len()).map_err(|_| NbtError::StringTooLong)?;
+    bytes.extend_from_slice(&len.to_be_bytes());
+    bytes.append(&mut encoded);
+
+    Ok(())
+}
+
+fn write_payload(bytes: &mut Vec<u8>, tag: &NbtTag) -> DataResult<()> {
+    match tag {
+        NbtTag::Byte(value) => bytes.extend_from_slice(&value.to_be_bytes()),
+        NbtTag::Short(value) => bytes.extend_from_slice(&value.to_be_bytes()),
+        NbtTag::Int(value) => bytes.extend_from_slice(&value.to_be_bytes()),
+        NbtTag::Long(value) => bytes.extend_from_slice(&value.to_be_bytes()),
+        NbtTag::Float(value) => bytes.extend_from_slice(&value.to_be_bytes()),
+        NbtTag::Double(value) => bytes.extend_from_slice(&value.to_be_bytes()),
+        NbtTag::ByteArray(values) => {
+            write_length(bytes, values.len())?;
+            bytes.extend(values.iter().flat_map(|value| value.to_be_bytes()));
+        }
+        NbtTag::String(value) => write_string(bytes, value)?,
+        NbtTag::List(list) => {
+            let element_type = list.iter().next().map_or(TAG_END, tag_type);
+
+            if list.iter().any(|element| tag_type(element) != element_type) {
+                return Err(NbtError::ListTypeMismatch)?;
+            }
+
+            bytes.push(element_type);
+            write_length(bytes, list.len())?;
+
+            for element in list {
+                write_payload(bytes, element)?;
+            }
+        }
+        NbtTag::Compound(compound) => {
+            for (name, value) in compound.inner() {
+                bytes.push(tag_type(value));
+                write_string(bytes, name)?;
+                write_payload(bytes, value)?;
+            }
+
+            bytes.push(TAG_END);
+        }
+        NbtTag::IntArray(values) => {
+            write_length(bytes, values.len())?;
+            bytes.extend(values.iter().flat_map(|value| value.to_be_bytes()));
+        }
+        NbtTag::LongArray(values) => {
+            write_length(bytes, values.len())?;
+            bytes.extend(values.iter().flat_map(|value| value.to_be_bytes()));
+        }
+    }
+
+    Ok(())
+}
+
+/// Reads NBT while keeping track of the nesting depth and the amount of bytes read.
+///
+/// Lengths are checked against the remaining data before anything is allocated,
+/// so a small payload can't claim a huge array.
+struct Reader<'a> {
+    data: &'a [u8],
+    position: usize,
+    depth: usize,
+    limits: NbtLimits,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], NbtError> {
+        let end = self
+            .position
+            .checked_add(len)
+            .ok_or(NbtError::SizeLimitExceeded)?;
+
+        if end > self.limits.max_size {
+            return Err(NbtError::SizeLimitExceeded);
+        }
+
+        let bytes = self
+            .data
+            .get(self.position..end)
+            .ok_or(NbtError::UnexpectedEnd)?;
+        self.position = end;
+
+        Ok(bytes)
+    }
+
+    fn take_array<const N: usize>(&mut self) -> Result<[u8; N], NbtError> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.take(N)?);
+        Ok(array)
+    }
+
+    fn read_u8(&mut self) -> Result<u8, NbtError> {
+        Ok(self.take(1)?[0])
+    }
+
+    /// Reads an array length, making sure `len * element_size` bytes are actually available.
+    fn read_length(&mut self, element_size: usize) -> Result<usize, NbtError> {
+        let len = i32::from_be_bytes(self.take_array()?);
+        let len = usize::try_from(len).map_err(|_| NbtError::NegativeLength)?;
+
+        let remaining = self.data.len().min(self.limits.max_size) - self.position;
+        if len.saturating_mul(element_size) > remaining {
+            return Err(if self.data.len() > self.limits.max_size {
+                NbtError::SizeLimitExceeded
+            } else {
+                NbtError::UnexpectedEnd
+            });
+        }
+
+        Ok(len)
+    }
+
+    fn read_string(&mut self) -> Result<String, NbtError> {
+        let len = u16::from_be_bytes(self.take_array()?);
+        let bytes = self.take(len.into())?;
+
+        let mut units = Vec::with_capacity(bytes.len());
+        let mut iter = bytes.iter().map(|byte| u16::from(*byte));
+
+        while let Some(first) = iter.next() {
+            let mut next = || match iter.next() {
+                Some(byte) if byte & 0xC0 == 0x80 => Ok(byte & 0x3F),
+                _ => Err(NbtError::InvalidString),
+            };
+
+            let unit = match first {
+                0x01..=0x7F => first,
+                0xC0..=0xDF => ((first & 0x1F) << 6) | next()?,
+                0xE0..=0xEF => ((first & 0x0F) << 12) | (next()? << 6) | next()?,
+                _ => return Err(NbtError::InvalidString),
+            };
+
+            units.push(unit);
+        }
+
+        String::from_utf16(&units).map_err(|_| NbtError::InvalidString)
+    }
+
+    fn enter(&mut self) -> Result<(), NbtError> {
+        self.depth += 1;
+
+        if self.depth > self.limits.max_depth {
+            return Err(NbtError::DepthLimitExceeded);
+        }
+
+        Ok(())
+    }
+
+    fn read_payload(&mut self, tag_type: u8) -> Result<NbtTag, NbtError> {
+        Ok(match tag_type {
+            TAG_BYTE => NbtTag::Byte(i8::from_be_bytes(self.take_array()?)),
+            TAG_SHORT => NbtTag::Short(i16::from_be_bytes(self.take_array()?)),
+            TAG_INT => NbtTag::Int(i32::from_be_bytes(self.take_array()?)),
+            TAG_LONG => NbtTag::Long(i64::from_be_bytes(self.take_array()?)),
+            TAG_FLOAT => NbtTag::Float(f32::from_be_bytes(self.take_array()?)),
+            TAG_DOUBLE => NbtTag::Double(f64::from_be_bytes(self.take_array()?)),
+            TAG_BYTE_ARRAY => {
+                let len = self.read_length(1)?;
+                NbtTag::ByteArray(
+                    self.take(len)?
+                        .iter()
+                        .map(|byte| i8::from_be_bytes([*byte]))
+                        .collect(),
+                )
+            }
+            TAG_STRING => NbtTag::String(self.read_string()?),
+            TAG_LIST => {
+                self.enter()?;
+
+                let element_type = self.read_u8()?;
+                let len = self.read_length(usize::from(element_type != TAG_END))?;
+
+                if element_type == TAG_END && len > 0 {
+                    return Err(NbtError::MissingListType);
+                }
+
+                let mut list = NbtList::with_capacity(len);
+                for _ in 0..len {
+                    list.push(self.read_payload(element_type)?);
+                }
+
+                self.depth -= 1;
+                NbtTag::List(list)
+            }
+            TAG_COMPOUND => {
+                self.enter()?;
+
+                let mut compound = NbtCompound::new();
+                loop {
+                    let value_type = self.read_u8()?;
+                    if value_type == TAG_END {
+                        break;
+                    }
+
+                    let name = self.read_string()?;
+                    let value = self.read_payload(value_type)?;
+                    compound.insert(name, value);
+                }
+
+                self.depth -= 1;
+                NbtTag::Compound(compound)
+            }
+            TAG_INT_ARRAY => {
+                let len = self.read_length(4)?;
+                let mut values = Vec::with_capacity(len);
+                for _ in 0..len {
+                    values.push(i32::from_be_bytes(self.take_array()?));
+                }
+                NbtTag::IntArray(values)
+            }
+            TAG_LONG_ARRAY => {
+                let len = self.read_length(8)?;
+                let mut values = Vec::with_capacity(len);
+                for _ in 0..len {
+                    values.push(i64::from_be_bytes(self.take_array()?));
+                }
+                NbtTag::LongArray(values)
+            }
+            _ => return Err(NbtError::InvalidTagType(tag_type)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use quartz_nbt::{NbtCompound, NbtList, NbtTag};
+
+    use crate::{
+        data_types::{Errors, Nbt, NbtError, NbtLimits},
+        values::Version,
+    };
+
+    static LEGACY: Version = Version::Release1_20_1;
+    static NAMELESS: Version = Version::Release1_20_2;
+
+    fn compound() -> NbtCompound {
+        let mut compound = NbtCompound::new();
+        compound.insert("a", NbtTag::Byte(1));
+        compound
+    }
+
+    #[test]
+    fn is_nameless() {
+        assert!(!Nbt::is_nameless(Version::Release1_8_9));
+        assert!(!Nbt::is_nameless(Version::Release1_20_1));
+        assert!(Nbt::is_nameless(Version::Snapshot23W31A));
+        assert!(Nbt::is_nameless(Version::Release1_20_4));
+    }
+
+    #[test]
+    fn encode_named() {
+        assert_eq!(
+            Nbt::from(compound()).encode(LEGACY).unwrap(),
+            vec![0x0A, 0x00, 0x00, 0x01, 0x00, 0x01, b'a', 0x01, 0x00]
+        );
+    }
+
+    #[test]
+    fn encode_nameless() {
+        assert_eq!(
+            Nbt::from(compound()).encode(NAMELESS).unwrap(),
+            vec![0x0A, 0x01, 0x00, 0x01, b'a', 0x01, 0x00]
+        );
+    }
+
+    #[test]
+    fn decode_named() {
+        let bytes = [
+            0x0A, 0x00, 0x03, b'r', b'o', b't', 0x01, 0x00, 0x01, b'a', 0x01, 0x00,
+        ];
+        assert_eq!(
+            Nbt::decode(&bytes, LEGACY).unwrap(),
+            (Nbt::from(compound()), bytes.len())
+        );
+    }
+
+    #[test]
+    fn decode_nameless() {
+        let bytes = [0x0A, 0x01, 0x00, 0x01, b'a', 0x01, 0x00];
+        assert_eq!(
+            Nbt::decode(&bytes, NAMELESS).unwrap(),
+            (Nbt::from(compound()), bytes.len())
+        );
+    }
+
+    #[test]
+    fn absent() {
+        assert_eq!(Nbt(None).encode(LEGACY).unwrap(), vec![0x00]);
+        assert_eq!(Nbt(None).encode(NAMELESS).unwrap(), vec![0x00]);
+        assert_eq!(Nbt::decode(&[0x00, 0xFF], LEGACY).unwrap(), (Nbt(None), 1));
+        assert_eq!(
+            Nbt::decode(&[0x00, 0xFF], NAMELESS).unwrap(),
+            (Nbt(None), 1)
+        );
+    }
+
+    #[test]
+    fn nameless_root_can_be_any_tag() {
+        let nbt = Nbt::from(NbtTag::String("Hello".into()));
+        let bytes = nbt.encode(NAMELESS).unwrap();
+        assert_eq!(bytes, vec![0x08, 0x00, 0x05, b'H', b'e', b'l', b'l', b'o']);
+        assert_eq!(Nbt::decode(&bytes, NAMELESS).unwrap(), (nbt, bytes.len()));
+    }
+
+    #[test]
+    fn named_root_must_be_compound() {
+        let nbt = Nbt::from(NbtTag::Int(1));
+        assert_eq!(
+            nbt.encode(LEGACY),
+            Err(Errors::NbtError(NbtError::RootNotCompound))
+        );
+        assert_eq!(
+            Nbt::decode(&[0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01], LEGACY),
+            Err(Errors::NbtError(NbtError::RootNotCompound))
+        );
+    }
+
+    #[test]
+    fn round_trip_all_tags() {
+        let mut list = NbtList::new();
+        list.push(NbtTag::Short(-2));
+        list.push(NbtTag::Short(3));
+
+        let mut inner = NbtCompound::new();
+        inner.insert("long", NbtTag::Long(i64::MIN));
+        inner.insert("empty", NbtTag::List(NbtList::new()));
+
+        let mut root = NbtCompound::new();
+        root.insert("byte", NbtTag::Byte(-1));
+        root.insert("int", NbtTag::Int(25565));
+        root.insert("float", NbtTag::Float(1.5));
+        root.insert("double", NbtTag::Double(-0.25));
+        root.insert("bytes", NbtTag::ByteArray(vec![1, -2, 3]));
+        root.insert("string", NbtTag::String("\0 你好 🚀".into()));
+        root.insert("list", NbtTag::List(list));
+        root.insert("compound", NbtTag::Compound(inner));
+        root.insert("ints", NbtTag::IntArray(vec![i32::MIN, 0, i32::MAX]));
+        root.insert("longs", NbtTag::LongArray(vec![7, -7]));
+
+        let nbt = Nbt::from(root);
+
+        for version in [LEGACY, NAMELESS] {
+            let bytes = nbt.encode(version).unwrap();
+            assert_eq!(
+                Nbt::decode(&bytes, version).unwrap(),
+                (nbt.clone(), bytes.len())
+            );
+        }
+    }
+
+    #[test]
+    fn modified_utf8() {
+        let nbt = Nbt::from(NbtTag::String("\0🚀".into()));
+        assert_eq!(
+            nbt.encode(NAMELESS).unwrap(),
+            vec![0x08, 0x00, 0x08, 0xC0, 0x80, 0xED, 0xA0, 0xBD, 0xED, 0xBA, 0x80]
+        );
+    }
+
+    #[test]
+    fn mixed_list() {
+        let mut list = NbtList::new();
+        list.push(NbtTag::Byte(1));
+        list.push(NbtTag::Int(1));
+
+        assert_eq!(
+            Nbt::from(NbtTag::List(list)).encode(NAMELESS),
+            Err(Errors::NbtError(NbtError::ListTypeMismatch))
+        );
+    }
+
+    #[test]
+    fn truncated() {
+        assert_eq!(
+            Nbt::decode(&[0x0A, 0x01, 0x00, 0x01, b'a'], NAMELESS),
+            Err(Errors::NbtError(NbtError::UnexpectedEnd))
+        );
+        assert_eq!(
+            Nbt::decode(&[], NAMELESS),
+            Err(Errors::NbtError(NbtError::UnexpectedEnd))
+        );
+    }
+
+    #[test]
+    fn invalid_tag_type() {
+        assert_eq!(
+            Nbt::decode(&[0x0D], NAMELESS),
+            Err(Errors::NbtError(NbtError::InvalidTagType(0x0D)))
+        );
+    }
+
+    #[test]
+    fn huge_array_length() {
+        assert_eq!(
+            Nbt::decode(&[0x0B, 0x7F, 0xFF, 0xFF, 0xFF, 0x00], NAMELESS),
+            Err(Errors::NbtError(NbtError::UnexpectedEnd))
+        );
+        assert_eq!(
+            Nbt::decode(&[0x07, 0xFF, 0xFF, 0xFF, 0xFF], NAMELESS),
+            Err(Errors::NbtError(NbtError::NegativeLength))
+        );
+    }
+
+    #[test]
+    fn list_of_end() {
+        assert_eq!(
+            Nbt::decode(&[0x09, 0x00, 0x7F, 0xFF, 0xFF, 0xFF], NAMELESS),
+            Err(Errors::NbtError(NbtError::MissingListType))
+        );
+        assert_eq!(
+            Nbt::decode(&[0x09, 0x00, 0x00, 0x00, 0x00, 0x00], NAMELESS).unwrap(),
+            (Nbt::from(NbtTag::List(NbtList::new())), 6)
+        );
+    }
+
+    #[test]
+    fn depth_limit() {
+        // A list of lists of lists...
+        let mut bytes = vec![0x09];
+        for _ in 0..600 {
+            bytes.extend_from_slice(&[0x09, 0x00, 0x00, 0x00, 0x01]);
+        }
+        bytes.extend_from_slice(&[0x00, 0x00, 0x00, 0x00, 0x00]);
+
+        assert_eq!(
+            Nbt::decode(&bytes, NAMELESS),
+            Err(Errors::NbtError(NbtError::DepthLimitExceeded))
+        );
+
+        let limits = NbtLimits {
+            max_depth: 1024,
+            ..NbtLimits::default()
+        };
+        assert!(Nbt::decode_with_limits(&bytes, NAMELESS, limits).is_ok());
+    }
+
+    #[test]
+    fn size_limit() {
+        let bytes = Nbt::from(NbtTag::ByteArray(vec![0; 64]))
+            .encode(NAMELESS)
+            .unwrap();
+        let limits = NbtLimits {
+            max_size: 32,
+            ..NbtLimits::default()
+        };
+
+        assert_eq!(
+            Nbt::decode_with_limits(&bytes, NAMELESS, limits),
+            Err(Errors::NbtError(NbtError::SizeLimitExceeded))
+        );
+    }
+
+    #[test]
+    fn into_compound() {
+        assert_eq!(Nbt(None).into_compound(), Ok(None));
+        assert_eq!(Nbt::from(compound()).into_compound(), Ok(Some(compound())));
+        assert_eq!(
+            Nbt::from(NbtTag::Byte(0)).into_compound(),
+            Err(Errors::NbtError(NbtError::RootNotCompound))
+        );
+    }
+}
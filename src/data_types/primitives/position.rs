@@ -1,6 +1,14 @@
 use thiserror::Error;
 
-use crate::data_types::{DataResult, Errors, SerDe};
+use crate::{
+    data_types::{DataResult, Errors, SerDe},
+    values::Version,
+};
+
+const MIN_HORIZONTAL: i32 = -(1 << 25);
+const MAX_HORIZONTAL: i32 = (1 << 25) - 1;
+const MIN_VERTICAL: i32 = -(1 << 11);
+const MAX_VERTICAL: i32 = (1 << 11) - 1;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Error)]
 pub enum PositionError {
@@ -22,27 +30,124 @@ pub struct Position {
     pub z: i32,
 }
 
+/// The position of a chunk column, in chunks.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ChunkPos {
+    pub x: i32,
+    pub z: i32,
+}
+
 impl Position {
     /// Creates a new [`Position`] from the given coordinates.
     ///
+    /// X and Z must fit into 26 signed bits (`-33554432..=33554431`),
+    /// and Y into 12 signed bits (`-2048..=2047`).
+    ///
     /// ## Errors
     ///
     /// Returns an error if any of the coordinates are out of range.
     pub fn new(x: i32, y: i32, z: i32) -> DataResult<Position> {
-        if x > 0b11_1111_1111_1111_1111_1111_1111 {
+        if !(MIN_HORIZONTAL..=MAX_HORIZONTAL).contains(&x) {
             return Err(PositionError::XOutOfRange)?;
         }
 
-        if y > 0b1111_1111_1111 {
+        if !(MIN_VERTICAL..=MAX_VERTICAL).contains(&y) {
             return Err(PositionError::YOutOfRange)?;
         }
 
-        if z > 0b11_1111_1111_1111_1111_1111_1111 {
+        if !(MIN_HORIZONTAL..=MAX_HORIZONTAL).contains(&z) {
             return Err(PositionError::ZOutOfRange)?;
         }
 
         Ok(Position { x, y, z })
     }
+
+    /// Whether `version` packs positions as X/Y/Z instead of X/Z/Y.
+    ///
+    /// The order was changed in snapshot 18w43a (1.14).
+    #[must_use]
+    pub fn is_legacy_layout(version: Version) -> bool {
+        version < Version::Snapshot18W43A
+    }
+
+    /// Encodes a [`Position`] into a 64-bit integer, using the layout of `version`.
+    ///
+    /// See [`Position::is_legacy_layout`].
+    #[must_use]
+    pub fn encode_for(&self, version: Version) -> i64 {
+        if !Self::is_legacy_layout(version) {
+            return self.encode();
+        }
+
+        let x = (i64::from(self.x) & 0x03FF_FFFF) << 38;
+        let y = (i64::from(self.y) & 0xFFF) << 26;
+        let z = i64::from(self.z) & 0x03FF_FFFF;
+
+        x | y | z
+    }
+
+    /// Decodes a 64-bit integer into a [`Position`], using the layout of `version`.
+    ///
+    /// Before 1.14, the first 26 bits represent the X coordinate,
+    /// the next 12 bits represent the Y coordinate and the last 26 bits represent the Z coordinate.
+    ///
+    /// ## Errors
+    ///
+    /// See [`Position::decode`].
+    pub fn decode_for(value: i64, version: Version) -> DataResult<Self> {
+        if !Self::is_legacy_layout(version) {
+            return Self::decode(value);
+        }
+
+        let x = value >> 38;
+        let y = value << 26 >> 52;
+        let z = value << 38 >> 38;
+
+        Self::new(
+            x.try_into().map_err(Errors::map(PositionError::Overflow))?,
+            y.try_into().map_err(Errors::map(PositionError::Overflow))?,
+            z.try_into().map_err(Errors::map(PositionError::Overflow))?,
+        )
+    }
+
+    /// Returns the position of the chunk column containing this block.
+    #[must_use]
+    pub fn chunk_pos(&self) -> ChunkPos {
+        ChunkPos {
+            x: self.x >> 4,
+            z: self.z >> 4,
+        }
+    }
+
+    /// Returns the Y coordinate of the chunk section containing this block, in sections.
+    #[must_use]
+    pub fn section_y(&self) -> i32 {
+        self.y >> 4
+    }
+
+    /// Returns the X, Y and Z offsets of this block inside its chunk section, each in `0..16`.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn section_offset(&self) -> (u8, u8, u8) {
+        (
+            (self.x & 0xF) as u8,
+            (self.y & 0xF) as u8,
+            (self.z & 0xF) as u8,
+        )
+    }
+}
+
+impl ChunkPos {
+    #[must_use]
+    pub fn new(x: i32, z: i32) -> Self {
+        ChunkPos { x, z }
+    }
+
+    /// Returns the X and Z coordinates of the block at the north-west corner of this chunk.
+    #[must_use]
+    pub fn min_block(&self) -> (i32, i32) {
+        (self.x << 4, self.z << 4)
+    }
 }
 
 impl SerDe<'_> for Position {
@@ -51,7 +156,6 @@ impl SerDe<'_> for Position {
     type Deserialized = DataResult<Self>;
 
     /// Encodes a [`Position`] into a 64-bit integer
-    #[must_use]
     fn encode(&self) -> Self::Serialized {
         let x = (i64::from(self.x) & 0x03FF_FFFF) << 38;
         let y = i64::from(self.y) & 0xFFF;
@@ -66,6 +170,8 @@ impl SerDe<'_> for Position {
     /// - The next 26 bits represent the Z coordinate.
     /// - The last 12 bits represent the Y
     ///
+    /// This is the layout used since 1.14, see [`Position::decode_for`] for older versions.
+    ///
     /// ## Errors
     ///
     /// Returns [`PositionError::Overflow`] if any of the coordinates overflow an `i32`.
@@ -86,9 +192,12 @@ impl SerDe<'_> for Position {
 
 #[cfg(test)]
 mod test {
-    use crate::data_types::SerDe;
+    use crate::{
+        data_types::{Errors, SerDe},
+        values::Version,
+    };
 
-    use super::Position;
+    use super::{ChunkPos, Position, PositionError};
 
     #[allow(clippy::unusual_byte_groupings)]
     static EXAMPLE_1_BYTES: i64 =
@@ -117,4 +226,85 @@ mod test {
         let position = Position::new(18_357_644, 831, -20_882_616).unwrap();
         assert_eq!(Position::decode(position.encode()).unwrap(), position);
     }
+
+    #[test]
+    fn bounds() {
+        assert!(Position::new(33_554_431, 2047, 33_554_431).is_ok());
+        assert!(Position::new(-33_554_432, -2048, -33_554_432).is_ok());
+        assert_eq!(
+            Position::new(33_554_432, 0, 0),
+            Err(Errors::PositionError(PositionError::XOutOfRange))
+        );
+        assert_eq!(
+            Position::new(-33_554_433, 0, 0),
+            Err(Errors::PositionError(PositionError::XOutOfRange))
+        );
+        assert_eq!(
+            Position::new(0, 2048, 0),
+            Err(Errors::PositionError(PositionError::YOutOfRange))
+        );
+        assert_eq!(
+            Position::new(0, -2049, 0),
+            Err(Errors::PositionError(PositionError::YOutOfRange))
+        );
+        assert_eq!(
+            Position::new(0, 0, 33_554_432),
+            Err(Errors::PositionError(PositionError::ZOutOfRange))
+        );
+        assert_eq!(
+            Position::new(0, 0, -33_554_433),
+            Err(Errors::PositionError(PositionError::ZOutOfRange))
+        );
+    }
+
+    #[allow(clippy::unusual_byte_groupings)]
+    static LEGACY_EXAMPLE_1_BYTES: i64 =
+        0b01000110000001110110001100_001100111111_10110000010101101101001000;
+
+    #[test]
+    fn encode_legacy() {
+        let position = Position::new(18_357_644, 831, -20_882_616).unwrap();
+        assert_eq!(
+            position.encode_for(Version::Release1_8_9),
+            LEGACY_EXAMPLE_1_BYTES
+        );
+        assert_eq!(
+            position.encode_for(Version::Release1_13_2),
+            LEGACY_EXAMPLE_1_BYTES
+        );
+        assert_eq!(position.encode_for(Version::Release1_14), EXAMPLE_1_BYTES);
+    }
+
+    #[test]
+    fn decode_legacy() {
+        let position = Position::new(18_357_644, 831, -20_882_616).unwrap();
+        assert_eq!(
+            Position::decode_for(LEGACY_EXAMPLE_1_BYTES, Version::Release1_12_2).unwrap(),
+            position
+        );
+        assert_eq!(
+            Position::decode_for(EXAMPLE_1_BYTES, Version::Release1_20_4).unwrap(),
+            position
+        );
+    }
+
+    #[test]
+    fn encode_decode_negative() {
+        let position = Position::new(-1, -64, -33_554_432).unwrap();
+        for version in [Version::Release1_8_9, Version::Release1_20_4] {
+            assert_eq!(
+                Position::decode_for(position.encode_for(version), version).unwrap(),
+                position
+            );
+        }
+    }
+
+    #[test]
+    fn chunk_helpers() {
+        let position = Position::new(-1, -64, 35).unwrap();
+        assert_eq!(position.chunk_pos(), ChunkPos::new(-1, 2));
+        assert_eq!(position.section_y(), -4);
+        assert_eq!(position.section_offset(), (15, 0, 3));
+        assert_eq!(ChunkPos::new(-1, 2).min_block(), (-16, 32));
+    }
 }
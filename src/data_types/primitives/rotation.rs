@@ -0,0 +1,242 @@
+use thiserror::Error;
+
+use crate::data_types::{Angle, DataResult, SerDe};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Error)]
+pub enum RotationError {
+    #[error("Not enough bytes to decode the rotation")]
+    UnexpectedEnd,
+}
+
+/// Rotation on the three axes, in degrees, as used by the armor stand metadata.
+///
+/// Encoded as three big-endian floats: pitch (X), yaw (Y) and roll (Z).
+#[derive(Debug, Clone, Copy, Default, PartialEq, PartialOrd)]
+pub struct Rotation {
+    pub pitch: f32,
+    pub yaw: f32,
+    pub roll: f32,
+}
+
+impl Rotation {
+    #[must_use]
+    pub fn new(pitch: f32, yaw: f32, roll: f32) -> Self {
+        Rotation { pitch, yaw, roll }
+    }
+}
+
+impl<'a> SerDe<'a> for Rotation {
+    type Input = &'a [u8];
+    type Serialized = [u8; 12];
+    type Deserialized = DataResult<(Self, usize)>;
+
+    /// Encodes a [`Rotation`] into 12 bytes.
+    fn encode(&self) -> Self::Serialized {
+        let mut bytes = [0; 12];
+        bytes[..4].copy_from_slice(&self.pitch.to_be_bytes());
+        bytes[4..8].copy_from_slice(&self.yaw.to_be_bytes());
+        bytes[8..].copy_from_slice(&self.roll.to_be_bytes());
+        bytes
+    }
+
+    /// Decodes a [`Rotation`] from the first 12 bytes of `data`, ignoring anything after them.
+    ///
+    /// Returns the rotation and the amount of bytes read.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`RotationError::UnexpectedEnd`] if `data` is shorter than 12 bytes.
+    fn decode(data: Self::Input) -> Self::Deserialized {
+        let bytes = data.get(..12).ok_or(RotationError::UnexpectedEnd)?;
+
+        let float = |index: usize| {
+            f32::from_be_bytes([
+                bytes[index],
+                bytes[index + 1],
+                bytes[index + 2],
+                bytes[index + 3],
+            ])
+        };
+
+        Ok((
+            Rotation {
+                pitch: float(0),
+                yaw: float(4),
+                roll: float(8),
+            },
+            12,
+        ))
+    }
+}
+
+/// Wraps degrees into the range `[-180, 180)`.
+#[must_use]
+pub fn wrap_degrees(degrees: f32) -> f32 {
+    (degrees + 180.0).rem_euclid(360.0) - 180.0
+}
+
+/// The direction an entity is looking at, in degrees.
+///
+/// Yaw is measured from the positive Z axis (south) towards the negative X axis (west),
+/// and pitch goes from -90 (looking up) to 90 (looking down).
+///
+/// Movement packets send both as floats,
+/// while entity and head-look packets send them as [`Angle`]s.
+#[derive(Debug, Clone, Copy, Default, PartialEq, PartialOrd)]
+pub struct Look {
+    pub yaw: f32,
+    pub pitch: f32,
+}
+
+impl Look {
+    #[must_use]
+    pub fn new(yaw: f32, pitch: f32) -> Self {
+        Look { yaw, pitch }
+    }
+
+    /// Creates a [`Look`] from the [`Angle`]s sent by entity packets.
+    #[must_use]
+    pub fn from_angles(yaw: Angle, pitch: Angle) -> Self {
+        Look {
+            yaw: yaw.to_signed_degrees(),
+            pitch: pitch.to_signed_degrees(),
+        }
+    }
+
+    /// Converts the yaw and pitch into [`Angle`]s, as sent by entity packets.
+    #[must_use]
+    pub fn to_angles(self) -> (Angle, Angle) {
+        (
+            Angle::from_degrees(self.yaw),
+            Angle::from_degrees(self.pitch),
+        )
+    }
+
+    /// Wraps the yaw into `[-180, 180)` and clamps the pitch into `[-90, 90]`.
+    #[must_use]
+    pub fn normalized(self) -> Self {
+        Look {
+            yaw: wrap_degrees(self.yaw),
+            pitch: self.pitch.clamp(-90.0, 90.0),
+        }
+    }
+
+    /// Returns the unit vector `(x, y, z)` this look points to.
+    #[must_use]
+    pub fn direction(self) -> (f64, f64, f64) {
+        let yaw = f64::from(self.yaw).to_radians();
+        let pitch = f64::from(self.pitch).to_radians();
+
+        (
+            -yaw.sin() * pitch.cos(),
+            -pitch.sin(),
+            yaw.cos() * pitch.cos(),
+        )
+    }
+
+    /// Creates a [`Look`] pointing along the vector `(x, y, z)`.
+    ///
+    /// A zero vector looks straight ahead to the south.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn towards(x: f64, y: f64, z: f64) -> Self {
+        let horizontal = x.hypot(z);
+
+        Look {
+            yaw: if horizontal == 0.0 {
+                0.0
+            } else {
+                (-x).atan2(z).to_degrees() as f32
+            },
+            pitch: (-y).atan2(horizontal).to_degrees() as f32,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::data_types::{Angle, Errors, SerDe};
+
+    use super::{wrap_degrees, Look, Rotation, RotationError};
+
+    fn assert_close(left: f64, right: f64) {
+        assert!((left - right).abs() < 1e-6, "{left} != {right}");
+    }
+
+    #[test]
+    fn encode() {
+        assert_eq!(
+            Rotation::new(1.0, -90.0, 0.5).encode(),
+            [0x3F, 0x80, 0x00, 0x00, 0xC2, 0xB4, 0x00, 0x00, 0x3F, 0x00, 0x00, 0x00]
+        );
+    }
+
+    #[test]
+    fn decode() {
+        assert_eq!(
+            Rotation::decode(&[
+                0x3F, 0x80, 0x00, 0x00, 0xC2, 0xB4, 0x00, 0x00, 0x3F, 0x00, 0x00, 0x00
+            ])
+            .unwrap(),
+            (Rotation::new(1.0, -90.0, 0.5), 12)
+        );
+        assert_eq!(
+            Rotation::decode(&[0x00; 13]).unwrap(),
+            (Rotation::default(), 12)
+        );
+        assert_eq!(
+            Rotation::decode(&[0x00; 11]),
+            Err(Errors::RotationError(RotationError::UnexpectedEnd))
+        );
+    }
+
+    #[test]
+    fn wrap() {
+        assert_close(wrap_degrees(0.0).into(), 0.0);
+        assert_close(wrap_degrees(180.0).into(), -180.0);
+        assert_close(wrap_degrees(270.0).into(), -90.0);
+        assert_close(wrap_degrees(-190.0).into(), 170.0);
+        assert_close(wrap_degrees(720.0 + 45.0).into(), 45.0);
+    }
+
+    #[test]
+    fn normalized() {
+        assert_eq!(
+            Look::new(450.0, -120.0).normalized(),
+            Look::new(90.0, -90.0)
+        );
+    }
+
+    #[test]
+    fn angles() {
+        let look = Look::new(-90.0, 45.0);
+        assert_eq!(look.to_angles(), (Angle::from(192), Angle::from(32)));
+        assert_eq!(Look::from_angles(Angle::from(192), Angle::from(32)), look);
+    }
+
+    #[test]
+    fn direction() {
+        let (x, y, z) = Look::new(0.0, 0.0).direction();
+        assert_close(x, 0.0);
+        assert_close(y, 0.0);
+        assert_close(z, 1.0);
+
+        let (x, y, z) = Look::new(90.0, 0.0).direction();
+        assert_close(x, -1.0);
+        assert_close(y, 0.0);
+        assert_close(z, 0.0);
+
+        let (x, y, z) = Look::new(0.0, -90.0).direction();
+        assert_close(x, 0.0);
+        assert_close(y, 1.0);
+        assert_close(z, 0.0);
+    }
+
+    #[test]
+    fn towards() {
+        assert_eq!(Look::towards(0.0, 0.0, 1.0), Look::new(0.0, 0.0));
+        assert_eq!(Look::towards(-1.0, 0.0, 0.0), Look::new(90.0, 0.0));
+        assert_eq!(Look::towards(0.0, -1.0, 0.0), Look::new(0.0, 90.0));
+        assert_eq!(Look::towards(1.0, 1.0, 0.0), Look::new(-90.0, -45.0));
+    }
+}
@@ -5,10 +5,12 @@ use crate::data_types::{Boolean, DataResult, SerDe, VarInt};
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Error)]
 pub enum SlotDataError {}
 
+#[derive(Debug, Clone, PartialEq)]
 pub struct Slot {
     pub data: Option<SlotData>,
 }
 
+#[derive(Debug, Clone, PartialEq)]
 #[allow(clippy::module_name_repetitions)]
 pub struct SlotData {
     pub item_id: VarInt,
@@ -2,18 +2,21 @@ use std::str::FromStr;
 
 use thiserror::Error;
 
-use crate::data_types::{DataResult, Errors, SerDe};
+use crate::data_types::{Codec, DataResult, Errors, GenericError, SerDe};
 
 use super::var_int::VarInt;
 
 static MAX_SIZE: u32 = 32767;
 
-/// Modified UTF-8 string prefixed with its size in bytes as a [`VarInt`].
+/// UTF-8 string prefixed with its size in bytes as a [`VarInt`].
 ///
 /// Maximum length of `n` characters, which varies by context;
 /// up to `n × 3` bytes can be used to encode `n` characters and both of those limits are checked.
 /// Maximum `n` value is 32767.
 /// The + 3 is due to the max size of a valid length [`VarInt`].
+///
+/// Like in Java, characters are counted as UTF-16 code units,
+/// so characters outside the Basic Multilingual Plane (like most emoji) count as two. See [`utf16_len`].
 #[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord)]
 pub struct String {
     pub data: std::string::String,
@@ -33,6 +36,35 @@ pub enum StringError {
     OutOfBoundsEncoding,
 }
 
+/// Returns the length of `string` as counted by Java's `String.length()`, in UTF-16 code units.
+#[must_use]
+pub fn utf16_len(string: &str) -> usize {
+    string.encode_utf16().count()
+}
+
+/// Checks that `string` fits into `max_size` UTF-16 code units and `max_size × 3` bytes.
+pub(crate) fn check_bounds(string: &str, max_size: usize) -> DataResult<()> {
+    if string.len() > max_size * 3 {
+        return Err(StringError::OutOfBoundsEncoding)?;
+    }
+
+    if utf16_len(string) > max_size {
+        return Err(StringError::OutOfBoundsLength)?;
+    }
+
+    Ok(())
+}
+
+/// Reads the [`VarInt`] length prefix of a string, returning it and its amount of bytes.
+///
+/// A missing or truncated prefix is a [`StringError::InvalidLength`].
+pub(crate) fn read_size(data: &[u8]) -> DataResult<(VarInt, usize)> {
+    match VarInt::read(data) {
+        Err(Errors::GenericError(GenericError::UnexpectedEnd)) => Err(StringError::InvalidLength)?,
+        result => result,
+    }
+}
+
 impl String {
     /// Creates a new String with a maximum length of `len`.
     ///
@@ -59,16 +91,10 @@ impl String {
     ///
     /// ## Errors
     ///
-    /// Returns [`StringError::OutOfBoundsLength`] if `string` is greater than 32767 characters,
-    /// or [`StringError::OutOfBoundsEncoding`] if the UTF-8 encoding of `string` is greater than 32767 bytes.
+    /// Returns [`StringError::OutOfBoundsEncoding`] if the UTF-8 encoding of `string` is greater than 3 times the maximum length,
+    /// or [`StringError::OutOfBoundsLength`] if `string` is greater than the maximum length, in UTF-16 code units.
     pub fn insert(&mut self, string: std::string::String) -> DataResult<&mut Self> {
-        if string.len() > self.max_size as usize {
-            return Err(StringError::OutOfBoundsLength)?;
-        }
-
-        if string.as_bytes().len() > (MAX_SIZE * 3) as usize {
-            return Err(StringError::OutOfBoundsEncoding)?;
-        }
+        check_bounds(&string, self.max_size as usize)?;
 
         self.data = string;
 
@@ -80,26 +106,52 @@ impl String {
     /// Unlike [`String::insert`], this does not replace the current string,
     /// but instead appends the string to the current string.
     ///
+    /// If the result does not fit, the current string is left untouched.
+    ///
     /// ## Errors
     ///
-    /// Returns [`StringError::OutOfBoundsLength`] if `string` is greater than 32767 characters,
-    /// if the current string length plus the length of `string` is greater than 32767,
-    /// or [`StringError::OutOfBoundsEncoding`] if the UTF-8 encoding of `string` is greater than 32767 bytes.
+    /// Returns [`StringError::OutOfBoundsEncoding`] if the UTF-8 encoding of the result is greater than 3 times the maximum length,
+    /// or [`StringError::OutOfBoundsLength`] if the result is greater than the maximum length, in UTF-16 code units.
+    // Takes an owned string like `insert`, which this API has always done
+    #[allow(clippy::needless_pass_by_value)]
     pub fn concat(&mut self, string: std::string::String) -> DataResult<&mut Self> {
-        if string.len() > self.max_size as usize {
-            return Err(StringError::OutOfBoundsLength)?;
+        if self.data.len() + string.len() > self.max_size as usize * 3 {
+            return Err(StringError::OutOfBoundsEncoding)?;
         }
 
-        if string.as_bytes().len() > (MAX_SIZE * 3) as usize {
-            return Err(StringError::OutOfBoundsEncoding)?;
+        if utf16_len(&self.data) + utf16_len(&string) > self.max_size as usize {
+            return Err(StringError::OutOfBoundsLength)?;
         }
 
-        self.data = string;
+        self.data.push_str(&string);
 
         Ok(self)
     }
 }
 
+impl String {
+    /// Decodes a String from the start of `data`, ignoring anything after it.
+    ///
+    /// Returns the string and the amount of bytes read.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`StringError::InvalidLength`] if the length is negative or there are not enough bytes,
+    /// or any error from [`String::decode`].
+    pub fn decode_prefixed(data: &[u8]) -> DataResult<(Self, usize)> {
+        let (size, position) = read_size(data)?;
+
+        let len = usize::try_from(size.value()).map_err(|_| StringError::InvalidLength)?;
+        let end = position + len;
+
+        if end > data.len() {
+            return Err(StringError::InvalidLength)?;
+        }
+
+        Ok((String::decode(&data[..end])?, end))
+    }
+}
+
 impl<'a> SerDe<'a> for String {
     type Input = &'a [u8];
     type Serialized = DataResult<Vec<u8>>;
@@ -109,9 +161,12 @@ impl<'a> SerDe<'a> for String {
     ///
     /// ## Errors
     ///
-    /// Returns [`StringError::OutOfBoundsLength`] if the length of the string overflows an i32,
+    /// Returns [`StringError::OutOfBoundsEncoding`] if the UTF-8 encoding of the string is greater than 3 times the maximum length,
+    /// [`StringError::OutOfBoundsLength`] if the string is greater than the maximum length, in UTF-16 code units,
     /// or [`VarIntError::EncodeOverflow`] if there is a problem encoding the [`VarInt`].
     fn encode(&self) -> Self::Serialized {
+        check_bounds(&self.data, self.max_size as usize)?;
+
         let len = self
             .data
             .len()
@@ -125,29 +180,38 @@ impl<'a> SerDe<'a> for String {
 
     /// Decodes a String from bytes.
     ///
+    /// The decoded string has the maximum length of 32767,
+    /// use a [`BoundedString`](super::BoundedString) to enforce a smaller one.
+    ///
     /// ## Errors
     ///
-    /// Returns [`StringError::InvalidUTF8`] if the bytes are not valid UTF-8,
-    /// [`StringError::InvalidLength`] if the length of the string is not equal to the length specified by the [`VarInt`]
+    /// Returns [`StringError::InvalidLength`] if the length prefix is truncated or not equal to the length of the string,
+    /// [`StringError::OutOfBoundsEncoding`] if the string is greater than `32767 × 3` bytes,
+    /// [`StringError::InvalidUTF8`] if the bytes are not valid UTF-8,
+    /// [`StringError::OutOfBoundsLength`] if the string is greater than 32767 UTF-16 code units,
     /// or [`VarIntError::EncodeOverflow`] if there is a problem encoding the [`VarInt`].
     fn decode(data: Self::Input) -> Self::Deserialized {
-        let mut position = 0;
-        let size = VarInt::decode(&data[position..])?;
+        let (size, position) = read_size(data)?;
+
+        #[allow(clippy::cast_sign_loss)]
+        if size.value() as usize != data.len() - position {
+            return Err(StringError::InvalidLength)?;
+        }
 
-        position += size.encode()?.len();
+        if data.len() - position > MAX_SIZE as usize * 3 {
+            return Err(StringError::OutOfBoundsEncoding)?;
+        }
 
         let data = std::string::String::from_utf8(data[position..].to_vec())
             .map_err(|_| StringError::InvalidUTF8)?;
 
-        #[allow(clippy::cast_sign_loss)]
-        if size.value() as usize != data.len() {
-            return Err(StringError::InvalidLength)?;
+        if utf16_len(&data) > MAX_SIZE as usize {
+            return Err(StringError::OutOfBoundsLength)?;
         }
 
         Ok(String {
             data,
-            #[allow(clippy::cast_sign_loss)]
-            max_size: size.value() as u32,
+            max_size: MAX_SIZE,
         })
     }
 }
@@ -156,11 +220,12 @@ impl FromStr for String {
     type Err = Errors;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s.len() > MAX_SIZE as usize {
+        let len = utf16_len(s);
+        if len > MAX_SIZE as usize {
             return Err(StringError::OutOfBoundsLength)?;
         }
         #[allow(clippy::cast_possible_truncation)]
-        let len = s.len() as u32;
+        let len = len as u32;
         let mut string = String::new(Some(len))?;
         string.insert(s.to_string())?;
         Ok(string)
@@ -178,7 +243,7 @@ mod test {
     use crate::data_types::{Errors, SerDe};
     use std::str::FromStr;
 
-    use super::{String, StringError};
+    use super::{utf16_len, String, StringError};
 
     #[test]
     fn encode_decode_string_001() {
@@ -372,4 +437,183 @@ mod test {
             panic!("Failed to decode the string");
         }
     }
+
+    #[test]
+    fn decode_prefixed() {
+        let bytes = [vec![0x05], b"Hello world".into()].concat();
+        let (string, len) = String::decode_prefixed(&bytes).unwrap();
+        assert_eq!(string.data, "Hello");
+        assert_eq!(len, 6);
+
+        assert_eq!(
+            String::decode_prefixed(&[0x05, b'H']),
+            Err(Errors::StringError(StringError::InvalidLength))
+        );
+        assert_eq!(
+            String::decode_prefixed(&[]),
+            Err(Errors::StringError(StringError::InvalidLength))
+        );
+        assert_eq!(
+            String::decode_prefixed(&[0x80]),
+            Err(Errors::StringError(StringError::InvalidLength))
+        );
+        assert_eq!(
+            String::decode(&[0x80]),
+            Err(Errors::StringError(StringError::InvalidLength))
+        );
+    }
+
+    fn bounded(max_size: u32) -> String {
+        String::new(Some(max_size)).unwrap()
+    }
+
+    fn prefixed(string: &str) -> Vec<u8> {
+        let len = i32::try_from(string.len()).unwrap();
+        [
+            crate::data_types::VarInt::new(len).encode().unwrap(),
+            string.as_bytes().to_vec(),
+        ]
+        .concat()
+    }
+
+    #[test]
+    fn utf16_length() {
+        assert_eq!(utf16_len(""), 0);
+        assert_eq!(utf16_len("abc"), 3);
+        assert_eq!(utf16_len("é"), 1);
+        assert_eq!(utf16_len("你好"), 2);
+        assert_eq!(utf16_len("🚀"), 2);
+        assert_eq!(utf16_len("a🚀b"), 4);
+    }
+
+    #[test]
+    fn insert_ascii_boundary() {
+        assert!(bounded(16).insert("a".repeat(16)).is_ok());
+        assert_eq!(
+            bounded(16).insert("a".repeat(17)).map(|_| ()),
+            Err(Errors::StringError(StringError::OutOfBoundsLength))
+        );
+    }
+
+    #[test]
+    fn insert_cjk_boundary() {
+        // 16 characters, 48 bytes
+        assert!(bounded(16).insert("你".repeat(16)).is_ok());
+        assert_eq!(
+            bounded(16).insert("你".repeat(17)).map(|_| ()),
+            Err(Errors::StringError(StringError::OutOfBoundsEncoding))
+        );
+    }
+
+    #[test]
+    fn insert_surrogate_pair_boundary() {
+        // 8 emoji are 16 UTF-16 code units, but only 32 bytes
+        assert!(bounded(16).insert("🚀".repeat(8)).is_ok());
+        assert_eq!(
+            bounded(16).insert("🚀".repeat(8) + "a").map(|_| ()),
+            Err(Errors::StringError(StringError::OutOfBoundsLength))
+        );
+        assert_eq!(
+            bounded(15).insert("🚀".repeat(8)).map(|_| ()),
+            Err(Errors::StringError(StringError::OutOfBoundsLength))
+        );
+        assert!(bounded(15).insert("🚀".repeat(7) + "a").is_ok());
+    }
+
+    #[test]
+    fn insert_replaces() {
+        let mut string = bounded(16);
+        string.insert("Hello".into()).unwrap();
+        string.insert("World".into()).unwrap();
+        assert_eq!(string.data, "World");
+    }
+
+    #[test]
+    fn concat_appends() {
+        let mut string = bounded(16);
+        string.insert("Hello, ".into()).unwrap();
+        string.concat("world!".into()).unwrap();
+        assert_eq!(string.data, "Hello, world!");
+    }
+
+    #[test]
+    fn concat_boundary() {
+        let mut string = bounded(16);
+        string.insert("🚀".repeat(7)).unwrap();
+        string.concat("a".into()).unwrap();
+        assert_eq!(
+            string.concat("🚀".into()).map(|_| ()),
+            Err(Errors::StringError(StringError::OutOfBoundsLength))
+        );
+        string.concat("b".into()).unwrap();
+        assert_eq!(
+            string.concat("c".into()).map(|_| ()),
+            Err(Errors::StringError(StringError::OutOfBoundsLength))
+        );
+        assert_eq!(string.data, "🚀".repeat(7) + "ab");
+
+        let mut string = bounded(2);
+        string.concat("🚀".into()).unwrap();
+        assert_eq!(
+            string.concat("a".into()).map(|_| ()),
+            Err(Errors::StringError(StringError::OutOfBoundsLength))
+        );
+    }
+
+    #[test]
+    fn from_str_boundary() {
+        assert!(String::from_str(&"a".repeat(32767)).is_ok());
+        assert!(String::from_str(&"你".repeat(32767)).is_ok());
+        assert!(String::from_str(&("🚀".repeat(16383) + "a")).is_ok());
+        assert_eq!(
+            String::from_str(&"a".repeat(32768)),
+            Err(Errors::StringError(StringError::OutOfBoundsLength))
+        );
+        assert_eq!(
+            String::from_str(&"🚀".repeat(16384)),
+            Err(Errors::StringError(StringError::OutOfBoundsLength))
+        );
+    }
+
+    #[test]
+    fn encode_boundary() {
+        let mut string = String::new(None).unwrap();
+        string.data = "🚀".repeat(16384);
+        assert_eq!(
+            string.encode(),
+            Err(Errors::StringError(StringError::OutOfBoundsLength))
+        );
+
+        let mut string = bounded(20);
+        string.data = "a".repeat(20);
+        assert!(string.encode().is_ok());
+        string.data.push('a');
+        assert_eq!(
+            string.encode(),
+            Err(Errors::StringError(StringError::OutOfBoundsLength))
+        );
+    }
+
+    #[test]
+    fn decode_boundary() {
+        // 32767 characters, 98301 bytes
+        let cjk = "你".repeat(32767);
+        assert_eq!(String::decode(&prefixed(&cjk)).unwrap().data, cjk);
+
+        let emoji = "🚀".repeat(16383) + "a";
+        assert_eq!(String::decode(&prefixed(&emoji)).unwrap().data, emoji);
+
+        assert_eq!(
+            String::decode(&prefixed(&"a".repeat(32768))),
+            Err(Errors::StringError(StringError::OutOfBoundsLength))
+        );
+        assert_eq!(
+            String::decode(&prefixed(&"🚀".repeat(16384))),
+            Err(Errors::StringError(StringError::OutOfBoundsLength))
+        );
+        assert_eq!(
+            String::decode(&prefixed(&("你".repeat(32767) + "a"))),
+            Err(Errors::StringError(StringError::OutOfBoundsEncoding))
+        );
+    }
 }
@@ -1,6 +1,6 @@
 use thiserror::Error;
 
-use crate::data_types::{DataResult, SerDe};
+use crate::data_types::{DataResult, GenericError, SerDe};
 
 static SEGMENT_BITS: i32 = 0x7F;
 static CONTINUE_BIT: i32 = 0x80;
@@ -26,6 +26,17 @@ impl VarInt {
     pub fn value(&self) -> i32 {
         self.0
     }
+
+    /// Converts a length to its [`VarInt`] prefix.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`GenericError::TooLong`] if `len` doesn't fit in an `i32`.
+    pub(crate) fn from_len(len: usize) -> DataResult<Self> {
+        Ok(VarInt(
+            i32::try_from(len).map_err(|_| GenericError::TooLong)?,
+        ))
+    }
 }
 
 impl<'a> SerDe<'a> for VarInt {
@@ -1,3 +1,680 @@
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::data_types::{
+    game_profile::GameProfile, property::Property, Angle, BitSet, Boolean, BoundedString,
+    DataResult, Identifier, Position, SerDe, TagIdentifier, VarInt, VarLong,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Error)]
+pub enum GenericError {
+    #[error("Not enough bytes to decode the value")]
+    UnexpectedEnd,
+    #[error("Array length can't be negative")]
+    NegativeLength,
+    #[error("Array is too long to be encoded")]
+    TooLong,
+    #[error("Unknown enum discriminant")]
+    UnknownVariant,
+}
+
+/// A value with a self-delimiting encoding, that can be read from the start of a buffer.
+///
+/// This is what [`Optional`], [`Array`], [`InferredArray`] and [`Enum`] need from what they hold,
+/// so they can be nested and composed into packet fields without custom code.
+/// It is implemented for the numeric types (big-endian), [`Uuid`] and the data types with a fixed or prefixed length.
+pub trait Codec: Sized {
+    /// Appends the encoded value to `bytes`.
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error if the value can't be encoded.
+    fn write(&self, bytes: &mut Vec<u8>) -> DataResult<()>;
+
+    /// Reads a value from the start of `data`, ignoring anything after it.
+    ///
+    /// Returns the value and the amount of bytes read.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`GenericError::UnexpectedEnd`] if there are not enough bytes, or an error if the value is not valid.
+    fn read(data: &[u8]) -> DataResult<(Self, usize)>;
+}
+
+/// An optional value, prefixed by a [`Boolean`] telling whether it is present.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Optional<X>(pub Option<X>);
+
+/// A list of values, prefixed by its length as a [`VarInt`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Array<X>(pub Vec<X>);
+
+/// A list of values without a length prefix, taking the rest of the packet.
+///
+/// It can only be the last field of a packet, since decoding reads until the end of `data`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct InferredArray<X>(pub Vec<X>);
+
+/// An enum sent as a discriminant, followed by the fields of the variant if it has any.
+///
+/// The discriminant is usually a [`VarInt`], a byte or a [`String`](crate::data_types::String),
+/// see [`Discriminated`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Enum<X>(pub X);
+
+/// An enum that can be sent in an [`Enum`].
+pub trait Discriminated: Sized {
+    /// What identifies the variant on the wire, e.g. [`VarInt`], [`u8`] or [`String`](crate::data_types::String).
+    type Discriminant: Codec;
+
+    fn discriminant(&self) -> Self::Discriminant;
+
+    /// Reads the variant identified by `discriminant`, and its fields from the start of `data` if it has any.
+    ///
+    /// Returns the variant and the amount of bytes read after the discriminant.
+    ///
+    /// ## Errors
+    ///
+    /// Should return [`GenericError::UnknownVariant`] if no variant matches `discriminant`.
+    fn read_variant(discriminant: Self::Discriminant, data: &[u8]) -> DataResult<(Self, usize)>;
+
+    /// Appends the fields of the variant to `bytes`. Does nothing by default, for fieldless enums.
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error if a field can't be encoded.
+    fn write_fields(&self, _bytes: &mut Vec<u8>) -> DataResult<()> {
+        Ok(())
+    }
+}
+
+impl<X: Codec> Codec for Optional<X> {
+    fn write(&self, bytes: &mut Vec<u8>) -> DataResult<()> {
+        bytes.push(Boolean::from(self.0.is_some()).encode());
+
+        match &self.0 {
+            Some(value) => value.write(bytes),
+            None => Ok(()),
+        }
+    }
+
+    fn read(data: &[u8]) -> DataResult<(Self, usize)> {
+        let present = data.first().ok_or(GenericError::UnexpectedEnd)?;
+
+        if bool::from(Boolean::decode(*present)?) {
+            let (value, len) = X::read(&data[1..])?;
+            Ok((Optional(Some(value)), len + 1))
+        } else {
+            Ok((Optional(None), 1))
+        }
+    }
+}
+
+impl<X: Codec> Codec for Array<X> {
+    fn write(&self, bytes: &mut Vec<u8>) -> DataResult<()> {
+        let len = i32::try_from(self.0.len()).map_err(|_| GenericError::TooLong)?;
+        bytes.append(&mut VarInt::new(len).encode()?);
+
+        self.0.iter().try_for_each(|value| value.write(bytes))
+    }
+
+    fn read(data: &[u8]) -> DataResult<(Self, usize)> {
+        let (len, mut position) = VarInt::read(data)?;
+        let len = usize::try_from(len.value()).map_err(|_| GenericError::NegativeLength)?;
+
+        // Every value takes at least a byte, so a bogus length can't allocate more than the packet
+        let mut values = Vec::with_capacity(len.min(data.len() - position));
+        for _ in 0..len {
+            let (value, read) = X::read(&data[position..])?;
+            position += read;
+            values.push(value);
+        }
+
+        Ok((Array(values), position))
+    }
+}
+
+impl<X: Codec> Codec for InferredArray<X> {
+    fn write(&self, bytes: &mut Vec<u8>) -> DataResult<()> {
+        self.0.iter().try_for_each(|value| value.write(bytes))
+    }
+
+    fn read(data: &[u8]) -> DataResult<(Self, usize)> {
+        let mut values = Vec::new();
+        let mut position = 0;
+
+        while position < data.len() {
+            let (value, read) = X::read(&data[position..])?;
+            position += read;
+            values.push(value);
+        }
+
+        Ok((InferredArray(values), position))
+    }
+}
+
+impl<X: Discriminated> Codec for Enum<X> {
+    fn write(&self, bytes: &mut Vec<u8>) -> DataResult<()> {
+        self.0.discriminant().write(bytes)?;
+        self.0.write_fields(bytes)
+    }
+
+    fn read(data: &[u8]) -> DataResult<(Self, usize)> {
+        let (discriminant, position) = X::Discriminant::read(data)?;
+        let (value, len) = X::read_variant(discriminant, &data[position..])?;
+
+        Ok((Enum(value), position + len))
+    }
+}
+
+/// A pair of values sent one after the other, e.g. the key and value of a map entry in an [`Array`].
+impl<A: Codec, B: Codec> Codec for (A, B) {
+    fn write(&self, bytes: &mut Vec<u8>) -> DataResult<()> {
+        self.0.write(bytes)?;
+        self.1.write(bytes)
+    }
+
+    fn read(data: &[u8]) -> DataResult<(Self, usize)> {
+        let (a, position) = A::read(data)?;
+        let (b, len) = B::read(&data[position..])?;
+
+        Ok(((a, b), position + len))
+    }
+}
+
+/// Implements [`Codec`] for a fieldless enum sent as a [`VarInt`].
+macro_rules! varint_enum {
+    ($name:ident { $($variant:ident = $id:literal),* $(,)? }) => {
+        impl $name {
+            #[must_use]
+            pub fn id(self) -> i32 {
+                match self {
+                    $($name::$variant => $id,)*
+                }
+            }
+
+            /// ## Errors
+            ///
+            /// Returns [`GenericError::UnknownVariant`](crate::data_types::GenericError::UnknownVariant) if no variant has this `id`.
+            pub fn from_id(id: i32) -> $crate::data_types::DataResult<Self> {
+                match id {
+                    $($id => Ok($name::$variant),)*
+                    _ => Err($crate::data_types::GenericError::UnknownVariant)?,
+                }
+            }
+        }
+
+        impl $crate::data_types::Codec for $name {
+            fn write(&self, bytes: &mut Vec<u8>) -> $crate::data_types::DataResult<()> {
+                $crate::data_types::VarInt::new(self.id()).write(bytes)
+            }
+
+            fn read(data: &[u8]) -> $crate::data_types::DataResult<(Self, usize)> {
+                let (id, len) = $crate::data_types::VarInt::read(data)?;
+                Ok(($name::from_id(id.value())?, len))
+            }
+        }
+    };
+}
+
+pub(crate) use varint_enum;
+
+/// Implements [`SerDe`] for the generic types, in the streaming form used by [`Identifier`] and others.
+macro_rules! serde_from_codec {
+    ($($name:ident<$x:ident: $bound:ident>),*) => {$(
+        impl<'a, $x: $bound> SerDe<'a> for $name<$x> {
+            type Input = &'a [u8];
+            type Serialized = DataResult<Vec<u8>>;
+            type Deserialized = DataResult<(Self, usize)>;
+
+            /// Encodes the value into bytes, see [`Codec::write`].
+            fn encode(&self) -> Self::Serialized {
+                let mut bytes = Vec::new();
+                self.write(&mut bytes)?;
+                Ok(bytes)
+            }
+
+            /// Decodes the value from the start of `data`, see [`Codec::read`].
+            ///
+            /// Returns the value and the amount of bytes read.
+            fn decode(data: Self::Input) -> Self::Deserialized {
+                Self::read(data)
+            }
+        }
+    )*};
+}
+
+serde_from_codec!(
+    Optional<X: Codec>,
+    Array<X: Codec>,
+    InferredArray<X: Codec>,
+    Enum<X: Discriminated>
+);
+
+/// Implements [`Codec`] for big-endian numbers.
+macro_rules! codec_for_numbers {
+    ($($number:ty),*) => {$(
+        impl Codec for $number {
+            fn write(&self, bytes: &mut Vec<u8>) -> DataResult<()> {
+                bytes.extend_from_slice(&self.to_be_bytes());
+                Ok(())
+            }
+
+            fn read(data: &[u8]) -> DataResult<(Self, usize)> {
+                const SIZE: usize = std::mem::size_of::<$number>();
+
+                let bytes = data
+                    .get(..SIZE)
+                    .ok_or(GenericError::UnexpectedEnd)?
+                    .try_into()
+                    .map_err(|_| GenericError::UnexpectedEnd)?;
+                Ok((<$number>::from_be_bytes(bytes), SIZE))
+            }
+        }
+    )*};
+}
+
+codec_for_numbers!(i8, u8, i16, u16, i32, u32, i64, u64, u128, f32, f64);
+
+/// Implements [`Codec`] for the types whose [`SerDe`] already reads from the start of a buffer.
+macro_rules! codec_from_serde {
+    ($($name:ty),*) => {$(
+        impl Codec for $name {
+            fn write(&self, bytes: &mut Vec<u8>) -> DataResult<()> {
+                bytes.append(&mut self.encode()?);
+                Ok(())
+            }
+
+            fn read(data: &[u8]) -> DataResult<(Self, usize)> {
+                Self::decode(data)
+            }
+        }
+    )*};
+}
+
+codec_from_serde!(Identifier, TagIdentifier, BitSet, Property, GameProfile);
+
+impl<const N: usize> Codec for BoundedString<N> {
+    fn write(&self, bytes: &mut Vec<u8>) -> DataResult<()> {
+        bytes.append(&mut self.encode()?);
+        Ok(())
+    }
+
+    fn read(data: &[u8]) -> DataResult<(Self, usize)> {
+        Self::decode(data)
+    }
+}
+
+impl Codec for crate::data_types::String {
+    fn write(&self, bytes: &mut Vec<u8>) -> DataResult<()> {
+        bytes.append(&mut self.encode()?);
+        Ok(())
+    }
+
+    fn read(data: &[u8]) -> DataResult<(Self, usize)> {
+        Self::decode_prefixed(data)
+    }
+}
+
+/// Returns the amount of bytes of the [`VarInt`] or [`VarLong`] at the start of `data`,
+/// up to its first byte without the continuation bit, or `None` if there is no such byte.
+///
+/// This is not always the length of the re-encoded value, since the Notchian decoder accepts padding like `[0x80, 0x00]`.
+fn var_len(data: &[u8]) -> Option<usize> {
+    data.iter()
+        .position(|byte| byte & 0x80 == 0)
+        .map(|last| last + 1)
+}
+
+impl Codec for VarInt {
+    fn write(&self, bytes: &mut Vec<u8>) -> DataResult<()> {
+        bytes.append(&mut self.encode()?);
+        Ok(())
+    }
+
+    fn read(data: &[u8]) -> DataResult<(Self, usize)> {
+        let Some(len) = var_len(data) else {
+            // Report a value that is too long rather than truncated
+            Self::decode(data)?;
+            return Err(GenericError::UnexpectedEnd)?;
+        };
+
+        Ok((Self::decode(&data[..len])?, len))
+    }
+}
+
+impl Codec for VarLong {
+    fn write(&self, bytes: &mut Vec<u8>) -> DataResult<()> {
+        bytes.append(&mut self.encode()?);
+        Ok(())
+    }
+
+    fn read(data: &[u8]) -> DataResult<(Self, usize)> {
+        let Some(len) = var_len(data) else {
+            // Report a value that is too long rather than truncated
+            Self::decode(data)?;
+            return Err(GenericError::UnexpectedEnd)?;
+        };
+
+        Ok((Self::decode(&data[..len])?, len))
+    }
+}
+
+impl Codec for Boolean {
+    fn write(&self, bytes: &mut Vec<u8>) -> DataResult<()> {
+        bytes.push(self.encode());
+        Ok(())
+    }
+
+    fn read(data: &[u8]) -> DataResult<(Self, usize)> {
+        let (byte, len) = u8::read(data)?;
+        Ok((Boolean::decode(byte)?, len))
+    }
+}
+
+impl Codec for Angle {
+    fn write(&self, bytes: &mut Vec<u8>) -> DataResult<()> {
+        bytes.push(self.encode());
+        Ok(())
+    }
+
+    fn read(data: &[u8]) -> DataResult<(Self, usize)> {
+        let (byte, len) = u8::read(data)?;
+        Ok((Angle::decode(byte), len))
+    }
+}
+
+impl Codec for Position {
+    /// Writes the [`Position`] in the current layout, see [`Position::encode_for`] for older versions.
+    fn write(&self, bytes: &mut Vec<u8>) -> DataResult<()> {
+        self.encode().write(bytes)
+    }
+
+    fn read(data: &[u8]) -> DataResult<(Self, usize)> {
+        let (value, len) = i64::read(data)?;
+        Ok((Position::decode(value)?, len))
+    }
+}
+
+impl Codec for Uuid {
+    fn write(&self, bytes: &mut Vec<u8>) -> DataResult<()> {
+        bytes.extend_from_slice(self.as_bytes());
+        Ok(())
+    }
+
+    fn read(data: &[u8]) -> DataResult<(Self, usize)> {
+        let (value, len) = u128::read(data)?;
+        Ok((Uuid::from_u128(value), len))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use uuid::Uuid;
+
+    use crate::data_types::{
+        DataResult, Errors, Identifier, SerDe, VarInt, VarIntError, VarLong, VarLongError,
+    };
+
+    use super::{Array, Codec, Discriminated, Enum, GenericError, InferredArray, Optional};
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum Hand {
+        Main,
+        Off,
+    }
+
+    impl Discriminated for Hand {
+        type Discriminant = VarInt;
+
+        fn discriminant(&self) -> VarInt {
+            VarInt::new(match self {
+                Hand::Main => 0,
+                Hand::Off => 1,
+            })
+        }
+
+        fn read_variant(discriminant: VarInt, _: &[u8]) -> DataResult<(Self, usize)> {
+            match discriminant.value() {
+                0 => Ok((Hand::Main, 0)),
+                1 => Ok((Hand::Off, 0)),
+                _ => Err(GenericError::UnknownVariant)?,
+            }
+        }
+    }
+
+    /// A byte discriminated enum with fields.
+    #[derive(Debug, Clone, PartialEq)]
+    enum Action {
+        Jump,
+        Move(f32, f32),
+    }
+
+    impl Discriminated for Action {
+        type Discriminant = u8;
+
+        fn discriminant(&self) -> u8 {
+            match self {
+                Action::Jump => 0,
+                Action::Move(..) => 1,
+            }
+        }
+
+        fn read_variant(discriminant: u8, data: &[u8]) -> DataResult<(Self, usize)> {
+            match discriminant {
+                0 => Ok((Action::Jump, 0)),
+                1 => {
+                    let (x, _) = f32::read(data)?;
+                    let (z, _) = f32::read(&data[4..])?;
+                    Ok((Action::Move(x, z), 8))
+                }
+                _ => Err(GenericError::UnknownVariant)?,
+            }
+        }
+
+        fn write_fields(&self, bytes: &mut Vec<u8>) -> DataResult<()> {
+            if let Action::Move(x, z) = self {
+                x.write(bytes)?;
+                z.write(bytes)?;
+            }
+            Ok(())
+        }
+    }
+
+    /// A string discriminated enum.
+    #[derive(Debug, Clone, PartialEq)]
+    enum Mode {
+        Survival,
+        Creative,
+    }
+
+    impl Discriminated for Mode {
+        type Discriminant = crate::data_types::String;
+
+        fn discriminant(&self) -> Self::Discriminant {
+            let name = match self {
+                Mode::Survival => "survival",
+                Mode::Creative => "creative",
+            };
+            crate::data_types::String::from_str(name).unwrap()
+        }
+
+        fn read_variant(discriminant: Self::Discriminant, _: &[u8]) -> DataResult<(Self, usize)> {
+            match discriminant.data.as_str() {
+                "survival" => Ok((Mode::Survival, 0)),
+                "creative" => Ok((Mode::Creative, 0)),
+                _ => Err(GenericError::UnknownVariant)?,
+            }
+        }
+    }
+
+    #[test]
+    fn optional() {
+        let some = Optional(Some(0x1234_u16));
+        assert_eq!(some.encode().unwrap(), [0x01, 0x12, 0x34]);
+        assert_eq!(Optional::decode(&[0x01, 0x12, 0x34]).unwrap(), (some, 3));
+
+        let none = Optional::<u16>(None);
+        assert_eq!(none.encode().unwrap(), [0x00]);
+        assert_eq!(Optional::decode(&[0x00, 0xFF]).unwrap(), (none, 1));
+
+        assert_eq!(
+            Optional::<u16>::decode(&[]),
+            Err(Errors::GenericError(GenericError::UnexpectedEnd))
+        );
+        assert_eq!(
+            Optional::<u16>::decode(&[0x01, 0x12]),
+            Err(Errors::GenericError(GenericError::UnexpectedEnd))
+        );
+        assert!(Optional::<u16>::decode(&[0x02]).is_err());
+    }
+
+    #[test]
+    fn array() {
+        let array = Array(vec![
+            Identifier::from_str("a").unwrap(),
+            Identifier::from_str("b").unwrap(),
+        ]);
+        let bytes = [
+            vec![2],
+            [vec![11], b"minecraft:a".to_vec()].concat(),
+            [vec![11], b"minecraft:b".to_vec()].concat(),
+        ]
+        .concat();
+        assert_eq!(array.encode().unwrap(), bytes);
+        assert_eq!(Array::decode(&bytes).unwrap(), (array, bytes.len()));
+
+        assert_eq!(
+            Array::<u8>::decode(&[0xFF, 0xFF, 0xFF, 0xFF, 0x0F]),
+            Err(Errors::GenericError(GenericError::NegativeLength))
+        );
+        assert_eq!(
+            Array::<u8>::decode(&[0xFF, 0xFF, 0xFF, 0xFF, 0x07, 0x00]),
+            Err(Errors::GenericError(GenericError::UnexpectedEnd))
+        );
+        assert_eq!(
+            Array::<u8>::decode(&[]),
+            Err(Errors::GenericError(GenericError::UnexpectedEnd))
+        );
+        assert_eq!(
+            Array::<u8>::decode(&[0x80]),
+            Err(Errors::GenericError(GenericError::UnexpectedEnd))
+        );
+    }
+
+    #[test]
+    fn var_int() {
+        assert_eq!(VarInt::read(&[0x00, 0xFF]).unwrap(), (VarInt::new(0), 1));
+        assert_eq!(
+            VarInt::read(&[0xDD, 0xC7, 0x01]).unwrap(),
+            (VarInt::new(25565), 3)
+        );
+        // Padded, but still valid
+        assert_eq!(VarInt::read(&[0x80, 0x00]).unwrap(), (VarInt::new(0), 2));
+        assert_eq!(
+            VarInt::read(&[0x81, 0x80, 0x00, 0x05]).unwrap(),
+            (VarInt::new(1), 3)
+        );
+
+        assert_eq!(
+            VarInt::read(&[]),
+            Err(Errors::GenericError(GenericError::UnexpectedEnd))
+        );
+        assert_eq!(
+            VarInt::read(&[0x80]),
+            Err(Errors::GenericError(GenericError::UnexpectedEnd))
+        );
+        assert_eq!(
+            VarInt::read(&[0xFF, 0xFF, 0xFF, 0xFF]),
+            Err(Errors::GenericError(GenericError::UnexpectedEnd))
+        );
+        assert_eq!(
+            VarInt::read(&[0x80, 0x80, 0x80, 0x80, 0x80, 0x00]),
+            Err(Errors::VarIntError(VarIntError::DecodeOverflow))
+        );
+        assert_eq!(
+            VarInt::read(&[0x80; 5]),
+            Err(Errors::VarIntError(VarIntError::DecodeOverflow))
+        );
+    }
+
+    #[test]
+    fn var_long() {
+        assert_eq!(
+            VarLong::read(&[0xFF, 0x01, 0x00]).unwrap(),
+            (VarLong::new(255), 2)
+        );
+        assert_eq!(VarLong::read(&[0x80, 0x00]).unwrap(), (VarLong::new(0), 2));
+
+        assert_eq!(
+            VarLong::read(&[]),
+            Err(Errors::GenericError(GenericError::UnexpectedEnd))
+        );
+        assert_eq!(
+            VarLong::read(&[0x80, 0x80]),
+            Err(Errors::GenericError(GenericError::UnexpectedEnd))
+        );
+        assert_eq!(
+            VarLong::read(&[0x80; 10]),
+            Err(Errors::VarFloatError(VarLongError::DecodeOverflow))
+        );
+    }
+
+    #[test]
+    fn nested() {
+        let value = Array(vec![Optional(Some(Array(vec![1_i8, -1]))), Optional(None)]);
+        let bytes = [0x02, 0x01, 0x02, 0x01, 0xFF, 0x00];
+        assert_eq!(value.encode().unwrap(), bytes);
+        assert_eq!(Array::decode(&bytes).unwrap(), (value, bytes.len()));
+    }
+
+    #[test]
+    fn inferred_array() {
+        let value = InferredArray(vec![Uuid::nil(), Uuid::max()]);
+        let bytes = value.encode().unwrap();
+        assert_eq!(bytes.len(), 32);
+        assert_eq!(InferredArray::decode(&bytes).unwrap(), (value, 32));
+
+        assert_eq!(
+            InferredArray::<u8>::decode(&[]).unwrap(),
+            (InferredArray(vec![]), 0)
+        );
+        assert_eq!(
+            InferredArray::<u16>::decode(&[0x00, 0x01, 0x02]),
+            Err(Errors::GenericError(GenericError::UnexpectedEnd))
+        );
+    }
+
+    #[test]
+    fn var_int_enum() {
+        assert_eq!(Enum(Hand::Off).encode().unwrap(), [0x01]);
+        assert_eq!(Enum::decode(&[0x00]).unwrap(), (Enum(Hand::Main), 1));
+        assert_eq!(
+            Enum::<Hand>::decode(&[0x02]),
+            Err(Errors::GenericError(GenericError::UnknownVariant))
+        );
+    }
+
+    #[test]
+    fn byte_enum() {
+        let value = Enum(Action::Move(1.0, -2.0));
+        let bytes = [0x01, 0x3F, 0x80, 0x00, 0x00, 0xC0, 0x00, 0x00, 0x00];
+        assert_eq!(value.encode().unwrap(), bytes);
+        assert_eq!(Enum::decode(&bytes).unwrap(), (value, 9));
+        assert_eq!(Enum::decode(&[0x00]).unwrap(), (Enum(Action::Jump), 1));
+    }
+
+    #[test]
+    fn string_enum() {
+        let bytes = [vec![8], b"creative".to_vec()].concat();
+        assert_eq!(Enum(Mode::Creative).encode().unwrap(), bytes);
+        assert_eq!(Enum::decode(&bytes).unwrap(), (Enum(Mode::Creative), 9));
+        assert_eq!(
+            Enum::<Mode>::decode(&[4, b'h', b'a', b'r', b'd']),
+            Err(Errors::GenericError(GenericError::UnknownVariant))
+        );
+        assert_eq!(Enum(Mode::Survival).encode().unwrap()[0], 8);
+    }
+}
@@ -1,5 +1,244 @@
+use std::str::FromStr;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use rsa::{Pkcs1v15Sign, RsaPrivateKey, RsaPublicKey};
+use sha1::{Digest, Sha1};
+use thiserror::Error;
+
+use super::{game_profile::GameProfileError, Boolean, BoundedString, DataResult, SerDe};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Error)]
+pub enum SignatureError {
+    #[error("The property is not signed")]
+    Missing,
+    #[error("The signature is not valid base64")]
+    InvalidBase64,
+    #[error("The signature does not match the value")]
+    Mismatch,
+    #[error("The value could not be signed with the given key")]
+    SigningFailed,
+}
+
+/// A property of a [`GameProfile`](super::game_profile::GameProfile), like the player's skin (see [`Property::TEXTURES`]).
+///
+/// The value is usually base64 encoded, and the signature,
+/// when present, is the base64 encoded signature of the value by Mojang.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Property {
-    name: super::String,
-    value: super::String,
-    signature: Option<super::String>,
+    pub name: BoundedString<64>,
+    pub value: BoundedString<32767>,
+    pub signature: Option<BoundedString<1024>>,
+}
+
+impl Property {
+    /// Name of the property holding the player's skin and cape.
+    pub const TEXTURES: &'static str = "textures";
+
+    /// Creates a new [`Property`].
+    ///
+    /// ## Errors
+    ///
+    /// Returns a [`StringError`](super::StringError) if the name is greater than 64 characters,
+    /// the value is greater than 32767 characters, or the signature is greater than 1024 characters.
+    pub fn new(name: &str, value: &str, signature: Option<&str>) -> DataResult<Self> {
+        Ok(Property {
+            name: BoundedString::from_str(name)?,
+            value: BoundedString::from_str(value)?,
+            signature: signature.map(BoundedString::from_str).transpose()?,
+        })
+    }
+
+    /// Checks the signature of the property with the given Yggdrasil public key.
+    ///
+    /// The signature is a base64 encoded `SHA1withRSA` (PKCS#1 v1.5) signature of the value as it is sent,
+    /// that is, of the base64 text and not of the JSON it encodes.
+    ///
+    /// Mojang's key can be loaded from `yggdrasil_session_pubkey.der`
+    /// with [`DecodePublicKey::from_public_key_der`](rsa::pkcs8::DecodePublicKey::from_public_key_der).
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`SignatureError::Missing`] if the property is not signed,
+    /// [`SignatureError::InvalidBase64`] if the signature is not valid base64,
+    /// or [`SignatureError::Mismatch`] if it was not made by `key` over this value.
+    pub fn verify(&self, key: &RsaPublicKey) -> DataResult<()> {
+        let signature = self.signature.as_ref().ok_or(SignatureError::Missing)?;
+        let signature = STANDARD
+            .decode(signature.as_str())
+            .map_err(|_| SignatureError::InvalidBase64)?;
+
+        key.verify(
+            Pkcs1v15Sign::new::<Sha1>(),
+            &Sha1::digest(self.value.as_bytes()),
+            &signature,
+        )
+        .map_err(|_| SignatureError::Mismatch)?;
+
+        Ok(())
+    }
+
+    /// Signs the property with `key`, replacing any previous signature.
+    ///
+    /// Mojang's private key is, of course, not available,
+    /// so this is only useful with keys a proxy or test controls, see [`Property::verify`].
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`SignatureError::SigningFailed`] if the key can't sign,
+    /// or [`StringError::OutOfBoundsLength`](super::StringError::OutOfBoundsLength)
+    /// if the signature is greater than 1024 characters (keys over 6144 bits).
+    pub fn sign(&mut self, key: &RsaPrivateKey) -> DataResult<()> {
+        let signature = key
+            .sign(
+                Pkcs1v15Sign::new::<Sha1>(),
+                &Sha1::digest(self.value.as_bytes()),
+            )
+            .map_err(|_| SignatureError::SigningFailed)?;
+
+        self.signature = Some(BoundedString::new(STANDARD.encode(signature))?);
+
+        Ok(())
+    }
+}
+
+impl<'a> SerDe<'a> for Property {
+    type Input = &'a [u8];
+    type Serialized = DataResult<Vec<u8>>;
+    type Deserialized = DataResult<(Self, usize)>;
+
+    /// Encodes a [`Property`] as its name, its value and its optional signature.
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error if there is a problem encoding any of the strings.
+    fn encode(&self) -> Self::Serialized {
+        let mut bytes = self.name.encode()?;
+        bytes.append(&mut self.value.encode()?);
+        bytes.push(Boolean::from(self.signature.is_some()).encode());
+
+        if let Some(signature) = &self.signature {
+            bytes.append(&mut signature.encode()?);
+        }
+
+        Ok(bytes)
+    }
+
+    /// Decodes a [`Property`] from the start of `data`.
+    ///
+    /// Returns the property and the amount of bytes read.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`GameProfileError::UnexpectedEnd`] if there are not enough bytes,
+    /// or an error if any of the strings or the boolean are not valid.
+    fn decode(data: Self::Input) -> Self::Deserialized {
+        let (name, mut position) = BoundedString::decode(data)?;
+
+        let (value, len) = BoundedString::decode(&data[position..])?;
+        position += len;
+
+        let has_signature = data.get(position).ok_or(GameProfileError::UnexpectedEnd)?;
+        position += 1;
+
+        let signature = if Boolean::decode(*has_signature)?.into() {
+            let (signature, len) = BoundedString::decode(&data[position..])?;
+            position += len;
+            Some(signature)
+        } else {
+            None
+        };
+
+        Ok((
+            Property {
+                name,
+                value,
+                signature,
+            },
+            position,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rsa::RsaPublicKey;
+
+    use crate::{
+        data_types::{game_profile::GameProfileError, Errors, SerDe},
+        test_fixtures::{KEY, OTHER_KEY},
+    };
+
+    use super::{Property, SignatureError};
+
+    #[test]
+    fn encode_decode() {
+        let property = Property::new("textures", "e30=", Some("c2lnbmF0dXJl")).unwrap();
+        let bytes = property.encode().unwrap();
+        assert_eq!(
+            bytes,
+            [
+                b"\x08textures".to_vec(),
+                b"\x04e30=".to_vec(),
+                vec![0x01],
+                b"\x0cc2lnbmF0dXJl".to_vec()
+            ]
+            .concat()
+        );
+        assert_eq!(Property::decode(&bytes).unwrap(), (property, bytes.len()));
+    }
+
+    #[test]
+    fn unsigned() {
+        let property = Property::new("textures", "e30=", None).unwrap();
+        let bytes = property.encode().unwrap();
+        assert_eq!(bytes.last(), Some(&0x00));
+        assert_eq!(Property::decode(&bytes).unwrap(), (property, bytes.len()));
+    }
+
+    #[test]
+    fn truncated() {
+        assert_eq!(
+            Property::decode(b"\x08textures\x04e30="),
+            Err(Errors::GameProfileError(GameProfileError::UnexpectedEnd))
+        );
+    }
+
+    #[test]
+    fn sign_verify() {
+        let public = RsaPublicKey::from(KEY.private_key());
+        let mut property = Property::new("textures", "e30=", None).unwrap();
+        assert_eq!(
+            property.verify(&public),
+            Err(Errors::SignatureError(SignatureError::Missing))
+        );
+
+        property.sign(KEY.private_key()).unwrap();
+        assert_eq!(property.signature.as_ref().unwrap().len(), 172);
+        assert_eq!(property.verify(&public), Ok(()));
+    }
+
+    #[test]
+    fn tampered() {
+        let public = RsaPublicKey::from(KEY.private_key());
+        let mut property = Property::new("textures", "e30=", None).unwrap();
+        property.sign(KEY.private_key()).unwrap();
+
+        let mut tampered = property.clone();
+        tampered.value = "e30K".parse().unwrap();
+        assert_eq!(
+            tampered.verify(&public),
+            Err(Errors::SignatureError(SignatureError::Mismatch))
+        );
+
+        assert_eq!(
+            property.verify(&RsaPublicKey::from(OTHER_KEY.private_key())),
+            Err(Errors::SignatureError(SignatureError::Mismatch))
+        );
+
+        property.signature = Some("not base64!".parse().unwrap());
+        assert_eq!(
+            property.verify(&public),
+            Err(Errors::SignatureError(SignatureError::InvalidBase64))
+        );
+    }
 }
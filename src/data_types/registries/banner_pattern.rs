@@ -0,0 +1,54 @@
+use quartz_nbt::NbtCompound;
+
+use super::RegistryElement;
+use crate::data_types::{
+    registry_data::{field, identifier},
+    DataResult, Identifier,
+};
+
+/// An element of the `minecraft:banner_pattern` registry.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BannerPattern {
+    /// Texture of the pattern, e.g. `minecraft:creeper`.
+    pub asset_id: Identifier,
+    /// Translation key of the pattern name, e.g. `block.minecraft.banner.creeper`.
+    pub translation_key: String,
+}
+
+impl RegistryElement for BannerPattern {
+    const REGISTRY: &'static str = "minecraft:banner_pattern";
+
+    fn from_nbt(nbt: &NbtCompound) -> DataResult<Self> {
+        Ok(BannerPattern {
+            asset_id: identifier(nbt, "asset_id")?,
+            translation_key: field::<&str>(nbt, "translation_key")?.to_owned(),
+        })
+    }
+
+    fn to_nbt(&self) -> NbtCompound {
+        let mut nbt = NbtCompound::new();
+
+        nbt.insert("asset_id", self.asset_id.to_string());
+        nbt.insert("translation_key", self.translation_key.as_str());
+
+        nbt
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use crate::data_types::Identifier;
+
+    use super::{BannerPattern, RegistryElement};
+
+    #[test]
+    fn round_trip() {
+        let pattern = BannerPattern {
+            asset_id: Identifier::from_str("minecraft:creeper").unwrap(),
+            translation_key: "block.minecraft.banner.creeper".to_owned(),
+        };
+        assert_eq!(BannerPattern::from_nbt(&pattern.to_nbt()).unwrap(), pattern);
+    }
+}
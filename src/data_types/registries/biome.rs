@@ -0,0 +1,215 @@
+use quartz_nbt::{NbtCompound, NbtTag};
+
+use super::RegistryElement;
+use crate::data_types::{
+    registry_data::{field, number, optional_field, RegistryDataError},
+    DataResult,
+};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TemperatureModifier {
+    #[default]
+    None,
+    /// Makes some parts of the biome colder, like in frozen oceans.
+    Frozen,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GrassColorModifier {
+    #[default]
+    None,
+    DarkForest,
+    Swamp,
+}
+
+/// The colors, sounds and particles of a [`Biome`].
+///
+/// The sounds, music and particle are kept as NBT, since the client only forwards them to the game.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BiomeEffects {
+    pub fog_color: i32,
+    pub water_color: i32,
+    pub water_fog_color: i32,
+    pub sky_color: i32,
+    pub foliage_color: Option<i32>,
+    pub grass_color: Option<i32>,
+    pub grass_color_modifier: GrassColorModifier,
+    pub particle: Option<NbtTag>,
+    pub ambient_sound: Option<NbtTag>,
+    pub mood_sound: Option<NbtTag>,
+    pub additions_sound: Option<NbtTag>,
+    pub music: Option<NbtTag>,
+}
+
+/// An element of the `minecraft:worldgen/biome` registry.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Biome {
+    pub has_precipitation: bool,
+    pub temperature: f32,
+    pub temperature_modifier: TemperatureModifier,
+    pub downfall: f32,
+    pub effects: BiomeEffects,
+}
+
+/// The raw optional fields of [`BiomeEffects`].
+const RAW_EFFECTS: [&str; 5] = [
+    "particle",
+    "ambient_sound",
+    "mood_sound",
+    "additions_sound",
+    "music",
+];
+
+impl BiomeEffects {
+    fn raw(&mut self, key: &str) -> &mut Option<NbtTag> {
+        match key {
+            "particle" => &mut self.particle,
+            "ambient_sound" => &mut self.ambient_sound,
+            "mood_sound" => &mut self.mood_sound,
+            "additions_sound" => &mut self.additions_sound,
+            _ => &mut self.music,
+        }
+    }
+
+    fn from_nbt(nbt: &NbtCompound) -> DataResult<Self> {
+        let grass_color_modifier = match optional_field::<&str>(nbt, "grass_color_modifier")? {
+            None | Some("none") => GrassColorModifier::None,
+            Some("dark_forest") => GrassColorModifier::DarkForest,
+            Some("swamp") => GrassColorModifier::Swamp,
+            Some(_) => return Err(RegistryDataError::InvalidField)?,
+        };
+
+        let mut effects = BiomeEffects {
+            fog_color: field(nbt, "fog_color")?,
+            water_color: field(nbt, "water_color")?,
+            water_fog_color: field(nbt, "water_fog_color")?,
+            sky_color: field(nbt, "sky_color")?,
+            foliage_color: optional_field(nbt, "foliage_color")?,
+            grass_color: optional_field(nbt, "grass_color")?,
+            grass_color_modifier,
+            ..BiomeEffects::default()
+        };
+
+        for key in RAW_EFFECTS {
+            *effects.raw(key) = nbt.inner().get(key).cloned();
+        }
+
+        Ok(effects)
+    }
+
+    fn to_nbt(&self) -> NbtCompound {
+        let mut nbt = NbtCompound::new();
+
+        nbt.insert("fog_color", self.fog_color);
+        nbt.insert("water_color", self.water_color);
+        nbt.insert("water_fog_color", self.water_fog_color);
+        nbt.insert("sky_color", self.sky_color);
+        if let Some(foliage_color) = self.foliage_color {
+            nbt.insert("foliage_color", foliage_color);
+        }
+        if let Some(grass_color) = self.grass_color {
+            nbt.insert("grass_color", grass_color);
+        }
+        match self.grass_color_modifier {
+            GrassColorModifier::None => {}
+            GrassColorModifier::DarkForest => nbt.insert("grass_color_modifier", "dark_forest"),
+            GrassColorModifier::Swamp => nbt.insert("grass_color_modifier", "swamp"),
+        }
+
+        let mut effects = self.clone();
+        for key in RAW_EFFECTS {
+            if let Some(tag) = effects.raw(key).take() {
+                nbt.insert(key, tag);
+            }
+        }
+
+        nbt
+    }
+}
+
+impl RegistryElement for Biome {
+    const REGISTRY: &'static str = "minecraft:worldgen/biome";
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn from_nbt(nbt: &NbtCompound) -> DataResult<Self> {
+        let temperature_modifier = match optional_field::<&str>(nbt, "temperature_modifier")? {
+            None | Some("none") => TemperatureModifier::None,
+            Some("frozen") => TemperatureModifier::Frozen,
+            Some(_) => return Err(RegistryDataError::InvalidField)?,
+        };
+
+        Ok(Biome {
+            has_precipitation: field(nbt, "has_precipitation")?,
+            temperature: number(nbt, "temperature")? as f32,
+            temperature_modifier,
+            downfall: number(nbt, "downfall")? as f32,
+            effects: BiomeEffects::from_nbt(field(nbt, "effects")?)?,
+        })
+    }
+
+    fn to_nbt(&self) -> NbtCompound {
+        let mut nbt = NbtCompound::new();
+
+        nbt.insert("has_precipitation", self.has_precipitation);
+        nbt.insert("temperature", self.temperature);
+        if self.temperature_modifier == TemperatureModifier::Frozen {
+            nbt.insert("temperature_modifier", "frozen");
+        }
+        nbt.insert("downfall", self.downfall);
+        nbt.insert("effects", self.effects.to_nbt());
+
+        nbt
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use quartz_nbt::compound;
+
+    use super::{Biome, GrassColorModifier, RegistryElement, TemperatureModifier};
+
+    #[test]
+    fn from_nbt() {
+        let nbt = compound! {
+            "has_precipitation": true,
+            "temperature": 0.8_f32,
+            "downfall": 0.9_f64,
+            "effects": {
+                "fog_color": 12_638_463,
+                "water_color": 6_388_580,
+                "water_fog_color": 2_302_743,
+                "sky_color": 7_907_327,
+                "grass_color_modifier": "swamp",
+                "mood_sound": { "sound": "minecraft:ambient.cave", "tick_delay": 6000 }
+            }
+        };
+
+        let biome = Biome::from_nbt(&nbt).unwrap();
+        assert!(biome.has_precipitation);
+        assert!((biome.downfall - 0.9).abs() < f32::EPSILON);
+        assert_eq!(biome.temperature_modifier, TemperatureModifier::None);
+        assert_eq!(
+            biome.effects.grass_color_modifier,
+            GrassColorModifier::Swamp
+        );
+        assert_eq!(biome.effects.foliage_color, None);
+        assert!(biome.effects.mood_sound.is_some());
+
+        assert_eq!(Biome::from_nbt(&biome.to_nbt()).unwrap(), biome);
+    }
+
+    #[test]
+    fn frozen() {
+        let biome = Biome {
+            temperature: -0.5,
+            temperature_modifier: TemperatureModifier::Frozen,
+            ..Biome::default()
+        };
+        let nbt = biome.to_nbt();
+        assert_eq!(
+            nbt.get::<_, &str>("temperature_modifier").unwrap(),
+            "frozen"
+        );
+        assert_eq!(Biome::from_nbt(&nbt).unwrap(), biome);
+    }
+}
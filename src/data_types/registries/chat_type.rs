@@ -0,0 +1,125 @@
+use quartz_nbt::{NbtCompound, NbtList, NbtTag};
+
+use super::RegistryElement;
+use crate::data_types::{
+    registry_data::{field, optional_field, RegistryDataError},
+    DataResult,
+};
+
+/// How a chat message is formatted, as a translation and the parameters passed to it.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChatDecoration {
+    /// Translation key, e.g. `chat.type.text`.
+    pub translation_key: String,
+    /// Parameters of the translation, among `sender`, `target` and `content`.
+    pub parameters: Vec<String>,
+    /// Style applied to the translated text.
+    pub style: Option<NbtCompound>,
+}
+
+impl ChatDecoration {
+    fn from_nbt(nbt: &NbtCompound) -> DataResult<Self> {
+        let parameters = field::<&NbtList>(nbt, "parameters")?
+            .iter()
+            .map(|parameter| match parameter {
+                NbtTag::String(parameter) => Ok(parameter.clone()),
+                _ => Err(RegistryDataError::InvalidField),
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(ChatDecoration {
+            translation_key: field::<&str>(nbt, "translation_key")?.to_owned(),
+            parameters,
+            style: optional_field::<&NbtCompound>(nbt, "style")?.cloned(),
+        })
+    }
+
+    fn to_nbt(&self) -> NbtCompound {
+        let mut nbt = NbtCompound::new();
+
+        nbt.insert("translation_key", self.translation_key.as_str());
+        nbt.insert(
+            "parameters",
+            NbtList::from(
+                self.parameters
+                    .iter()
+                    .map(|parameter| NbtTag::String(parameter.clone()))
+                    .collect::<Vec<_>>(),
+            ),
+        );
+        if let Some(style) = &self.style {
+            nbt.insert("style", style.clone());
+        }
+
+        nbt
+    }
+}
+
+/// An element of the `minecraft:chat_type` registry.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChatType {
+    pub chat: ChatDecoration,
+    pub narration: ChatDecoration,
+}
+
+impl RegistryElement for ChatType {
+    const REGISTRY: &'static str = "minecraft:chat_type";
+
+    fn from_nbt(nbt: &NbtCompound) -> DataResult<Self> {
+        Ok(ChatType {
+            chat: ChatDecoration::from_nbt(field(nbt, "chat")?)?,
+            narration: ChatDecoration::from_nbt(field(nbt, "narration")?)?,
+        })
+    }
+
+    fn to_nbt(&self) -> NbtCompound {
+        let mut nbt = NbtCompound::new();
+
+        nbt.insert("chat", self.chat.to_nbt());
+        nbt.insert("narration", self.narration.to_nbt());
+
+        nbt
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use quartz_nbt::compound;
+
+    use crate::data_types::{registry_data::RegistryDataError, Errors};
+
+    use super::{ChatType, RegistryElement};
+
+    #[test]
+    fn from_nbt() {
+        let nbt = compound! {
+            "chat": {
+                "translation_key": "commands.message.display.incoming",
+                "parameters": ["sender", "content"],
+                "style": { "color": "gray", "italic": true }
+            },
+            "narration": {
+                "translation_key": "chat.type.text.narrate",
+                "parameters": ["sender", "content"]
+            }
+        };
+
+        let chat_type = ChatType::from_nbt(&nbt).unwrap();
+        assert_eq!(chat_type.chat.parameters, ["sender", "content"]);
+        assert!(chat_type.chat.style.is_some());
+        assert_eq!(chat_type.narration.style, None);
+        assert_eq!(ChatType::from_nbt(&chat_type.to_nbt()).unwrap(), chat_type);
+    }
+
+    #[test]
+    fn invalid_parameter() {
+        let nbt = compound! {
+            "chat": { "translation_key": "chat.type.text", "parameters": [1, 2] },
+            "narration": { "translation_key": "chat.type.text.narrate", "parameters": [] }
+        };
+        assert_eq!(
+            ChatType::from_nbt(&nbt),
+            Err(Errors::RegistryDataError(RegistryDataError::InvalidField))
+        );
+    }
+}
@@ -0,0 +1,170 @@
+use quartz_nbt::NbtCompound;
+
+use super::RegistryElement;
+use crate::data_types::{
+    registry_data::{field, number, optional_field, RegistryDataError},
+    DataResult,
+};
+
+/// Whether the damage scales with the difficulty.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DamageScaling {
+    Never,
+    #[default]
+    WhenCausedByLivingNonPlayer,
+    Always,
+}
+
+/// The sound played when taking the damage.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DamageEffects {
+    #[default]
+    Hurt,
+    Thorns,
+    Drowning,
+    Burning,
+    Poking,
+    Freezing,
+}
+
+/// How the death message is picked.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DeathMessageType {
+    #[default]
+    Default,
+    FallVariants,
+    IntentionalGameDesign,
+}
+
+/// Maps a field enum to its name in the registry, and back.
+macro_rules! names {
+    ($name:ident { $($variant:ident => $value:literal),* $(,)? }) => {
+        impl $name {
+            fn name(self) -> &'static str {
+                match self {
+                    $($name::$variant => $value),*
+                }
+            }
+
+            fn from_name(name: &str) -> DataResult<Self> {
+                match name {
+                    $($value => Ok($name::$variant),)*
+                    _ => Err(RegistryDataError::InvalidField)?,
+                }
+            }
+        }
+    };
+}
+
+names!(DamageScaling {
+    Never => "never",
+    WhenCausedByLivingNonPlayer => "when_caused_by_living_non_player",
+    Always => "always",
+});
+
+names!(DamageEffects {
+    Hurt => "hurt",
+    Thorns => "thorns",
+    Drowning => "drowning",
+    Burning => "burning",
+    Poking => "poking",
+    Freezing => "freezing",
+});
+
+names!(DeathMessageType {
+    Default => "default",
+    FallVariants => "fall_variants",
+    IntentionalGameDesign => "intentional_game_design",
+});
+
+/// An element of the `minecraft:damage_type` registry.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DamageType {
+    /// Used in the death message translation, e.g. `death.attack.<message_id>`.
+    pub message_id: String,
+    pub scaling: DamageScaling,
+    /// Hunger exhaustion caused by the damage.
+    pub exhaustion: f32,
+    pub effects: DamageEffects,
+    pub death_message_type: DeathMessageType,
+}
+
+impl RegistryElement for DamageType {
+    const REGISTRY: &'static str = "minecraft:damage_type";
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn from_nbt(nbt: &NbtCompound) -> DataResult<Self> {
+        Ok(DamageType {
+            message_id: field::<&str>(nbt, "message_id")?.to_owned(),
+            scaling: DamageScaling::from_name(field(nbt, "scaling")?)?,
+            exhaustion: number(nbt, "exhaustion")? as f32,
+            effects: optional_field(nbt, "effects")?
+                .map_or(Ok(DamageEffects::default()), DamageEffects::from_name)?,
+            death_message_type: optional_field(nbt, "death_message_type")?
+                .map_or(Ok(DeathMessageType::default()), DeathMessageType::from_name)?,
+        })
+    }
+
+    fn to_nbt(&self) -> NbtCompound {
+        let mut nbt = NbtCompound::new();
+
+        nbt.insert("message_id", self.message_id.as_str());
+        nbt.insert("scaling", self.scaling.name());
+        nbt.insert("exhaustion", self.exhaustion);
+        if self.effects != DamageEffects::default() {
+            nbt.insert("effects", self.effects.name());
+        }
+        if self.death_message_type != DeathMessageType::default() {
+            nbt.insert("death_message_type", self.death_message_type.name());
+        }
+
+        nbt
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use quartz_nbt::compound;
+
+    use crate::data_types::{registry_data::RegistryDataError, Errors};
+
+    use super::{DamageEffects, DamageScaling, DamageType, DeathMessageType, RegistryElement};
+
+    #[test]
+    fn from_nbt() {
+        let nbt = compound! {
+            "message_id": "fall",
+            "scaling": "when_caused_by_living_non_player",
+            "exhaustion": 0.0_f32,
+            "death_message_type": "fall_variants"
+        };
+
+        let damage = DamageType::from_nbt(&nbt).unwrap();
+        assert_eq!(damage.scaling, DamageScaling::WhenCausedByLivingNonPlayer);
+        assert_eq!(damage.effects, DamageEffects::Hurt);
+        assert_eq!(damage.death_message_type, DeathMessageType::FallVariants);
+        assert_eq!(DamageType::from_nbt(&damage.to_nbt()).unwrap(), damage);
+
+        let burning = DamageType {
+            message_id: "inFire".to_owned(),
+            scaling: DamageScaling::Always,
+            exhaustion: 0.1,
+            effects: DamageEffects::Burning,
+            death_message_type: DeathMessageType::Default,
+        };
+        assert_eq!(DamageType::from_nbt(&burning.to_nbt()).unwrap(), burning);
+    }
+
+    #[test]
+    fn unknown_scaling() {
+        let nbt = compound! {
+            "message_id": "fall",
+            "scaling": "sometimes",
+            "exhaustion": 0.0_f32
+        };
+        assert_eq!(
+            DamageType::from_nbt(&nbt),
+            Err(Errors::RegistryDataError(RegistryDataError::InvalidField))
+        );
+    }
+}
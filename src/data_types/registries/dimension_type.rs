@@ -0,0 +1,304 @@
+use quartz_nbt::{NbtCompound, NbtTag};
+
+use super::RegistryElement;
+use crate::data_types::{
+    registry_data::{field, identifier, number, optional_field, RegistryDataError},
+    DataResult, Identifier, TagIdentifier,
+};
+
+/// The lowest `min_y` of a dimension.
+pub const MIN_Y: i32 = -2032;
+/// The highest block a dimension can reach, `min_y + height` can't exceed it plus one.
+pub const MAX_Y: i32 = 2031;
+
+/// An integer picked by the game, as used by [`DimensionType::monster_spawn_light_level`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntProvider {
+    Constant(i32),
+    /// A uniformly distributed integer, both bounds included.
+    Uniform {
+        min_inclusive: i32,
+        max_inclusive: i32,
+    },
+}
+
+/// An element of the `minecraft:dimension_type` registry.
+#[derive(Debug, Clone, PartialEq)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct DimensionType {
+    /// Fixes the time of day, in ticks.
+    pub fixed_time: Option<i64>,
+    pub has_skylight: bool,
+    pub has_ceiling: bool,
+    pub ultrawarm: bool,
+    pub natural: bool,
+    pub coordinate_scale: f64,
+    pub bed_works: bool,
+    pub respawn_anchor_works: bool,
+    /// Lowest Y level, a multiple of 16.
+    pub min_y: i32,
+    /// Number of blocks, a multiple of 16.
+    pub height: i32,
+    /// Maximum height for portals and chorus fruits, up to `height`.
+    pub logical_height: i32,
+    /// Blocks that burn forever in this dimension.
+    pub infiniburn: TagIdentifier,
+    /// Sky and fog effects, e.g. `minecraft:the_nether`.
+    pub effects: Identifier,
+    pub ambient_light: f32,
+    pub piglin_safe: bool,
+    pub has_raids: bool,
+    pub monster_spawn_light_level: IntProvider,
+    pub monster_spawn_block_light_limit: i32,
+}
+
+impl DimensionType {
+    /// Checks the vertical bounds of the dimension, like the game does when loading it.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`RegistryDataError::InvalidMinY`] if `min_y` is not a multiple of 16 in `[-2032, 2016]`,
+    /// [`RegistryDataError::InvalidHeight`] if `height` is not a multiple of 16 in `[16, 4064]`,
+    /// or if the dimension goes above Y 2031,
+    /// or [`RegistryDataError::InvalidLogicalHeight`] if `logical_height` is greater than `height`.
+    pub fn validate(&self) -> DataResult<()> {
+        if self.min_y % 16 != 0 || !(MIN_Y..=MAX_Y - 15).contains(&self.min_y) {
+            return Err(RegistryDataError::InvalidMinY)?;
+        }
+
+        if self.height % 16 != 0
+            || !(16..=MAX_Y - MIN_Y + 1).contains(&self.height)
+            || self.min_y + self.height > MAX_Y + 1
+        {
+            return Err(RegistryDataError::InvalidHeight)?;
+        }
+
+        if self.logical_height > self.height {
+            return Err(RegistryDataError::InvalidLogicalHeight)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl RegistryElement for DimensionType {
+    const REGISTRY: &'static str = "minecraft:dimension_type";
+
+    /// Reads a dimension type, and [validates](DimensionType::validate) it.
+    #[allow(clippy::cast_possible_truncation)]
+    fn from_nbt(nbt: &NbtCompound) -> DataResult<Self> {
+        let monster_spawn_light_level = match nbt.inner().get("monster_spawn_light_level") {
+            Some(NbtTag::Int(value)) => IntProvider::Constant(*value),
+            Some(NbtTag::Compound(provider)) => {
+                // Up to 1.20.4 the bounds are nested in a value compound, 1.20.5 flattens them
+                let bounds = optional_field::<&NbtCompound>(provider, "value")?.unwrap_or(provider);
+                IntProvider::Uniform {
+                    min_inclusive: field(bounds, "min_inclusive")?,
+                    max_inclusive: field(bounds, "max_inclusive")?,
+                }
+            }
+            Some(_) => return Err(RegistryDataError::InvalidField)?,
+            None => return Err(RegistryDataError::MissingField)?,
+        };
+
+        let infiniburn = field::<&str>(nbt, "infiniburn")?
+            .parse()
+            .map_err(|_| RegistryDataError::InvalidField)?;
+
+        let dimension = DimensionType {
+            fixed_time: optional_field(nbt, "fixed_time")?,
+            has_skylight: field(nbt, "has_skylight")?,
+            has_ceiling: field(nbt, "has_ceiling")?,
+            ultrawarm: field(nbt, "ultrawarm")?,
+            natural: field(nbt, "natural")?,
+            coordinate_scale: number(nbt, "coordinate_scale")?,
+            bed_works: field(nbt, "bed_works")?,
+            respawn_anchor_works: field(nbt, "respawn_anchor_works")?,
+            min_y: field(nbt, "min_y")?,
+            height: field(nbt, "height")?,
+            logical_height: field(nbt, "logical_height")?,
+            infiniburn,
+            effects: identifier(nbt, "effects")?,
+            ambient_light: number(nbt, "ambient_light")? as f32,
+            piglin_safe: field(nbt, "piglin_safe")?,
+            has_raids: field(nbt, "has_raids")?,
+            monster_spawn_light_level,
+            monster_spawn_block_light_limit: field(nbt, "monster_spawn_block_light_limit")?,
+        };
+
+        dimension.validate()?;
+
+        Ok(dimension)
+    }
+
+    fn to_nbt(&self) -> NbtCompound {
+        let mut nbt = NbtCompound::new();
+
+        if let Some(fixed_time) = self.fixed_time {
+            nbt.insert("fixed_time", fixed_time);
+        }
+        nbt.insert("has_skylight", self.has_skylight);
+        nbt.insert("has_ceiling", self.has_ceiling);
+        nbt.insert("ultrawarm", self.ultrawarm);
+        nbt.insert("natural", self.natural);
+        nbt.insert("coordinate_scale", self.coordinate_scale);
+        nbt.insert("bed_works", self.bed_works);
+        nbt.insert("respawn_anchor_works", self.respawn_anchor_works);
+        nbt.insert("min_y", self.min_y);
+        nbt.insert("height", self.height);
+        nbt.insert("logical_height", self.logical_height);
+        nbt.insert("infiniburn", self.infiniburn.to_string());
+        nbt.insert("effects", self.effects.to_string());
+        nbt.insert("ambient_light", self.ambient_light);
+        nbt.insert("piglin_safe", self.piglin_safe);
+        nbt.insert("has_raids", self.has_raids);
+        match self.monster_spawn_light_level {
+            IntProvider::Constant(value) => nbt.insert("monster_spawn_light_level", value),
+            IntProvider::Uniform {
+                min_inclusive,
+                max_inclusive,
+            } => {
+                let mut bounds = NbtCompound::new();
+                bounds.insert("min_inclusive", min_inclusive);
+                bounds.insert("max_inclusive", max_inclusive);
+
+                let mut provider = NbtCompound::new();
+                provider.insert("type", "minecraft:uniform");
+                provider.insert("value", bounds);
+                nbt.insert("monster_spawn_light_level", provider);
+            }
+        }
+        nbt.insert(
+            "monster_spawn_block_light_limit",
+            self.monster_spawn_block_light_limit,
+        );
+
+        nbt
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use quartz_nbt::{compound, NbtTag};
+
+    use crate::data_types::{registry_data::RegistryDataError, Errors, Identifier, TagIdentifier};
+
+    use super::{DimensionType, IntProvider, RegistryElement};
+
+    fn overworld() -> DimensionType {
+        DimensionType {
+            fixed_time: None,
+            has_skylight: true,
+            has_ceiling: false,
+            ultrawarm: false,
+            natural: true,
+            coordinate_scale: 1.0,
+            bed_works: true,
+            respawn_anchor_works: false,
+            min_y: -64,
+            height: 384,
+            logical_height: 384,
+            infiniburn: TagIdentifier::from_str("#minecraft:infiniburn_overworld").unwrap(),
+            effects: Identifier::from_str("minecraft:overworld").unwrap(),
+            ambient_light: 0.0,
+            piglin_safe: false,
+            has_raids: true,
+            monster_spawn_light_level: IntProvider::Uniform {
+                min_inclusive: 0,
+                max_inclusive: 7,
+            },
+            monster_spawn_block_light_limit: 0,
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let nbt = overworld().to_nbt();
+        assert_eq!(DimensionType::from_nbt(&nbt).unwrap(), overworld());
+
+        let mut nether = overworld();
+        nether.fixed_time = Some(18000);
+        nether.monster_spawn_light_level = IntProvider::Constant(7);
+        assert_eq!(DimensionType::from_nbt(&nether.to_nbt()).unwrap(), nether);
+    }
+
+    #[test]
+    fn nested_provider() {
+        let nbt = overworld().to_nbt();
+        assert_eq!(
+            nbt.inner().get("monster_spawn_light_level"),
+            Some(&NbtTag::Compound(compound! {
+                "type": "minecraft:uniform",
+                "value": { "min_inclusive": 0, "max_inclusive": 7 }
+            }))
+        );
+    }
+
+    #[test]
+    fn flat_provider() {
+        let mut nbt = overworld().to_nbt();
+        nbt.insert(
+            "monster_spawn_light_level",
+            compound! {
+                "type": "minecraft:uniform",
+                "min_inclusive": 0,
+                "max_inclusive": 7
+            },
+        );
+        assert_eq!(DimensionType::from_nbt(&nbt).unwrap(), overworld());
+    }
+
+    #[test]
+    fn validation() {
+        let invalid = |edit: fn(&mut DimensionType)| {
+            let mut dimension = overworld();
+            edit(&mut dimension);
+            DimensionType::from_nbt(&dimension.to_nbt()).unwrap_err()
+        };
+
+        assert_eq!(
+            invalid(|dimension| dimension.min_y = -60),
+            Errors::RegistryDataError(RegistryDataError::InvalidMinY)
+        );
+        assert_eq!(
+            invalid(|dimension| dimension.min_y = -2048),
+            Errors::RegistryDataError(RegistryDataError::InvalidMinY)
+        );
+        assert_eq!(
+            invalid(|dimension| dimension.height = 380),
+            Errors::RegistryDataError(RegistryDataError::InvalidHeight)
+        );
+        assert_eq!(
+            invalid(|dimension| dimension.height = 0),
+            Errors::RegistryDataError(RegistryDataError::InvalidHeight)
+        );
+        assert_eq!(
+            invalid(|dimension| {
+                dimension.min_y = 2000;
+                dimension.height = 48;
+            }),
+            Errors::RegistryDataError(RegistryDataError::InvalidHeight)
+        );
+        assert_eq!(
+            invalid(|dimension| dimension.logical_height = 400),
+            Errors::RegistryDataError(RegistryDataError::InvalidLogicalHeight)
+        );
+
+        let mut tallest = overworld();
+        tallest.min_y = -2032;
+        tallest.height = 4064;
+        assert_eq!(tallest.validate(), Ok(()));
+    }
+
+    #[test]
+    fn missing_field() {
+        let mut nbt = overworld().to_nbt();
+        nbt.inner_mut().remove("height");
+        assert_eq!(
+            DimensionType::from_nbt(&nbt),
+            Err(Errors::RegistryDataError(RegistryDataError::MissingField))
+        );
+    }
+}
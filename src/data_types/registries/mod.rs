@@ -0,0 +1,34 @@
+pub mod banner_pattern;
+pub mod biome;
+pub mod chat_type;
+pub mod damage_type;
+pub mod dimension_type;
+pub mod trim;
+
+pub use banner_pattern::*;
+pub use biome::*;
+pub use chat_type::*;
+pub use damage_type::*;
+pub use dimension_type::*;
+pub use trim::*;
+
+use quartz_nbt::NbtCompound;
+
+use super::DataResult;
+
+/// An element of a synchronized registry, sent in [`RegistryData`](super::registry_data::RegistryData).
+///
+/// The typed structs only cover the fields the client reads over the network.
+pub trait RegistryElement: Sized {
+    /// Identifier of the registry, e.g. `minecraft:dimension_type`.
+    const REGISTRY: &'static str;
+
+    /// Reads the element from its NBT data.
+    ///
+    /// ## Errors
+    ///
+    /// Returns a [`RegistryDataError`](super::registry_data::RegistryDataError) if a field is missing or not valid.
+    fn from_nbt(nbt: &NbtCompound) -> DataResult<Self>;
+
+    fn to_nbt(&self) -> NbtCompound;
+}
@@ -0,0 +1,167 @@
+use std::collections::BTreeMap;
+
+use quartz_nbt::{NbtCompound, NbtTag};
+
+use super::RegistryElement;
+use crate::data_types::{
+    registry_data::{field, identifier, number, optional_field, RegistryDataError},
+    DataResult, Identifier,
+};
+
+/// An element of the `minecraft:trim_pattern` registry.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrimPattern {
+    pub asset_id: Identifier,
+    /// Smithing template item applying the pattern.
+    pub template_item: Identifier,
+    /// Name of the pattern, as a text component.
+    pub description: NbtTag,
+    /// Whether the pattern is drawn over the armor as a decal.
+    pub decal: bool,
+}
+
+impl RegistryElement for TrimPattern {
+    const REGISTRY: &'static str = "minecraft:trim_pattern";
+
+    fn from_nbt(nbt: &NbtCompound) -> DataResult<Self> {
+        Ok(TrimPattern {
+            asset_id: identifier(nbt, "asset_id")?,
+            template_item: identifier(nbt, "template_item")?,
+            description: description(nbt)?,
+            decal: optional_field(nbt, "decal")?.unwrap_or(false),
+        })
+    }
+
+    fn to_nbt(&self) -> NbtCompound {
+        let mut nbt = NbtCompound::new();
+
+        nbt.insert("asset_id", self.asset_id.to_string());
+        nbt.insert("template_item", self.template_item.to_string());
+        nbt.insert("description", self.description.clone());
+        nbt.insert("decal", self.decal);
+
+        nbt
+    }
+}
+
+/// An element of the `minecraft:trim_material` registry.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrimMaterial {
+    /// Suffix of the trim textures, e.g. `gold`.
+    pub asset_name: String,
+    /// Item used to apply the material.
+    pub ingredient: Identifier,
+    pub item_model_index: f32,
+    /// Asset names used instead of `asset_name` on the given armor materials.
+    pub override_armor_materials: BTreeMap<String, String>,
+    /// Name of the material, as a text component.
+    pub description: NbtTag,
+}
+
+impl RegistryElement for TrimMaterial {
+    const REGISTRY: &'static str = "minecraft:trim_material";
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn from_nbt(nbt: &NbtCompound) -> DataResult<Self> {
+        let override_armor_materials =
+            match optional_field::<&NbtCompound>(nbt, "override_armor_materials")? {
+                Some(overrides) => overrides
+                    .inner()
+                    .iter()
+                    .map(|(material, asset_name)| match asset_name {
+                        NbtTag::String(asset_name) => Ok((material.clone(), asset_name.clone())),
+                        _ => Err(RegistryDataError::InvalidField),
+                    })
+                    .collect::<Result<_, _>>()?,
+                None => BTreeMap::new(),
+            };
+
+        Ok(TrimMaterial {
+            asset_name: field::<&str>(nbt, "asset_name")?.to_owned(),
+            ingredient: identifier(nbt, "ingredient")?,
+            item_model_index: number(nbt, "item_model_index")? as f32,
+            override_armor_materials,
+            description: description(nbt)?,
+        })
+    }
+
+    fn to_nbt(&self) -> NbtCompound {
+        let mut nbt = NbtCompound::new();
+
+        nbt.insert("asset_name", self.asset_name.as_str());
+        nbt.insert("ingredient", self.ingredient.to_string());
+        nbt.insert("item_model_index", self.item_model_index);
+        if !self.override_armor_materials.is_empty() {
+            let mut overrides = NbtCompound::new();
+            for (material, asset_name) in &self.override_armor_materials {
+                overrides.insert(material.as_str(), asset_name.as_str());
+            }
+            nbt.insert("override_armor_materials", overrides);
+        }
+        nbt.insert("description", self.description.clone());
+
+        nbt
+    }
+}
+
+/// Reads a text component, which is either a string or a compound.
+fn description(nbt: &NbtCompound) -> DataResult<NbtTag> {
+    match nbt.inner().get("description") {
+        Some(tag @ (NbtTag::String(_) | NbtTag::Compound(_))) => Ok(tag.clone()),
+        Some(_) => Err(RegistryDataError::InvalidField)?,
+        None => Err(RegistryDataError::MissingField)?,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use quartz_nbt::{compound, NbtTag};
+
+    use crate::data_types::{registry_data::RegistryDataError, Errors, Identifier};
+
+    use super::{RegistryElement, TrimMaterial, TrimPattern};
+
+    #[test]
+    fn pattern() {
+        let nbt = compound! {
+            "asset_id": "minecraft:coast",
+            "template_item": "minecraft:coast_armor_trim_smithing_template",
+            "description": { "translate": "trim_pattern.minecraft.coast" }
+        };
+
+        let pattern = TrimPattern::from_nbt(&nbt).unwrap();
+        assert!(!pattern.decal);
+        assert_eq!(TrimPattern::from_nbt(&pattern.to_nbt()).unwrap(), pattern);
+    }
+
+    #[test]
+    fn material() {
+        let nbt = compound! {
+            "asset_name": "gold",
+            "ingredient": "minecraft:gold_ingot",
+            "item_model_index": 0.6_f32,
+            "override_armor_materials": { "gold": "gold_darker" },
+            "description": { "translate": "trim_material.minecraft.gold", "color": "#DEB12D" }
+        };
+
+        let material = TrimMaterial::from_nbt(&nbt).unwrap();
+        assert_eq!(
+            material.ingredient,
+            Identifier::from_str("minecraft:gold_ingot").unwrap()
+        );
+        assert_eq!(material.override_armor_materials["gold"], "gold_darker");
+        assert_eq!(
+            TrimMaterial::from_nbt(&material.to_nbt()).unwrap(),
+            material
+        );
+
+        let mut nbt = material.to_nbt();
+        nbt.insert("description", NbtTag::Int(0));
+        assert_eq!(
+            TrimMaterial::from_nbt(&nbt),
+            Err(Errors::RegistryDataError(RegistryDataError::InvalidField))
+        );
+    }
+}
@@ -0,0 +1,411 @@
+use std::str::FromStr;
+
+use quartz_nbt::{NbtCompound, NbtList, NbtTag};
+use thiserror::Error;
+
+use super::{
+    registries::RegistryElement, registry_data_entry::Entry, Array, Codec, DataResult, Identifier,
+    Nbt, SerDe,
+};
+use crate::values::Version;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Error)]
+pub enum RegistryDataError {
+    #[error("A required registry field is missing")]
+    MissingField,
+    #[error("A registry field has the wrong type or an invalid value")]
+    InvalidField,
+    #[error("The single NBT form needs the data of every entry")]
+    MissingData,
+    #[error("Registry data is only sent during configuration, since 23w31a")]
+    UnsupportedVersion,
+    #[error("min_y must be a multiple of 16 between -2032 and 2016")]
+    InvalidMinY,
+    #[error(
+        "height must be a multiple of 16 between 16 and 4064, and min_y + height can't exceed 2032"
+    )]
+    InvalidHeight,
+    #[error("logical_height can't exceed height")]
+    InvalidLogicalHeight,
+}
+
+/// The two layouts of the Registry Data packet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegistryDataForm {
+    /// 1.20.2 to 1.20.4: a single NBT compound holding every registry, with numeric ids and the data of every entry.
+    SingleNbt,
+    /// 1.20.5 and later: one packet per registry, with a list of entries whose data is optional.
+    EntryList,
+}
+
+impl RegistryDataForm {
+    /// Returns the form used by `version`, or `None` before 23w31a, when there was no configuration state.
+    ///
+    /// No [`Version`] is recent enough for [`RegistryDataForm::EntryList`] yet, so it has to be chosen explicitly.
+    #[must_use]
+    pub fn for_version(version: Version) -> Option<Self> {
+        (version >= Version::Snapshot23W31A).then_some(RegistryDataForm::SingleNbt)
+    }
+}
+
+/// The entries of a registry, e.g. `minecraft:dimension_type`.
+///
+/// In the [`RegistryDataForm::EntryList`] form, each packet holds one [`RegistryData`] (see its [`SerDe`] impl),
+/// while in the [`RegistryDataForm::SingleNbt`] form, one packet holds all of them
+/// (see [`RegistryData::encode_single_nbt`]).
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegistryData {
+    pub registry_id: Identifier,
+    pub entries: Vec<Entry>,
+}
+
+impl RegistryData {
+    #[must_use]
+    pub fn new(registry_id: Identifier, entries: Vec<Entry>) -> Self {
+        RegistryData {
+            registry_id,
+            entries,
+        }
+    }
+
+    /// Creates the registry of `T` from typed elements, keeping their order.
+    ///
+    /// ## Errors
+    ///
+    /// Returns an [`IdentifierError`](super::IdentifierError) if [`RegistryElement::REGISTRY`] is not valid.
+    pub fn from_elements<'a, T: RegistryElement + 'a>(
+        elements: impl IntoIterator<Item = (Identifier, &'a T)>,
+    ) -> DataResult<Self> {
+        Ok(RegistryData {
+            registry_id: Identifier::from_str(T::REGISTRY)?,
+            entries: elements
+                .into_iter()
+                .map(|(id, element)| Entry::from_element(id, element))
+                .collect(),
+        })
+    }
+
+    /// Returns the entry `id`.
+    #[must_use]
+    pub fn entry(&self, id: &Identifier) -> Option<&Entry> {
+        self.entries.iter().find(|entry| &entry.id == id)
+    }
+
+    /// Returns the numeric id of the entry `id`, which is its index in the registry.
+    #[must_use]
+    pub fn network_id(&self, id: &Identifier) -> Option<usize> {
+        self.entries.iter().position(|entry| &entry.id == id)
+    }
+
+    /// Converts the registry to its compound in the [`RegistryDataForm::SingleNbt`] form:
+    /// `{ type: <registry id>, value: [{ name, id, element }] }`.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`RegistryDataError::MissingData`] if an entry has no data,
+    /// or [`RegistryDataError::InvalidField`] if there are more than [`i32::MAX`] entries.
+    pub fn to_single_nbt(&self) -> DataResult<NbtCompound> {
+        let mut value = NbtList::with_capacity(self.entries.len());
+
+        for (id, entry) in self.entries.iter().enumerate() {
+            let mut element = NbtCompound::new();
+            element.insert("name", entry.id.to_string());
+            element.insert(
+                "id",
+                i32::try_from(id).map_err(|_| RegistryDataError::InvalidField)?,
+            );
+            element.insert(
+                "element",
+                entry.data.clone().ok_or(RegistryDataError::MissingData)?,
+            );
+            value.push(element);
+        }
+
+        let mut registry = NbtCompound::new();
+        registry.insert("type", self.registry_id.to_string());
+        registry.insert("value", value);
+
+        Ok(registry)
+    }
+
+    /// Reads a registry from its compound in the [`RegistryDataForm::SingleNbt`] form, sorting the entries by id.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`RegistryDataError::MissingField`] or [`RegistryDataError::InvalidField`] if the compound is malformed.
+    pub fn from_single_nbt(registry: &NbtCompound) -> DataResult<Self> {
+        let registry_id = Identifier::from_str(field::<&str>(registry, "type")?)?;
+        let value = field::<&NbtList>(registry, "value")?;
+
+        let mut entries = value
+            .iter()
+            .map(|element| {
+                let NbtTag::Compound(element) = element else {
+                    return Err(RegistryDataError::InvalidField)?;
+                };
+
+                Ok((
+                    field::<i32>(element, "id")?,
+                    Entry::new(
+                        Identifier::from_str(field::<&str>(element, "name")?)?,
+                        Some(field::<&NbtCompound>(element, "element")?.clone()),
+                    ),
+                ))
+            })
+            .collect::<DataResult<Vec<_>>>()?;
+
+        entries.sort_by_key(|(id, _)| *id);
+
+        Ok(RegistryData {
+            registry_id,
+            entries: entries.into_iter().map(|(_, entry)| entry).collect(),
+        })
+    }
+
+    /// Encodes the body of a Registry Data packet in the [`RegistryDataForm::SingleNbt`] form,
+    /// a compound with every registry keyed by its id.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`RegistryDataError::UnsupportedVersion`] before 23w31a,
+    /// or any error from [`RegistryData::to_single_nbt`].
+    pub fn encode_single_nbt(registries: &[RegistryData], version: Version) -> DataResult<Vec<u8>> {
+        if RegistryDataForm::for_version(version).is_none() {
+            return Err(RegistryDataError::UnsupportedVersion)?;
+        }
+
+        let mut codec = NbtCompound::new();
+        for registry in registries {
+            codec.insert(registry.registry_id.to_string(), registry.to_single_nbt()?);
+        }
+
+        Nbt::from(codec).encode(version)
+    }
+
+    /// Decodes the body of a Registry Data packet in the [`RegistryDataForm::SingleNbt`] form.
+    ///
+    /// Returns the registries, sorted by id, and the amount of bytes read.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`RegistryDataError::UnsupportedVersion`] before 23w31a,
+    /// [`RegistryDataError::MissingField`] if there is no NBT,
+    /// or any error from [`Nbt::decode`] and [`RegistryData::from_single_nbt`].
+    pub fn decode_single_nbt(data: &[u8], version: Version) -> DataResult<(Vec<Self>, usize)> {
+        if RegistryDataForm::for_version(version).is_none() {
+            return Err(RegistryDataError::UnsupportedVersion)?;
+        }
+
+        let (nbt, len) = Nbt::decode(data, version)?;
+        let codec = nbt
+            .into_compound()?
+            .ok_or(RegistryDataError::MissingField)?;
+
+        let mut registries = codec
+            .inner()
+            .values()
+            .map(|registry| match registry {
+                NbtTag::Compound(registry) => RegistryData::from_single_nbt(registry),
+                _ => Err(RegistryDataError::InvalidField)?,
+            })
+            .collect::<DataResult<Vec<_>>>()?;
+
+        registries.sort_by(|a, b| a.registry_id.cmp(&b.registry_id));
+
+        Ok((registries, len))
+    }
+}
+
+impl<'a> SerDe<'a> for RegistryData {
+    type Input = &'a [u8];
+    type Serialized = DataResult<Vec<u8>>;
+    type Deserialized = DataResult<(Self, usize)>;
+
+    /// Encodes the body of a Registry Data packet in the [`RegistryDataForm::EntryList`] form:
+    /// the registry id, then the entries as an [`Array`].
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error if the id or an entry can't be encoded.
+    fn encode(&self) -> Self::Serialized {
+        let mut bytes = self.registry_id.encode()?;
+        Array(self.entries.clone()).write(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// Decodes the body of a Registry Data packet in the [`RegistryDataForm::EntryList`] form.
+    ///
+    /// Returns the registry and the amount of bytes read.
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error if the id or an entry is not valid.
+    fn decode(data: Self::Input) -> Self::Deserialized {
+        let (registry_id, position) = Identifier::decode(data)?;
+        let (Array(entries), len) = Array::read(&data[position..])?;
+
+        Ok((
+            RegistryData {
+                registry_id,
+                entries,
+            },
+            position + len,
+        ))
+    }
+}
+
+impl Codec for Entry {
+    fn write(&self, bytes: &mut Vec<u8>) -> DataResult<()> {
+        bytes.append(&mut self.encode()?);
+        Ok(())
+    }
+
+    fn read(data: &[u8]) -> DataResult<(Self, usize)> {
+        Self::decode(data)
+    }
+}
+
+/// Reads the field `key` of a compound.
+///
+/// ## Errors
+///
+/// Returns [`RegistryDataError::MissingField`] if there is no such field,
+/// or [`RegistryDataError::InvalidField`] if it is not a `T`.
+pub(crate) fn field<'a, T: TryFrom<&'a NbtTag>>(nbt: &'a NbtCompound, key: &str) -> DataResult<T> {
+    let tag = nbt
+        .inner()
+        .get(key)
+        .ok_or(RegistryDataError::MissingField)?;
+
+    Ok(T::try_from(tag).map_err(|_| RegistryDataError::InvalidField)?)
+}
+
+/// Reads the field `key` of a compound if it is present.
+///
+/// ## Errors
+///
+/// Returns [`RegistryDataError::InvalidField`] if the field is not a `T`.
+pub(crate) fn optional_field<'a, T: TryFrom<&'a NbtTag>>(
+    nbt: &'a NbtCompound,
+    key: &str,
+) -> DataResult<Option<T>> {
+    nbt.inner()
+        .get(key)
+        .map(|tag| Ok(T::try_from(tag).map_err(|_| RegistryDataError::InvalidField)?))
+        .transpose()
+}
+
+/// Reads a numeric field of any type as a [`f64`], since vanilla data is not strict about it.
+///
+/// ## Errors
+///
+/// See [`field`].
+pub(crate) fn number(nbt: &NbtCompound, key: &str) -> DataResult<f64> {
+    match nbt.inner().get(key) {
+        Some(NbtTag::Byte(value)) => Ok(f64::from(*value)),
+        Some(NbtTag::Short(value)) => Ok(f64::from(*value)),
+        Some(NbtTag::Int(value)) => Ok(f64::from(*value)),
+        #[allow(clippy::cast_precision_loss)]
+        Some(NbtTag::Long(value)) => Ok(*value as f64),
+        Some(NbtTag::Float(value)) => Ok(f64::from(*value)),
+        Some(NbtTag::Double(value)) => Ok(*value),
+        Some(_) => Err(RegistryDataError::InvalidField)?,
+        None => Err(RegistryDataError::MissingField)?,
+    }
+}
+
+/// Reads a field holding an [`Identifier`] as a string.
+///
+/// ## Errors
+///
+/// See [`field`], or an [`IdentifierError`](super::IdentifierError) if the identifier is not valid.
+pub(crate) fn identifier(nbt: &NbtCompound, key: &str) -> DataResult<Identifier> {
+    Ok(Identifier::from_str(field::<&str>(nbt, key)?)?)
+}
+
+#[cfg(test)]
+mod test {
+    use quartz_nbt::compound;
+
+    use crate::{
+        data_types::{registry_data_entry::Entry, Errors, SerDe},
+        test_fixtures::id,
+        values::Version,
+    };
+
+    use super::{RegistryData, RegistryDataError, RegistryDataForm};
+
+    fn biomes() -> RegistryData {
+        RegistryData::new(
+            id("worldgen/biome"),
+            vec![
+                Entry::new(id("plains"), Some(compound! { "downfall": 0.4_f32 })),
+                Entry::new(id("desert"), Some(compound! { "downfall": 0.0_f32 })),
+            ],
+        )
+    }
+
+    #[test]
+    fn form() {
+        assert_eq!(RegistryDataForm::for_version(Version::Release1_20_1), None);
+        assert_eq!(
+            RegistryDataForm::for_version(Version::Release1_20_2),
+            Some(RegistryDataForm::SingleNbt)
+        );
+    }
+
+    #[test]
+    fn entry_list() {
+        let mut registry = biomes();
+        registry.entries[1].data = None;
+
+        let bytes = registry.encode().unwrap();
+        assert_eq!(&bytes[..25], b"\x18minecraft:worldgen/biome");
+        assert_eq!(bytes[25], 2);
+        assert_eq!(
+            RegistryData::decode(&bytes).unwrap(),
+            (registry, bytes.len())
+        );
+    }
+
+    #[test]
+    fn single_nbt() {
+        let registries = vec![
+            biomes(),
+            RegistryData::new(
+                id("chat_type"),
+                vec![Entry::new(id("chat"), Some(compound! { "chat": "x" }))],
+            ),
+        ];
+
+        let bytes = RegistryData::encode_single_nbt(&registries, Version::Release1_20_4).unwrap();
+        assert_eq!(bytes[0], 0x0A);
+
+        let (decoded, len) =
+            RegistryData::decode_single_nbt(&bytes, Version::Release1_20_4).unwrap();
+        assert_eq!(len, bytes.len());
+        // Sorted by registry id
+        assert_eq!(decoded, vec![registries[1].clone(), registries[0].clone()]);
+        assert_eq!(decoded[1].network_id(&id("desert")), Some(1));
+    }
+
+    #[test]
+    fn single_nbt_errors() {
+        let mut registry = biomes();
+        registry.entries[0].data = None;
+        assert_eq!(
+            RegistryData::encode_single_nbt(&[registry], Version::Release1_20_4),
+            Err(Errors::RegistryDataError(RegistryDataError::MissingData))
+        );
+        assert_eq!(
+            RegistryData::encode_single_nbt(&[biomes()], Version::Release1_20_1),
+            Err(Errors::RegistryDataError(
+                RegistryDataError::UnsupportedVersion
+            ))
+        );
+        assert_eq!(
+            RegistryData::from_single_nbt(&compound! { "type": "minecraft:chat_type" }),
+            Err(Errors::RegistryDataError(RegistryDataError::MissingField))
+        );
+    }
+}
@@ -1,6 +1,132 @@
-use super::Identifier;
+use quartz_nbt::NbtCompound;
 
+use super::{
+    registries::RegistryElement, registry_data::RegistryDataError, Boolean, Codec, DataResult,
+    Identifier, Nbt, SerDe,
+};
+use crate::values::Version;
+
+/// An entry of a registry, sent by [`RegistryData`](super::registry_data::RegistryData).
+///
+/// Since 1.20.5, `data` can be omitted when the client already has the entry from a known pack.
+#[derive(Debug, Clone, PartialEq)]
 pub struct Entry {
-    id: Identifier,
-    data: Option<quartz_nbt::NbtCompound>,
+    pub id: Identifier,
+    pub data: Option<NbtCompound>,
+}
+
+impl Entry {
+    #[must_use]
+    pub fn new(id: Identifier, data: Option<NbtCompound>) -> Self {
+        Entry { id, data }
+    }
+
+    /// Creates an entry holding a typed registry element.
+    #[must_use]
+    pub fn from_element<T: RegistryElement>(id: Identifier, element: &T) -> Self {
+        Entry {
+            id,
+            data: Some(element.to_nbt()),
+        }
+    }
+
+    /// Reads the data of the entry as a typed registry element, if it is present.
+    ///
+    /// ## Errors
+    ///
+    /// Returns a [`RegistryDataError`] if the data is not a valid `T`.
+    pub fn element<T: RegistryElement>(&self) -> DataResult<Option<T>> {
+        self.data.as_ref().map(T::from_nbt).transpose()
+    }
+}
+
+/// Entry NBT is always nameless, since the entry list form only exists after 23w31a.
+const NAMELESS: Version = Version::Snapshot23W31A;
+
+impl<'a> SerDe<'a> for Entry {
+    type Input = &'a [u8];
+    type Serialized = DataResult<Vec<u8>>;
+    type Deserialized = DataResult<(Self, usize)>;
+
+    /// Encodes an [`Entry`] as its id, then its data as an optional nameless NBT compound.
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error if the id or the NBT can't be encoded.
+    fn encode(&self) -> Self::Serialized {
+        let mut bytes = self.id.encode()?;
+        Boolean::from(self.data.is_some()).write(&mut bytes)?;
+
+        if let Some(data) = &self.data {
+            bytes.append(&mut Nbt::from(data.clone()).encode(NAMELESS)?);
+        }
+
+        Ok(bytes)
+    }
+
+    /// Decodes an [`Entry`] from the start of `data`.
+    ///
+    /// Returns the entry and the amount of bytes read.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`RegistryDataError::InvalidField`] if the data is not a compound,
+    /// or an error if the id, the boolean or the NBT are not valid.
+    fn decode(data: Self::Input) -> Self::Deserialized {
+        let (id, mut position) = Identifier::decode(data)?;
+        let (has_data, len) = Boolean::read(&data[position..])?;
+        position += len;
+
+        let nbt = if bool::from(has_data) {
+            let (nbt, len) = Nbt::decode(&data[position..], NAMELESS)?;
+            position += len;
+            Some(
+                nbt.into_compound()?
+                    .ok_or(RegistryDataError::InvalidField)?,
+            )
+        } else {
+            None
+        };
+
+        Ok((Entry { id, data: nbt }, position))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use quartz_nbt::{compound, NbtCompound};
+
+    use crate::data_types::{Identifier, SerDe};
+
+    use super::Entry;
+
+    #[test]
+    fn encode_decode() {
+        let entry = Entry::new(
+            Identifier::from_str("minecraft:plains").unwrap(),
+            Some(compound! { "downfall": 0.4_f32 }),
+        );
+        let bytes = entry.encode().unwrap();
+        assert_eq!(&bytes[..17], b"\x10minecraft:plains");
+        assert_eq!(bytes[17], 0x01);
+        assert_eq!(bytes[18], 0x0A);
+        assert_eq!(Entry::decode(&bytes).unwrap(), (entry, bytes.len()));
+    }
+
+    #[test]
+    fn without_data() {
+        let entry = Entry::new(Identifier::from_str("minecraft:plains").unwrap(), None);
+        let bytes = entry.encode().unwrap();
+        assert_eq!(bytes.last(), Some(&0x00));
+        assert_eq!(Entry::decode(&bytes).unwrap(), (entry.clone(), bytes.len()));
+        assert_eq!(
+            Entry::new(entry.id.clone(), Some(NbtCompound::new()))
+                .encode()
+                .unwrap()
+                .len(),
+            bytes.len() + 2
+        );
+    }
 }
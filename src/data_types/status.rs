@@ -0,0 +1,347 @@
+use std::fmt::Display;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::{Map, Value};
+use thiserror::Error;
+use uuid::Uuid;
+
+use super::DataResult;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Error)]
+pub enum StatusError {
+    #[error("The status response is not valid JSON")]
+    InvalidJson,
+    #[error("The favicon must be a base64 PNG data URI")]
+    InvalidFavicon,
+    #[error("The favicon must be 64×64 pixels")]
+    InvalidFaviconSize,
+}
+
+/// The response to a Status Request, sent as JSON in the Status Response packet.
+///
+/// ```json
+/// {
+///     "version": { "name": "1.20.4", "protocol": 765 },
+///     "players": { "max": 20, "online": 1, "sample": [{ "name": "Notch", "id": "069a79f4-44e9-4726-a5be-fca90e38aaf5" }] },
+///     "description": { "text": "A Minecraft Server" },
+///     "favicon": "data:image/png;base64,...",
+///     "enforcesSecureChat": true
+/// }
+/// ```
+///
+/// Fields added by mods, like `forgeData`, are kept in [`Status::extensions`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Status {
+    pub version: StatusVersion,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub players: Option<Players>,
+    /// The message of the day, as a text component.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<Value>,
+    /// `None` when parsing a favicon that is not valid, since servers send all sorts of images.
+    #[serde(
+        default,
+        deserialize_with = "lenient_favicon",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub favicon: Option<Favicon>,
+    /// Since 1.19.1.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enforces_secure_chat: Option<bool>,
+    /// Only used from 1.19 to 1.19.2.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previews_chat: Option<bool>,
+    /// Any other field, e.g. `forgeData` or `modinfo`.
+    #[serde(flatten)]
+    pub extensions: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatusVersion {
+    /// Shown by the client when the protocol doesn't match, e.g. `1.20.4`.
+    pub name: String,
+    pub protocol: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Players {
+    pub max: i32,
+    pub online: i32,
+    /// Shown when hovering the player count.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sample: Vec<PlayerSample>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlayerSample {
+    pub name: String,
+    pub id: Uuid,
+}
+
+/// The icon of a server, a 64×64 PNG sent as a base64 data URI.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Favicon {
+    png: Vec<u8>,
+}
+
+impl StatusVersion {
+    #[must_use]
+    pub fn new(name: &str, protocol: i32) -> Self {
+        StatusVersion {
+            name: name.to_owned(),
+            protocol,
+        }
+    }
+}
+
+impl Favicon {
+    /// Width and height of a favicon, in pixels.
+    pub const SIZE: u32 = 64;
+
+    const PREFIX: &'static str = "data:image/png;base64,";
+    const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+
+    /// Creates a favicon from the bytes of a PNG image.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`StatusError::InvalidFavicon`] if `png` doesn't start with a PNG header,
+    /// or [`StatusError::InvalidFaviconSize`] if the image is not 64×64.
+    pub fn from_png(png: Vec<u8>) -> DataResult<Self> {
+        if png.len() < 24 || png[..8] != Self::PNG_SIGNATURE || &png[12..16] != b"IHDR" {
+            return Err(StatusError::InvalidFavicon)?;
+        }
+
+        let width = u32::from_be_bytes([png[16], png[17], png[18], png[19]]);
+        let height = u32::from_be_bytes([png[20], png[21], png[22], png[23]]);
+        if width != Self::SIZE || height != Self::SIZE {
+            return Err(StatusError::InvalidFaviconSize)?;
+        }
+
+        Ok(Favicon { png })
+    }
+
+    #[must_use]
+    pub fn png(&self) -> &[u8] {
+        &self.png
+    }
+}
+
+impl TryFrom<String> for Favicon {
+    type Error = StatusError;
+
+    /// Parses a `data:image/png;base64,` URI. Line breaks, sent by some older servers, are ignored.
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let data = value
+            .strip_prefix(Self::PREFIX)
+            .ok_or(StatusError::InvalidFavicon)?
+            .replace(['\n', '\r'], "");
+        let png = STANDARD
+            .decode(data)
+            .map_err(|_| StatusError::InvalidFavicon)?;
+
+        Favicon::from_png(png).map_err(|error| match error {
+            super::Errors::StatusError(error) => error,
+            _ => StatusError::InvalidFavicon,
+        })
+    }
+}
+
+/// Deserializes a favicon, ignoring it if it is not valid rather than failing the whole status.
+fn lenient_favicon<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Favicon>, D::Error> {
+    let uri = Option::<String>::deserialize(deserializer)?;
+    Ok(uri.and_then(|uri| Favicon::try_from(uri).ok()))
+}
+
+impl From<Favicon> for String {
+    fn from(value: Favicon) -> Self {
+        value.to_string()
+    }
+}
+
+impl Display for Favicon {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", Self::PREFIX, STANDARD.encode(&self.png))
+    }
+}
+
+impl Status {
+    /// Creates a status without a player sample, favicon or extensions.
+    #[must_use]
+    pub fn new(version: StatusVersion, max: i32, online: i32, motd: &str) -> Self {
+        Status {
+            version,
+            players: Some(Players {
+                max,
+                online,
+                sample: Vec::new(),
+            }),
+            description: Some(Value::from(motd)),
+            favicon: None,
+            enforces_secure_chat: None,
+            previews_chat: None,
+            extensions: Map::new(),
+        }
+    }
+
+    /// Returns the description as plain text, concatenating the `text` of every component and ignoring styles.
+    #[must_use]
+    pub fn description_text(&self) -> String {
+        fn flatten(component: &Value, text: &mut String) {
+            match component {
+                Value::String(string) => text.push_str(string),
+                Value::Array(components) => components
+                    .iter()
+                    .for_each(|component| flatten(component, text)),
+                Value::Object(component) => {
+                    if let Some(string) = component.get("text").and_then(Value::as_str) {
+                        text.push_str(string);
+                    }
+                    if let Some(extra) = component.get("extra") {
+                        flatten(extra, text);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let mut text = String::new();
+        if let Some(description) = &self.description {
+            flatten(description, &mut text);
+        }
+        text
+    }
+
+    /// Parses the JSON of a Status Response.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`StatusError::InvalidJson`] if `json` is not a valid status.
+    /// A favicon that is not valid is dropped instead.
+    pub fn decode(json: &str) -> DataResult<Self> {
+        Ok(serde_json::from_str(json).map_err(|_| StatusError::InvalidJson)?)
+    }
+
+    /// Serializes the status to the JSON of a Status Response.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`StatusError::InvalidJson`] if the status can't be serialized.
+    pub fn encode(&self) -> DataResult<String> {
+        Ok(serde_json::to_string(self).map_err(|_| StatusError::InvalidJson)?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use serde_json::json;
+    use uuid::Uuid;
+
+    use crate::data_types::Errors;
+
+    use super::{Favicon, PlayerSample, Status, StatusError, StatusVersion};
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let mut png = vec![0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, 0, 0, 0, 13];
+        png.extend_from_slice(b"IHDR");
+        png.extend_from_slice(&width.to_be_bytes());
+        png.extend_from_slice(&height.to_be_bytes());
+        png.extend_from_slice(&[8, 6, 0, 0, 0]);
+        png
+    }
+
+    #[test]
+    fn decode() {
+        let status = Status::decode(
+            r#"{
+                "version": { "name": "1.20.4", "protocol": 765 },
+                "players": {
+                    "max": 100,
+                    "online": 5,
+                    "sample": [{ "name": "Notch", "id": "069a79f4-44e9-4726-a5be-fca90e38aaf5" }]
+                },
+                "description": { "text": "Hello ", "extra": [{ "text": "world", "bold": true }] },
+                "enforcesSecureChat": true,
+                "forgeData": { "fmlNetworkVersion": 3 }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(status.version, StatusVersion::new("1.20.4", 765));
+        let players = status.players.as_ref().unwrap();
+        assert_eq!((players.max, players.online), (100, 5));
+        assert_eq!(
+            players.sample,
+            [PlayerSample {
+                name: "Notch".to_owned(),
+                id: Uuid::from_str("069a79f4-44e9-4726-a5be-fca90e38aaf5").unwrap(),
+            }]
+        );
+        assert_eq!(status.description_text(), "Hello world");
+        assert_eq!(status.enforces_secure_chat, Some(true));
+        assert_eq!(status.previews_chat, None);
+        assert_eq!(
+            status.extensions["forgeData"],
+            json!({ "fmlNetworkVersion": 3 })
+        );
+
+        assert_eq!(Status::decode(&status.encode().unwrap()).unwrap(), status);
+        assert_eq!(
+            Status::decode("{}"),
+            Err(Errors::StatusError(StatusError::InvalidJson))
+        );
+    }
+
+    #[test]
+    fn encode() {
+        let status = Status::new(
+            StatusVersion::new("1.20.4", 765),
+            20,
+            0,
+            "A Minecraft Server",
+        );
+        assert_eq!(
+            status.encode().unwrap(),
+            r#"{"version":{"name":"1.20.4","protocol":765},"players":{"max":20,"online":0},"description":"A Minecraft Server"}"#
+        );
+    }
+
+    #[test]
+    fn favicon() {
+        let favicon = Favicon::from_png(png(64, 64)).unwrap();
+        let uri = favicon.to_string();
+        assert!(uri.starts_with("data:image/png;base64,iVBORw0KGgo"));
+        assert_eq!(Favicon::try_from(uri.clone()).unwrap(), favicon);
+
+        let mut status = Status::new(StatusVersion::new("1.20.4", 765), 20, 0, "");
+        status.favicon = Some(favicon);
+        assert_eq!(Status::decode(&status.encode().unwrap()).unwrap(), status);
+
+        assert_eq!(
+            Favicon::from_png(png(128, 128)),
+            Err(Errors::StatusError(StatusError::InvalidFaviconSize))
+        );
+        assert_eq!(
+            Favicon::from_png(b"GIF89a".to_vec()),
+            Err(Errors::StatusError(StatusError::InvalidFavicon))
+        );
+        assert_eq!(
+            Favicon::try_from(uri.replace("image/png", "image/gif")),
+            Err(StatusError::InvalidFavicon)
+        );
+
+        let mut status = serde_json::to_value(&status).unwrap();
+        status["favicon"] = json!(uri.replace("image/png", "image/gif"));
+        assert_eq!(Status::decode(&status.to_string()).unwrap().favicon, None);
+
+        let wrapped = format!("{}\n{}", &uri[..40], &uri[40..]);
+        assert_eq!(Favicon::try_from(wrapped).unwrap().png(), png(64, 64));
+    }
+}
@@ -0,0 +1,370 @@
+use std::collections::BTreeMap;
+
+use thiserror::Error;
+
+use super::{
+    registry_data::RegistryData, Array, Codec, DataResult, Identifier, SerDe, TagIdentifier, VarInt,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Error)]
+pub enum TagsError {
+    #[error("A tag references an entry that is not in its registry")]
+    UnknownEntry,
+    #[error("The tag is not defined for this registry")]
+    UnknownTag,
+    #[error("An id set type must be zero or positive")]
+    InvalidIdSet,
+}
+
+/// Every tag sent by Update Tags: registry identifier → tag name → network ids of the entries.
+///
+/// Registries and tags are kept sorted, so encoding is deterministic.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TagRegistry {
+    pub registries: BTreeMap<Identifier, BTreeMap<TagIdentifier, Vec<VarInt>>>,
+}
+
+impl TagRegistry {
+    #[must_use]
+    pub fn new() -> Self {
+        TagRegistry::default()
+    }
+
+    /// Returns the tags of the registry `registry_id`.
+    #[must_use]
+    pub fn registry(
+        &self,
+        registry_id: &Identifier,
+    ) -> Option<&BTreeMap<TagIdentifier, Vec<VarInt>>> {
+        self.registries.get(registry_id)
+    }
+
+    /// Returns the network ids in the tag `tag` of the registry `registry_id`.
+    #[must_use]
+    pub fn tag(&self, registry_id: &Identifier, tag: &TagIdentifier) -> Option<&[VarInt]> {
+        self.registries
+            .get(registry_id)
+            .and_then(|tags| tags.get(tag))
+            .map(Vec::as_slice)
+    }
+
+    /// Returns whether the entry `id` of the registry `registry_id` is in the tag `tag`.
+    #[must_use]
+    pub fn contains(&self, registry_id: &Identifier, tag: &TagIdentifier, id: VarInt) -> bool {
+        self.tag(registry_id, tag)
+            .is_some_and(|ids| ids.contains(&id))
+    }
+
+    /// Sets the tag `tag` of the registry `registry_id`, replacing it if it exists.
+    pub fn set_tag(&mut self, registry_id: Identifier, tag: TagIdentifier, ids: Vec<VarInt>) {
+        self.registries
+            .entry(registry_id)
+            .or_default()
+            .insert(tag, ids);
+    }
+
+    /// Sets the tag `tag` of the registry `registry_id` from entry names,
+    /// using `network_id` to find the id of each entry.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`TagsError::UnknownEntry`] if `network_id` returns `None` for an entry,
+    /// or if an id doesn't fit a [`VarInt`].
+    pub fn set_tag_by_names<'a>(
+        &mut self,
+        registry_id: Identifier,
+        tag: TagIdentifier,
+        names: impl IntoIterator<Item = &'a Identifier>,
+        network_id: impl Fn(&Identifier) -> Option<usize>,
+    ) -> DataResult<()> {
+        let ids = names
+            .into_iter()
+            .map(|name| {
+                network_id(name)
+                    .and_then(|id| i32::try_from(id).ok())
+                    .map(VarInt::new)
+                    .ok_or(TagsError::UnknownEntry)
+            })
+            .collect::<Result<_, _>>()?;
+
+        self.set_tag(registry_id, tag, ids);
+
+        Ok(())
+    }
+
+    /// Sets the tag `tag` of a synchronized registry from entry names, resolved with [`RegistryData::network_id`].
+    ///
+    /// ## Errors
+    ///
+    /// See [`TagRegistry::set_tag_by_names`].
+    pub fn set_tag_from_registry<'a>(
+        &mut self,
+        registry: &RegistryData,
+        tag: TagIdentifier,
+        names: impl IntoIterator<Item = &'a Identifier>,
+    ) -> DataResult<()> {
+        self.set_tag_by_names(registry.registry_id.clone(), tag, names, |name| {
+            registry.network_id(name)
+        })
+    }
+
+    /// Returns the names of the entries in the tag `tag` of a synchronized registry.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`TagsError::UnknownTag`] if the registry has no such tag,
+    /// or [`TagsError::UnknownEntry`] if an id is not in `registry`.
+    pub fn names<'a>(
+        &self,
+        registry: &'a RegistryData,
+        tag: &TagIdentifier,
+    ) -> DataResult<Vec<&'a Identifier>> {
+        let ids = self
+            .tag(&registry.registry_id, tag)
+            .ok_or(TagsError::UnknownTag)?;
+
+        ids.iter()
+            .map(|id| {
+                Ok(usize::try_from(id.value())
+                    .ok()
+                    .and_then(|id| registry.entries.get(id))
+                    .map(|entry| &entry.id)
+                    .ok_or(TagsError::UnknownEntry)?)
+            })
+            .collect()
+    }
+}
+
+impl<'a> SerDe<'a> for TagRegistry {
+    type Input = &'a [u8];
+    type Serialized = DataResult<Vec<u8>>;
+    type Deserialized = DataResult<(Self, usize)>;
+
+    /// Encodes the body of an Update Tags packet:
+    /// an array of registries, each with an array of tags, each with an array of [`VarInt`] ids.
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error if an identifier can't be encoded, or an array is too long.
+    fn encode(&self) -> Self::Serialized {
+        let mut bytes = Vec::new();
+
+        VarInt::from_len(self.registries.len())?.write(&mut bytes)?;
+        for (registry_id, tags) in &self.registries {
+            registry_id.write(&mut bytes)?;
+            VarInt::from_len(tags.len())?.write(&mut bytes)?;
+            for (tag, ids) in tags {
+                tag.write(&mut bytes)?;
+                Array(ids.clone()).write(&mut bytes)?;
+            }
+        }
+
+        Ok(bytes)
+    }
+
+    /// Decodes the body of an Update Tags packet.
+    ///
+    /// Returns the tags and the amount of bytes read.
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error if an identifier or an array is not valid.
+    fn decode(data: Self::Input) -> Self::Deserialized {
+        let (Array(registries), len) =
+            Array::<(Identifier, Array<(TagIdentifier, Array<VarInt>)>)>::read(data)?;
+
+        let registries = registries
+            .into_iter()
+            .map(|(registry_id, Array(tags))| {
+                (
+                    registry_id,
+                    tags.into_iter()
+                        .map(|(tag, Array(ids))| (tag, ids))
+                        .collect(),
+                )
+            })
+            .collect();
+
+        Ok((TagRegistry { registries }, len))
+    }
+}
+
+/// A set of registry entries, referenced either by a tag or inline by their network ids.
+///
+/// Sent as a [`VarInt`] type: `0` is followed by a tag name, while `n + 1` is followed by `n` ids.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IdSet {
+    Tag(TagIdentifier),
+    Ids(Vec<VarInt>),
+}
+
+impl IdSet {
+    /// Returns the network ids in the set, looking the tag up in the registry `registry_id` of `tags`.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`TagsError::UnknownTag`] if the tag is not defined.
+    pub fn resolve<'a>(
+        &'a self,
+        tags: &'a TagRegistry,
+        registry_id: &Identifier,
+    ) -> DataResult<&'a [VarInt]> {
+        match self {
+            IdSet::Tag(tag) => Ok(tags.tag(registry_id, tag).ok_or(TagsError::UnknownTag)?),
+            IdSet::Ids(ids) => Ok(ids),
+        }
+    }
+}
+
+impl Codec for IdSet {
+    fn write(&self, bytes: &mut Vec<u8>) -> DataResult<()> {
+        match self {
+            IdSet::Tag(tag) => {
+                VarInt::new(0).write(bytes)?;
+                tag.write(bytes)
+            }
+            IdSet::Ids(ids) => {
+                VarInt::from_len(ids.len() + 1)?.write(bytes)?;
+                ids.iter().try_for_each(|id| id.write(bytes))
+            }
+        }
+    }
+
+    fn read(data: &[u8]) -> DataResult<(Self, usize)> {
+        let (kind, mut position) = VarInt::read(data)?;
+
+        if kind.value() == 0 {
+            let (tag, len) = TagIdentifier::read(&data[position..])?;
+            return Ok((IdSet::Tag(tag), position + len));
+        }
+
+        let count = usize::try_from(kind.value()).map_err(|_| TagsError::InvalidIdSet)? - 1;
+        let mut ids = Vec::with_capacity(count.min(data.len()));
+        for _ in 0..count {
+            let (id, len) = VarInt::read(&data[position..])?;
+            position += len;
+            ids.push(id);
+        }
+
+        Ok((IdSet::Ids(ids), position))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use crate::{
+        data_types::{
+            registry_data::RegistryData, registry_data_entry::Entry, Codec, Errors, SerDe,
+            TagIdentifier, VarInt,
+        },
+        test_fixtures::id,
+    };
+
+    use super::{IdSet, TagRegistry, TagsError};
+
+    fn tag(value: &str) -> TagIdentifier {
+        TagIdentifier::from_str(value).unwrap()
+    }
+
+    fn biomes() -> RegistryData {
+        RegistryData::new(
+            id("minecraft:worldgen/biome"),
+            ["minecraft:badlands", "minecraft:desert", "minecraft:plains"]
+                .into_iter()
+                .map(|name| Entry::new(id(name), None))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn encode_decode() {
+        let mut tags = TagRegistry::new();
+        tags.set_tag(
+            id("minecraft:block"),
+            tag("#minecraft:logs"),
+            vec![VarInt::new(1), VarInt::new(300)],
+        );
+        tags.set_tag(id("minecraft:block"), tag("#minecraft:air"), vec![]);
+
+        let bytes = tags.encode().unwrap();
+        assert_eq!(bytes[0], 1);
+        assert_eq!(&bytes[1..17], b"\x0fminecraft:block");
+        assert_eq!(bytes[17], 2);
+        assert_eq!(&bytes[18..33], b"\x0dminecraft:air\x00");
+        assert_eq!(&bytes[33..], b"\x0eminecraft:logs\x02\x01\xac\x02");
+
+        assert_eq!(TagRegistry::decode(&bytes).unwrap(), (tags, bytes.len()));
+    }
+
+    #[test]
+    fn names() {
+        let biomes = biomes();
+        let hot = tag("#minecraft:is_hot");
+
+        let mut tags = TagRegistry::new();
+        tags.set_tag_from_registry(
+            &biomes,
+            hot.clone(),
+            [&id("minecraft:desert"), &id("minecraft:badlands")],
+        )
+        .unwrap();
+        assert_eq!(
+            tags.tag(&biomes.registry_id, &hot),
+            Some([VarInt::new(1), VarInt::new(0)].as_slice())
+        );
+        assert!(tags.contains(&biomes.registry_id, &hot, VarInt::new(0)));
+        assert!(!tags.contains(&biomes.registry_id, &hot, VarInt::new(2)));
+        assert_eq!(
+            tags.names(&biomes, &hot).unwrap(),
+            [&id("minecraft:desert"), &id("minecraft:badlands")]
+        );
+
+        assert_eq!(
+            tags.set_tag_from_registry(&biomes, hot.clone(), [&id("minecraft:ocean")]),
+            Err(Errors::TagsError(TagsError::UnknownEntry))
+        );
+        assert_eq!(
+            tags.names(&biomes, &tag("#minecraft:is_cold")),
+            Err(Errors::TagsError(TagsError::UnknownTag))
+        );
+    }
+
+    #[test]
+    fn id_set() {
+        let logs = IdSet::Tag(tag("#minecraft:logs"));
+        let mut bytes = Vec::new();
+        logs.write(&mut bytes).unwrap();
+        assert_eq!(bytes, b"\x00\x0eminecraft:logs");
+        assert_eq!(IdSet::read(&bytes).unwrap(), (logs.clone(), bytes.len()));
+
+        let inline = IdSet::Ids(vec![VarInt::new(4), VarInt::new(7)]);
+        let mut bytes = Vec::new();
+        inline.write(&mut bytes).unwrap();
+        assert_eq!(bytes, [0x03, 0x04, 0x07]);
+        assert_eq!(IdSet::read(&bytes).unwrap(), (inline.clone(), 3));
+        assert_eq!(IdSet::read(&[0x01]).unwrap(), (IdSet::Ids(Vec::new()), 1));
+        assert_eq!(
+            IdSet::read(&[0x80, 0x80, 0x80, 0x80, 0x08]),
+            Err(Errors::TagsError(TagsError::InvalidIdSet))
+        );
+
+        let mut tags = TagRegistry::new();
+        tags.set_tag(
+            id("minecraft:block"),
+            tag("#minecraft:logs"),
+            vec![VarInt::new(9)],
+        );
+        let blocks = id("minecraft:block");
+        assert_eq!(logs.resolve(&tags, &blocks).unwrap(), [VarInt::new(9)]);
+        assert_eq!(
+            inline.resolve(&tags, &blocks).unwrap(),
+            [VarInt::new(4), VarInt::new(7)]
+        );
+        assert_eq!(
+            logs.resolve(&tags, &id("minecraft:item")),
+            Err(Errors::TagsError(TagsError::UnknownTag))
+        );
+    }
+}
@@ -0,0 +1,247 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use uuid::Uuid;
+
+use super::DataResult;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Error)]
+pub enum TexturesError {
+    #[error("The textures property is not valid base64")]
+    InvalidBase64,
+    #[error("The textures property is not valid JSON")]
+    InvalidJson,
+}
+
+/// The decoded value of the [`textures`](super::property::Property::TEXTURES) property of a
+/// [`GameProfile`](super::game_profile::GameProfile).
+///
+/// The property value is this JSON object, base64 encoded:
+///
+/// ```json
+/// {
+///     "timestamp": 1700000000000,
+///     "profileId": "069a79f444e94726a5befca90e38aaf5",
+///     "profileName": "Notch",
+///     "textures": {
+///         "SKIN": { "url": "http://textures.minecraft.net/texture/...", "metadata": { "model": "slim" } },
+///         "CAPE": { "url": "http://textures.minecraft.net/texture/..." }
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Textures {
+    /// When the property was generated, in milliseconds since the Unix epoch.
+    pub timestamp: i64,
+    #[serde(serialize_with = "uuid::serde::simple::serialize")]
+    pub profile_id: Uuid,
+    pub profile_name: String,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub signature_required: bool,
+    #[serde(default)]
+    pub textures: TextureSet,
+}
+
+/// The skin and cape of a [`Textures`]. Both are optional.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TextureSet {
+    #[serde(rename = "SKIN", default, skip_serializing_if = "Option::is_none")]
+    pub skin: Option<Skin>,
+    #[serde(rename = "CAPE", default, skip_serializing_if = "Option::is_none")]
+    pub cape: Option<Cape>,
+}
+
+/// The player model a skin is drawn on.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SkinModel {
+    /// Steve, with 4 pixels wide arms. Sent without metadata.
+    #[default]
+    Classic,
+    /// Alex, with 3 pixels wide arms.
+    Slim,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "RawSkin", into = "RawSkin")]
+pub struct Skin {
+    pub url: String,
+    pub model: SkinModel,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Cape {
+    pub url: String,
+}
+
+/// The JSON shape of a [`Skin`], where the model is nested in an optional metadata object.
+#[derive(Serialize, Deserialize)]
+struct RawSkin {
+    url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    metadata: Option<SkinMetadata>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SkinMetadata {
+    #[serde(default)]
+    model: SkinModel,
+}
+
+impl From<RawSkin> for Skin {
+    fn from(value: RawSkin) -> Self {
+        Skin {
+            url: value.url,
+            model: value
+                .metadata
+                .map(|metadata| metadata.model)
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl From<Skin> for RawSkin {
+    fn from(value: Skin) -> Self {
+        RawSkin {
+            url: value.url,
+            metadata: (value.model == SkinModel::Slim)
+                .then_some(SkinMetadata { model: value.model }),
+        }
+    }
+}
+
+impl Textures {
+    /// Creates [`Textures`] without a skin or a cape.
+    #[must_use]
+    pub fn new(timestamp: i64, profile_id: Uuid, profile_name: &str) -> Self {
+        Textures {
+            timestamp,
+            profile_id,
+            profile_name: profile_name.to_owned(),
+            signature_required: false,
+            textures: TextureSet::default(),
+        }
+    }
+
+    #[must_use]
+    pub fn skin(&self) -> Option<&Skin> {
+        self.textures.skin.as_ref()
+    }
+
+    #[must_use]
+    pub fn cape(&self) -> Option<&Cape> {
+        self.textures.cape.as_ref()
+    }
+
+    pub fn set_skin(&mut self, url: &str, model: SkinModel) {
+        self.textures.skin = Some(Skin {
+            url: url.to_owned(),
+            model,
+        });
+    }
+
+    pub fn set_cape(&mut self, url: &str) {
+        self.textures.cape = Some(Cape {
+            url: url.to_owned(),
+        });
+    }
+
+    /// Decodes [`Textures`] from the base64 encoded JSON of a property value.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`TexturesError::InvalidBase64`] if `value` is not valid base64,
+    /// or [`TexturesError::InvalidJson`] if the decoded bytes are not a valid textures object.
+    pub fn decode(value: &str) -> DataResult<Self> {
+        let json = STANDARD
+            .decode(value)
+            .map_err(|_| TexturesError::InvalidBase64)?;
+
+        Ok(serde_json::from_slice(&json).map_err(|_| TexturesError::InvalidJson)?)
+    }
+
+    /// Encodes [`Textures`] into the base64 encoded JSON of a property value.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`TexturesError::InvalidJson`] if the textures can't be serialized.
+    pub fn encode(&self) -> DataResult<String> {
+        let json = serde_json::to_vec(self).map_err(|_| TexturesError::InvalidJson)?;
+
+        Ok(STANDARD.encode(json))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use base64::{engine::general_purpose::STANDARD, Engine};
+    use uuid::Uuid;
+
+    use crate::data_types::Errors;
+
+    use super::{SkinModel, Textures, TexturesError};
+
+    const NOTCH: &str = r#"{"timestamp":1700000000000,"profileId":"069a79f444e94726a5befca90e38aaf5","profileName":"Notch","signatureRequired":true,"textures":{"SKIN":{"url":"http://textures.minecraft.net/texture/skin","metadata":{"model":"slim"}},"CAPE":{"url":"http://textures.minecraft.net/texture/cape"}}}"#;
+
+    #[test]
+    fn decode() {
+        let textures = Textures::decode(&STANDARD.encode(NOTCH)).unwrap();
+
+        assert_eq!(textures.timestamp, 1_700_000_000_000);
+        assert_eq!(
+            textures.profile_id,
+            Uuid::from_str("069a79f4-44e9-4726-a5be-fca90e38aaf5").unwrap()
+        );
+        assert_eq!(textures.profile_name, "Notch");
+        assert!(textures.signature_required);
+
+        let skin = textures.skin().unwrap();
+        assert_eq!(skin.url, "http://textures.minecraft.net/texture/skin");
+        assert_eq!(skin.model, SkinModel::Slim);
+        assert_eq!(
+            textures.cape().unwrap().url,
+            "http://textures.minecraft.net/texture/cape"
+        );
+    }
+
+    #[test]
+    fn decode_classic() {
+        let json = r#"{"timestamp":0,"profileId":"069a79f444e94726a5befca90e38aaf5","profileName":"Notch","textures":{"SKIN":{"url":"a"}}}"#;
+        let textures = Textures::decode(&STANDARD.encode(json)).unwrap();
+
+        assert!(!textures.signature_required);
+        assert_eq!(textures.skin().unwrap().model, SkinModel::Classic);
+        assert_eq!(textures.cape(), None);
+    }
+
+    #[test]
+    fn encode() {
+        let textures = Textures::decode(&STANDARD.encode(NOTCH)).unwrap();
+        assert_eq!(
+            STANDARD.decode(textures.encode().unwrap()).unwrap(),
+            NOTCH.as_bytes()
+        );
+
+        let mut textures = Textures::new(0, Uuid::nil(), "Steve");
+        textures.set_skin("a", SkinModel::Classic);
+        assert_eq!(
+            STANDARD.decode(textures.encode().unwrap()).unwrap(),
+            br#"{"timestamp":0,"profileId":"00000000000000000000000000000000","profileName":"Steve","textures":{"SKIN":{"url":"a"}}}"#
+        );
+    }
+
+    #[test]
+    fn invalid() {
+        assert_eq!(
+            Textures::decode("not base64!"),
+            Err(Errors::TexturesError(TexturesError::InvalidBase64))
+        );
+        assert_eq!(
+            Textures::decode(&STANDARD.encode("{}")),
+            Err(Errors::TexturesError(TexturesError::InvalidJson))
+        );
+    }
+}
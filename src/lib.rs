@@ -1,7 +1,13 @@
 #![warn(clippy::all, clippy::pedantic)]
-#![allow(clippy::module_name_repetitions, clippy::inline_always)]
-#![feature(try_trait_v2)]
+#![allow(
+    clippy::module_name_repetitions,
+    clippy::inline_always,
+    clippy::needless_return_with_question_mark
+)]
 
 pub mod data_types;
 pub mod packet;
 pub mod values;
+
+#[cfg(test)]
+mod test_fixtures;
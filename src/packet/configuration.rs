@@ -0,0 +1,517 @@
+use std::str::FromStr;
+
+use quartz_nbt::NbtTag;
+use thiserror::Error;
+use uuid::Uuid;
+
+use super::cookies::CookieError;
+use crate::{
+    data_types::{
+        varint_enum, Array, Boolean, BoundedString, Codec, DataResult, Identifier, Nbt, VarInt,
+    },
+    values::Version,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Error)]
+pub enum ConfigurationError {
+    #[error("A text component can't be empty NBT")]
+    EmptyTextComponent,
+    #[error("A crash report can't have more than 32 custom details")]
+    TooManyReportDetails,
+}
+
+/// Configuration packets only exist since 23w31a, so their NBT is always nameless.
+const NBT_VERSION: Version = Version::Snapshot23W31A;
+
+/// Writes a text component as nameless network NBT.
+fn write_component(component: &NbtTag, bytes: &mut Vec<u8>) -> DataResult<()> {
+    bytes.append(&mut Nbt(Some(component.clone())).encode(NBT_VERSION)?);
+    Ok(())
+}
+
+fn read_component(data: &[u8]) -> DataResult<(NbtTag, usize)> {
+    match Nbt::decode(data, NBT_VERSION)? {
+        (Nbt(Some(component)), len) => Ok((component, len)),
+        (Nbt(None), _) => Err(ConfigurationError::EmptyTextComponent)?,
+    }
+}
+
+/// What the client did with a resource pack, sent in a Resource Pack Response.
+///
+/// The client first answers [`ResourcePackResult::Accepted`] or [`ResourcePackResult::Declined`],
+/// then the result of the download.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ResourcePackResult {
+    SuccessfullyDownloaded,
+    Declined,
+    FailedToDownload,
+    Accepted,
+    Downloaded,
+    InvalidUrl,
+    FailedToReload,
+    Discarded,
+}
+
+varint_enum!(ResourcePackResult {
+    SuccessfullyDownloaded = 0,
+    Declined = 1,
+    FailedToDownload = 2,
+    Accepted = 3,
+    Downloaded = 4,
+    InvalidUrl = 5,
+    FailedToReload = 6,
+    Discarded = 7,
+});
+
+/// A resource pack the client should download, identified by `uuid` to remove it later.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AddResourcePack {
+    pub uuid: Uuid,
+    pub url: BoundedString<32767>,
+    /// The hex SHA-1 of the pack, or empty to skip the check.
+    pub hash: BoundedString<40>,
+    /// Whether the client is disconnected if it declines the pack.
+    pub forced: Boolean,
+    /// Text component shown in the prompt.
+    pub prompt: Option<NbtTag>,
+}
+
+impl Codec for AddResourcePack {
+    fn write(&self, bytes: &mut Vec<u8>) -> DataResult<()> {
+        self.uuid.write(bytes)?;
+        self.url.write(bytes)?;
+        self.hash.write(bytes)?;
+        self.forced.write(bytes)?;
+        Boolean::from(self.prompt.is_some()).write(bytes)?;
+        match &self.prompt {
+            Some(prompt) => write_component(prompt, bytes),
+            None => Ok(()),
+        }
+    }
+
+    fn read(data: &[u8]) -> DataResult<(Self, usize)> {
+        let (uuid, mut position) = Uuid::read(data)?;
+        let (url, len) = BoundedString::read(&data[position..])?;
+        position += len;
+        let (hash, len) = BoundedString::read(&data[position..])?;
+        position += len;
+        let (forced, len) = Boolean::read(&data[position..])?;
+        position += len;
+        let (has_prompt, len) = Boolean::read(&data[position..])?;
+        position += len;
+
+        let prompt = if bool::from(has_prompt) {
+            let (prompt, len) = read_component(&data[position..])?;
+            position += len;
+            Some(prompt)
+        } else {
+            None
+        };
+
+        Ok((
+            AddResourcePack {
+                uuid,
+                url,
+                hash,
+                forced,
+                prompt,
+            },
+            position,
+        ))
+    }
+}
+
+/// A data pack, which both sides can have, to avoid sending its registry entries.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct KnownPack {
+    pub namespace: BoundedString<32767>,
+    pub id: BoundedString<32767>,
+    pub version: BoundedString<32767>,
+}
+
+impl KnownPack {
+    /// Creates a new [`KnownPack`].
+    ///
+    /// ## Errors
+    ///
+    /// Returns a [`StringError`](crate::data_types::StringError) if a value is longer than 32767 characters.
+    pub fn new(namespace: &str, id: &str, version: &str) -> DataResult<Self> {
+        Ok(KnownPack {
+            namespace: BoundedString::from_str(namespace)?,
+            id: BoundedString::from_str(id)?,
+            version: BoundedString::from_str(version)?,
+        })
+    }
+
+    /// The built-in pack of vanilla, `minecraft:core`, whose version is the one of the game, e.g. `1.21`.
+    ///
+    /// ## Errors
+    ///
+    /// Returns a [`StringError`](crate::data_types::StringError) if `version` is longer than 32767 characters.
+    pub fn core(version: &str) -> DataResult<Self> {
+        KnownPack::new("minecraft", "core", version)
+    }
+}
+
+impl Codec for KnownPack {
+    fn write(&self, bytes: &mut Vec<u8>) -> DataResult<()> {
+        self.namespace.write(bytes)?;
+        self.id.write(bytes)?;
+        self.version.write(bytes)
+    }
+
+    fn read(data: &[u8]) -> DataResult<(Self, usize)> {
+        let (namespace, mut position) = BoundedString::read(data)?;
+        let (id, len) = BoundedString::read(&data[position..])?;
+        position += len;
+        let (version, len) = BoundedString::read(&data[position..])?;
+        position += len;
+
+        Ok((
+            KnownPack {
+                namespace,
+                id,
+                version,
+            },
+            position,
+        ))
+    }
+}
+
+/// Tells the client to reconnect to another server, with a handshake for a transfer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transfer {
+    pub host: BoundedString<32767>,
+    pub port: VarInt,
+}
+
+impl Transfer {
+    /// Creates a new [`Transfer`]. The client keeps its cookies, see [`CookieJar`](super::cookies::CookieJar).
+    ///
+    /// ## Errors
+    ///
+    /// Returns a [`StringError`](crate::data_types::StringError) if `host` is longer than 32767 characters.
+    pub fn new(host: &str, port: u16) -> DataResult<Self> {
+        Ok(Transfer {
+            host: BoundedString::from_str(host)?,
+            port: VarInt::new(i32::from(port)),
+        })
+    }
+}
+
+impl Codec for Transfer {
+    fn write(&self, bytes: &mut Vec<u8>) -> DataResult<()> {
+        self.host.write(bytes)?;
+        self.port.write(bytes)
+    }
+
+    fn read(data: &[u8]) -> DataResult<(Self, usize)> {
+        let (host, position) = BoundedString::read(data)?;
+        let (port, len) = VarInt::read(&data[position..])?;
+
+        Ok((Transfer { host, port }, position + len))
+    }
+}
+
+/// A cookie the client keeps, and sends back in a Cookie Response when requested,
+/// even after a transfer to another server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoreCookie {
+    pub key: Identifier,
+    pub payload: Vec<u8>,
+}
+
+impl StoreCookie {
+    /// Maximum size of a cookie payload, in bytes.
+    pub const MAX_PAYLOAD: usize = 5120;
+}
+
+impl Codec for StoreCookie {
+    fn write(&self, bytes: &mut Vec<u8>) -> DataResult<()> {
+        if self.payload.len() > Self::MAX_PAYLOAD {
+            return Err(CookieError::CookieTooLarge)?;
+        }
+
+        self.key.write(bytes)?;
+        Array(self.payload.clone()).write(bytes)
+    }
+
+    fn read(data: &[u8]) -> DataResult<(Self, usize)> {
+        let (key, position) = Identifier::read(data)?;
+        let (Array(payload), len) = Array::read(&data[position..])?;
+        if payload.len() > Self::MAX_PAYLOAD {
+            return Err(CookieError::CookieTooLarge)?;
+        }
+
+        Ok((StoreCookie { key, payload }, position + len))
+    }
+}
+
+/// A detail added to the crash reports of the client, e.g. the name of the server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReportDetail {
+    pub title: BoundedString<128>,
+    pub description: BoundedString<4096>,
+}
+
+impl ReportDetail {
+    /// Maximum amount of details in a Custom Report Details packet.
+    pub const MAX_DETAILS: usize = 32;
+}
+
+/// The details of a Custom Report Details packet, at most [`ReportDetail::MAX_DETAILS`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ReportDetails(pub Vec<ReportDetail>);
+
+impl Codec for ReportDetail {
+    fn write(&self, bytes: &mut Vec<u8>) -> DataResult<()> {
+        self.title.write(bytes)?;
+        self.description.write(bytes)
+    }
+
+    fn read(data: &[u8]) -> DataResult<(Self, usize)> {
+        let (title, position) = BoundedString::read(data)?;
+        let (description, len) = BoundedString::read(&data[position..])?;
+
+        Ok((ReportDetail { title, description }, position + len))
+    }
+}
+
+impl Codec for ReportDetails {
+    fn write(&self, bytes: &mut Vec<u8>) -> DataResult<()> {
+        if self.0.len() > ReportDetail::MAX_DETAILS {
+            return Err(ConfigurationError::TooManyReportDetails)?;
+        }
+
+        Array(self.0.clone()).write(bytes)
+    }
+
+    fn read(data: &[u8]) -> DataResult<(Self, usize)> {
+        // Checked before reading the details, which could be large
+        let (len, _) = VarInt::read(data)?;
+        if usize::try_from(len.value()).is_ok_and(|len| len > ReportDetail::MAX_DETAILS) {
+            return Err(ConfigurationError::TooManyReportDetails)?;
+        }
+
+        let (Array(details), len) = Array::read(data)?;
+        Ok((ReportDetails(details), len))
+    }
+}
+
+/// The links the client shows in the pause menu, with a translated label.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BuiltInLink {
+    BugReport,
+    CommunityGuidelines,
+    Support,
+    Status,
+    Feedback,
+    Community,
+    Website,
+    Forums,
+    News,
+    Announcements,
+}
+
+varint_enum!(BuiltInLink {
+    BugReport = 0,
+    CommunityGuidelines = 1,
+    Support = 2,
+    Status = 3,
+    Feedback = 4,
+    Community = 5,
+    Website = 6,
+    Forums = 7,
+    News = 8,
+    Announcements = 9,
+});
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ServerLinkLabel {
+    BuiltIn(BuiltInLink),
+    /// A text component.
+    Custom(NbtTag),
+}
+
+/// A link shown in the pause menu. [`BuiltInLink::BugReport`] is also shown in disconnection screens.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServerLink {
+    pub label: ServerLinkLabel,
+    pub url: BoundedString<32767>,
+}
+
+impl Codec for ServerLink {
+    fn write(&self, bytes: &mut Vec<u8>) -> DataResult<()> {
+        match &self.label {
+            ServerLinkLabel::BuiltIn(link) => {
+                Boolean::from(true).write(bytes)?;
+                link.write(bytes)?;
+            }
+            ServerLinkLabel::Custom(component) => {
+                Boolean::from(false).write(bytes)?;
+                write_component(component, bytes)?;
+            }
+        }
+
+        self.url.write(bytes)
+    }
+
+    fn read(data: &[u8]) -> DataResult<(Self, usize)> {
+        let (built_in, mut position) = Boolean::read(data)?;
+        let label = if bool::from(built_in) {
+            let (link, len) = BuiltInLink::read(&data[position..])?;
+            position += len;
+            ServerLinkLabel::BuiltIn(link)
+        } else {
+            let (component, len) = read_component(&data[position..])?;
+            position += len;
+            ServerLinkLabel::Custom(component)
+        };
+        let (url, len) = BoundedString::read(&data[position..])?;
+
+        Ok((ServerLink { label, url }, position + len))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use quartz_nbt::{compound, NbtTag};
+    use uuid::Uuid;
+
+    use crate::{
+        data_types::{Array, Boolean, BoundedString, Codec, Errors, GenericError, Identifier},
+        packet::cookies::CookieError,
+    };
+
+    use super::{
+        AddResourcePack, BuiltInLink, ConfigurationError, KnownPack, ReportDetail, ReportDetails,
+        ResourcePackResult, ServerLink, ServerLinkLabel, StoreCookie, Transfer,
+    };
+
+    fn round_trip<X: Codec + PartialEq + std::fmt::Debug>(value: &X) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        value.write(&mut bytes).unwrap();
+
+        let (decoded, len) = X::read(&bytes).unwrap();
+        assert_eq!((&decoded, len), (value, bytes.len()));
+        bytes
+    }
+
+    #[test]
+    fn resource_packs() {
+        assert_eq!(round_trip(&ResourcePackResult::Discarded), [7]);
+        assert_eq!(
+            ResourcePackResult::read(&[8]),
+            Err(Errors::GenericError(GenericError::UnknownVariant))
+        );
+
+        let mut pack = AddResourcePack {
+            uuid: Uuid::from_u128(1),
+            url: BoundedString::from_str("https://example.com/pack.zip").unwrap(),
+            hash: BoundedString::default(),
+            forced: Boolean::from(true),
+            prompt: None,
+        };
+        let bytes = round_trip(&pack);
+        assert_eq!(bytes.len(), 16 + 29 + 1 + 1 + 1);
+
+        pack.prompt = Some(NbtTag::from(compound! { "text": "Please" }));
+        round_trip(&pack);
+        pack.prompt = Some(NbtTag::from("Please"));
+        round_trip(&pack);
+    }
+
+    #[test]
+    fn known_packs() {
+        let pack = KnownPack {
+            namespace: BoundedString::from_str("minecraft").unwrap(),
+            id: BoundedString::from_str("core").unwrap(),
+            version: BoundedString::from_str("1.21").unwrap(),
+        };
+        let mut bytes = Vec::new();
+        Array(vec![pack.clone()]).write(&mut bytes).unwrap();
+        assert_eq!(bytes, b"\x01\x09minecraft\x04core\x041.21");
+        assert_eq!(
+            Array::<KnownPack>::read(&bytes).unwrap(),
+            (Array(vec![pack]), bytes.len())
+        );
+    }
+
+    #[test]
+    fn transfer_and_cookies() {
+        let transfer = Transfer::new("mc.example.com", 25565).unwrap();
+        assert_eq!(round_trip(&transfer).len(), 15 + 3);
+
+        let mut cookie = StoreCookie {
+            key: Identifier::from_str("example:session").unwrap(),
+            payload: vec![1, 2, 3],
+        };
+        round_trip(&cookie);
+
+        cookie.payload = vec![0; StoreCookie::MAX_PAYLOAD + 1];
+        assert_eq!(
+            cookie.write(&mut Vec::new()),
+            Err(Errors::CookieError(CookieError::CookieTooLarge))
+        );
+        let mut bytes = Vec::new();
+        cookie.key.write(&mut bytes).unwrap();
+        Array(cookie.payload).write(&mut bytes).unwrap();
+        assert_eq!(
+            StoreCookie::read(&bytes),
+            Err(Errors::CookieError(CookieError::CookieTooLarge))
+        );
+    }
+
+    #[test]
+    fn report_details() {
+        let detail = ReportDetail {
+            title: BoundedString::from_str("Server").unwrap(),
+            description: BoundedString::from_str("example.com").unwrap(),
+        };
+        round_trip(&detail);
+        round_trip(&ReportDetails(vec![detail.clone(); 32]));
+
+        let details = vec![detail; 33];
+        assert_eq!(
+            ReportDetails(details.clone()).write(&mut Vec::new()),
+            Err(Errors::ConfigurationError(
+                ConfigurationError::TooManyReportDetails
+            ))
+        );
+        let mut bytes = Vec::new();
+        Array(details).write(&mut bytes).unwrap();
+        assert_eq!(
+            ReportDetails::read(&bytes),
+            Err(Errors::ConfigurationError(
+                ConfigurationError::TooManyReportDetails
+            ))
+        );
+    }
+
+    #[test]
+    fn server_links() {
+        let link = ServerLink {
+            label: ServerLinkLabel::BuiltIn(BuiltInLink::Website),
+            url: BoundedString::from_str("https://example.com").unwrap(),
+        };
+        assert_eq!(&round_trip(&link)[..3], [1, 6, 19]);
+
+        let link = ServerLink {
+            label: ServerLinkLabel::Custom(NbtTag::from("Wiki")),
+            url: BoundedString::from_str("https://example.com/wiki").unwrap(),
+        };
+        assert_eq!(
+            &round_trip(&link)[..8],
+            [0, 8, 0, 4, b'W', b'i', b'k', b'i']
+        );
+
+        assert_eq!(
+            ServerLink::read(&[0, 0]),
+            Err(Errors::ConfigurationError(
+                ConfigurationError::EmptyTextComponent
+            ))
+        );
+    }
+}
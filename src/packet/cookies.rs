@@ -0,0 +1,340 @@
+use std::{
+    collections::HashMap,
+    sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender},
+    time::Duration,
+};
+
+use thiserror::Error;
+
+use super::{configuration::StoreCookie, schema::ConnectionState, ClientPacket, ServerPacket};
+use crate::data_types::{DataResult, Identifier};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Error)]
+pub enum CookieError {
+    #[error("A cookie can't be greater than 5 kiB")]
+    CookieTooLarge,
+    #[error("Cookies can only be requested during login, configuration and play")]
+    InvalidState,
+    #[error("The packet is not a cookie packet")]
+    UnexpectedPacket,
+    #[error("The client sent a cookie that was not requested")]
+    UnrequestedCookie,
+    #[error("The client didn't send the cookie in time")]
+    Timeout,
+    #[error("The connection was closed before the client sent the cookie")]
+    Disconnected,
+}
+
+/// The cookies a client keeps, by key, across the servers it is transferred to.
+///
+/// Like the Notchian client, cookies are only kept in memory, so they are lost when the client quits.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CookieJar {
+    cookies: HashMap<Identifier, Vec<u8>>,
+}
+
+impl CookieJar {
+    #[must_use]
+    pub fn new() -> Self {
+        CookieJar::default()
+    }
+
+    #[must_use]
+    pub fn get(&self, key: &Identifier) -> Option<&[u8]> {
+        self.cookies.get(key).map(Vec::as_slice)
+    }
+
+    /// Stores a cookie, replacing the one with the same key.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`CookieError::CookieTooLarge`] if the payload is greater than [`StoreCookie::MAX_PAYLOAD`].
+    pub fn insert(&mut self, key: Identifier, payload: Vec<u8>) -> DataResult<()> {
+        if payload.len() > StoreCookie::MAX_PAYLOAD {
+            return Err(CookieError::CookieTooLarge)?;
+        }
+
+        self.cookies.insert(key, payload);
+        Ok(())
+    }
+
+    pub fn remove(&mut self, key: &Identifier) -> Option<Vec<u8>> {
+        self.cookies.remove(key)
+    }
+
+    /// Stores the cookie of a Store Cookie packet, in configuration or play.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`CookieError::UnexpectedPacket`] if `packet` is not a Store Cookie,
+    /// or [`CookieError::CookieTooLarge`] if the payload is too large.
+    pub fn store(&mut self, packet: &ServerPacket) -> DataResult<()> {
+        match packet {
+            ServerPacket::StoreCookieAtConfiguration { cookie }
+            | ServerPacket::StoreCookieAtPlay { cookie } => {
+                self.insert(cookie.key.clone(), cookie.payload.clone())
+            }
+            _ => Err(CookieError::UnexpectedPacket)?,
+        }
+    }
+
+    /// Answers a Cookie Request with the cookie of its key, or without payload if there is none.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`CookieError::UnexpectedPacket`] if `packet` is not a Cookie Request.
+    pub fn respond(&self, packet: &ServerPacket) -> DataResult<ClientPacket> {
+        let response = |key: &Identifier| (key.clone(), self.get(key).map(<[u8]>::to_vec));
+
+        Ok(match packet {
+            ServerPacket::CookieRequestAtLogin { key } => {
+                let (key, payload) = response(key);
+                ClientPacket::CookieResponseAtLogin { key, payload }
+            }
+            ServerPacket::CookieRequestAtConfiguration { key } => {
+                let (key, payload) = response(key);
+                ClientPacket::CookieResponseAtConfiguration { key, payload }
+            }
+            ServerPacket::CookieRequestAtPlay { key } => {
+                let (key, payload) = response(key);
+                ClientPacket::CookieResponseAtPlay { key, payload }
+            }
+            _ => return Err(CookieError::UnexpectedPacket)?,
+        })
+    }
+}
+
+/// The cookies a server requested from a client and is waiting for.
+///
+/// The connection passes every Cookie Response it reads to [`CookieRequests::receive`],
+/// which hands the payload to the [`PendingCookie`] of the request, possibly on another thread.
+#[derive(Debug, Default)]
+pub struct CookieRequests {
+    pending: HashMap<Identifier, Vec<Sender<Option<Vec<u8>>>>>,
+}
+
+/// A cookie requested with [`CookieRequests::request`], received once the client answers.
+#[derive(Debug)]
+pub struct PendingCookie {
+    key: Identifier,
+    receiver: Receiver<Option<Vec<u8>>>,
+}
+
+impl CookieRequests {
+    #[must_use]
+    pub fn new() -> Self {
+        CookieRequests::default()
+    }
+
+    /// Requests the cookie `key`, returning the Cookie Request to send in `state`.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`CookieError::InvalidState`] if cookies can't be requested in `state`.
+    pub fn request(
+        &mut self,
+        key: Identifier,
+        state: ConnectionState,
+    ) -> DataResult<(ServerPacket, PendingCookie)> {
+        let packet = match state {
+            ConnectionState::Login => ServerPacket::CookieRequestAtLogin { key: key.clone() },
+            ConnectionState::Configuration => {
+                ServerPacket::CookieRequestAtConfiguration { key: key.clone() }
+            }
+            ConnectionState::Play => ServerPacket::CookieRequestAtPlay { key: key.clone() },
+            ConnectionState::Handshaking | ConnectionState::Status => {
+                return Err(CookieError::InvalidState)?
+            }
+        };
+
+        let (sender, receiver) = channel();
+        self.pending.entry(key.clone()).or_default().push(sender);
+
+        Ok((packet, PendingCookie { key, receiver }))
+    }
+
+    /// Whether a cookie was requested and not received yet.
+    #[must_use]
+    pub fn is_pending(&self, key: &Identifier) -> bool {
+        self.pending.contains_key(key)
+    }
+
+    /// Hands the payload of a Cookie Response to the oldest request for its key.
+    ///
+    /// Returns the key of the cookie.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`CookieError::UnexpectedPacket`] if `packet` is not a Cookie Response,
+    /// [`CookieError::UnrequestedCookie`] if the cookie was not requested,
+    /// or [`CookieError::CookieTooLarge`] if the payload is greater than [`StoreCookie::MAX_PAYLOAD`],
+    /// in which case the Notchian server disconnects the client.
+    pub fn receive(&mut self, packet: &ClientPacket) -> DataResult<Identifier> {
+        let (ClientPacket::CookieResponseAtLogin { key, payload }
+        | ClientPacket::CookieResponseAtConfiguration { key, payload }
+        | ClientPacket::CookieResponseAtPlay { key, payload }) = packet
+        else {
+            return Err(CookieError::UnexpectedPacket)?;
+        };
+
+        if payload
+            .as_ref()
+            .is_some_and(|payload| payload.len() > StoreCookie::MAX_PAYLOAD)
+        {
+            return Err(CookieError::CookieTooLarge)?;
+        }
+
+        let senders = self
+            .pending
+            .get_mut(key)
+            .ok_or(CookieError::UnrequestedCookie)?;
+        let sender = senders.remove(0);
+        if senders.is_empty() {
+            self.pending.remove(key);
+        }
+
+        // The request may have been dropped, in which case the cookie is not needed anymore
+        let _ = sender.send(payload.clone());
+        Ok(key.clone())
+    }
+}
+
+impl PendingCookie {
+    #[must_use]
+    pub fn key(&self) -> &Identifier {
+        &self.key
+    }
+
+    /// Returns the payload if the client already answered, `None` inside if it has no such cookie.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`CookieError::Timeout`] if the client didn't answer yet,
+    /// or [`CookieError::Disconnected`] if the [`CookieRequests`] was dropped.
+    pub fn try_get(&self) -> DataResult<Option<Vec<u8>>> {
+        self.wait(Duration::ZERO)
+    }
+
+    /// Blocks until the client answers or `timeout` elapses.
+    ///
+    /// Returns the payload, `None` inside if the client has no such cookie.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`CookieError::Timeout`] if the client didn't answer in time,
+    /// or [`CookieError::Disconnected`] if the [`CookieRequests`] was dropped.
+    pub fn wait(&self, timeout: Duration) -> DataResult<Option<Vec<u8>>> {
+        match self.receiver.recv_timeout(timeout) {
+            Ok(payload) => Ok(payload),
+            Err(RecvTimeoutError::Timeout) => Err(CookieError::Timeout)?,
+            Err(RecvTimeoutError::Disconnected) => Err(CookieError::Disconnected)?,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{str::FromStr, thread, time::Duration};
+
+    use crate::{
+        data_types::{Errors, Identifier},
+        packet::{configuration::StoreCookie, schema::ConnectionState, ClientPacket, ServerPacket},
+    };
+
+    use super::{CookieError, CookieJar, CookieRequests};
+
+    fn key() -> Identifier {
+        Identifier::from_str("example:session").unwrap()
+    }
+
+    #[test]
+    fn jar() {
+        let mut jar = CookieJar::new();
+        jar.store(&ServerPacket::StoreCookieAtPlay {
+            cookie: StoreCookie {
+                key: key(),
+                payload: vec![1, 2, 3],
+            },
+        })
+        .unwrap();
+        assert_eq!(jar.get(&key()), Some([1, 2, 3].as_slice()));
+
+        assert_eq!(
+            jar.insert(key(), vec![0; StoreCookie::MAX_PAYLOAD + 1]),
+            Err(Errors::CookieError(CookieError::CookieTooLarge))
+        );
+        assert_eq!(jar.get(&key()), Some([1, 2, 3].as_slice()));
+
+        let ClientPacket::CookieResponseAtLogin {
+            key: found,
+            payload,
+        } = jar
+            .respond(&ServerPacket::CookieRequestAtLogin { key: key() })
+            .unwrap()
+        else {
+            unreachable!()
+        };
+        assert_eq!((found, payload), (key(), Some(vec![1, 2, 3])));
+
+        jar.remove(&key());
+        let ClientPacket::CookieResponseAtConfiguration { payload, .. } = jar
+            .respond(&ServerPacket::CookieRequestAtConfiguration { key: key() })
+            .unwrap()
+        else {
+            unreachable!()
+        };
+        assert_eq!(payload, None);
+    }
+
+    #[test]
+    fn request() {
+        let mut jar = CookieJar::new();
+        jar.insert(key(), b"token".to_vec()).unwrap();
+
+        let mut requests = CookieRequests::new();
+        let (request, pending) = requests
+            .request(key(), ConnectionState::Configuration)
+            .unwrap();
+        assert!(requests.is_pending(&key()));
+        assert_eq!(
+            pending.try_get(),
+            Err(Errors::CookieError(CookieError::Timeout))
+        );
+
+        let waiting = thread::spawn(move || pending.wait(Duration::from_secs(5)));
+        let response = jar.respond(&request).unwrap();
+        assert_eq!(requests.receive(&response).unwrap(), key());
+        assert_eq!(waiting.join().unwrap().unwrap(), Some(b"token".to_vec()));
+
+        assert!(!requests.is_pending(&key()));
+        assert_eq!(
+            requests.receive(&response),
+            Err(Errors::CookieError(CookieError::UnrequestedCookie))
+        );
+        assert_eq!(
+            requests.request(key(), ConnectionState::Status).map(|_| ()),
+            Err(Errors::CookieError(CookieError::InvalidState))
+        );
+
+        let (_, pending) = requests.request(key(), ConnectionState::Play).unwrap();
+        drop(requests);
+        assert_eq!(
+            pending.try_get(),
+            Err(Errors::CookieError(CookieError::Disconnected))
+        );
+    }
+
+    #[test]
+    fn too_large() {
+        let mut requests = CookieRequests::new();
+        let _ = requests.request(key(), ConnectionState::Login).unwrap();
+
+        assert_eq!(
+            requests.receive(&ClientPacket::CookieResponseAtLogin {
+                key: key(),
+                payload: Some(vec![0; StoreCookie::MAX_PAYLOAD + 1]),
+            }),
+            Err(Errors::CookieError(CookieError::CookieTooLarge))
+        );
+    }
+}
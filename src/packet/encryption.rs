@@ -0,0 +1,424 @@
+use std::fmt::Write;
+
+use rand::{rngs::ThreadRng, RngCore};
+use rsa::{
+    pkcs8::{DecodePublicKey, EncodePublicKey},
+    Pkcs1v15Encrypt, RsaPrivateKey, RsaPublicKey,
+};
+use sha1::{Digest, Sha1};
+use thiserror::Error;
+
+use super::{ClientPacket, ServerPacket};
+use crate::data_types::{self, Boolean, DataResult, VarInt};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Error)]
+pub enum EncryptionError {
+    #[error("The RSA key pair could not be generated")]
+    KeyGeneration,
+    #[error("The public key is not a valid X.509 SubjectPublicKeyInfo RSA key")]
+    InvalidPublicKey,
+    #[error("The data could not be encrypted with the public key")]
+    EncryptionFailed,
+    #[error("The data could not be decrypted with the private key")]
+    DecryptionFailed,
+    #[error("The verify token doesn't match the one sent in the Encryption Request")]
+    TokenMismatch,
+    #[error("The shared secret must be 16 bytes")]
+    InvalidSharedSecret,
+    #[error("The packet is not an Encryption Request or Encryption Response")]
+    UnexpectedPacket,
+}
+
+/// Size of the RSA key of vanilla servers, in bits.
+pub const KEY_BITS: usize = 1024;
+/// Length of the verify token of vanilla servers.
+pub const VERIFY_TOKEN_LEN: usize = 4;
+/// Length of the AES/CFB8 key both sides use once encryption is enabled.
+pub const SHARED_SECRET_LEN: usize = 16;
+
+/// The key both sides encrypt the connection with, after the encryption handshake.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SharedSecret(pub [u8; SHARED_SECRET_LEN]);
+
+impl SharedSecret {
+    /// Generates a random shared secret, as clients do.
+    #[must_use]
+    pub fn generate() -> Self {
+        let mut secret = [0; SHARED_SECRET_LEN];
+        ThreadRng::default().fill_bytes(&mut secret);
+        SharedSecret(secret)
+    }
+}
+
+/// The key pair of a server, used to receive the [`SharedSecret`] of each client.
+///
+/// Vanilla servers generate one 1024-bit key when starting, and use it for every connection.
+#[derive(Debug, Clone)]
+pub struct ServerKey {
+    private_key: RsaPrivateKey,
+    public_key_der: Vec<u8>,
+}
+
+/// The verify token sent to a client in an Encryption Request, to check its response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VerifyToken(pub [u8; VERIFY_TOKEN_LEN]);
+
+impl VerifyToken {
+    #[must_use]
+    pub fn generate() -> Self {
+        let mut token = [0; VERIFY_TOKEN_LEN];
+        ThreadRng::default().fill_bytes(&mut token);
+        VerifyToken(token)
+    }
+}
+
+impl ServerKey {
+    /// Generates a new [`KEY_BITS`] key pair.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`EncryptionError::KeyGeneration`] if the key could not be generated.
+    pub fn generate() -> DataResult<Self> {
+        let private_key = RsaPrivateKey::new(&mut ThreadRng::default(), KEY_BITS)
+            .map_err(|_| EncryptionError::KeyGeneration)?;
+
+        ServerKey::from_private_key(private_key)
+    }
+
+    /// Uses an existing key pair.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`EncryptionError::InvalidPublicKey`] if the public key can't be DER encoded.
+    pub fn from_private_key(private_key: RsaPrivateKey) -> DataResult<Self> {
+        let public_key_der = private_key
+            .to_public_key()
+            .to_public_key_der()
+            .map_err(|_| EncryptionError::InvalidPublicKey)?
+            .into_vec();
+
+        Ok(ServerKey {
+            private_key,
+            public_key_der,
+        })
+    }
+
+    /// The private key, e.g. to sign the properties of the game profiles the server creates.
+    #[must_use]
+    pub fn private_key(&self) -> &RsaPrivateKey {
+        &self.private_key
+    }
+
+    /// The public key, as the DER encoded X.509 `SubjectPublicKeyInfo` sent in the Encryption Request.
+    #[must_use]
+    pub fn public_key_der(&self) -> &[u8] {
+        &self.public_key_der
+    }
+
+    /// Creates the Encryption Request for a new connection, with a new [`VerifyToken`].
+    ///
+    /// `server_id` is empty for vanilla servers, and is used for the server hash.
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error if `server_id` is longer than 20 characters.
+    pub fn request(
+        &self,
+        server_id: &str,
+        should_authenticate: bool,
+    ) -> DataResult<(ServerPacket, VerifyToken)> {
+        let verify_token = VerifyToken::generate();
+
+        let mut id = data_types::String::new(Some(20))?;
+        id.insert(server_id.to_owned())?;
+
+        let packet = ServerPacket::EncryptionRequest {
+            server_id: id,
+            public_key_length: VarInt::from_len(self.public_key_der.len())?,
+            public_key: self.public_key_der.clone(),
+            verify_token_length: VarInt::from_len(VERIFY_TOKEN_LEN)?,
+            verify_token: verify_token.0.to_vec(),
+            should_authenticate: Boolean::from(should_authenticate),
+        };
+
+        Ok((packet, verify_token))
+    }
+
+    /// Decrypts the shared secret and verify token of an Encryption Response,
+    /// and checks the token is the one sent with [`ServerKey::request`].
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`EncryptionError::DecryptionFailed`] if a value is not valid PKCS#1 v1.5 for this key,
+    /// [`EncryptionError::TokenMismatch`] if the token is not `verify_token`,
+    /// or [`EncryptionError::InvalidSharedSecret`] if the secret is not 16 bytes.
+    pub fn decrypt(
+        &self,
+        verify_token: VerifyToken,
+        encrypted_shared_secret: &[u8],
+        encrypted_verify_token: &[u8],
+    ) -> DataResult<SharedSecret> {
+        let token = self
+            .private_key
+            .decrypt(Pkcs1v15Encrypt, encrypted_verify_token)
+            .map_err(|_| EncryptionError::DecryptionFailed)?;
+        if token != verify_token.0 {
+            return Err(EncryptionError::TokenMismatch)?;
+        }
+
+        let secret = self
+            .private_key
+            .decrypt(Pkcs1v15Encrypt, encrypted_shared_secret)
+            .map_err(|_| EncryptionError::DecryptionFailed)?;
+
+        Ok(SharedSecret(
+            secret
+                .try_into()
+                .map_err(|_| EncryptionError::InvalidSharedSecret)?,
+        ))
+    }
+
+    /// Same as [`ServerKey::decrypt`], reading the values from a [`ClientPacket::EncryptionResponse`].
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`EncryptionError::UnexpectedPacket`] if `packet` is not an Encryption Response,
+    /// or any error from [`ServerKey::decrypt`].
+    pub fn decrypt_response(
+        &self,
+        verify_token: VerifyToken,
+        packet: &ClientPacket,
+    ) -> DataResult<SharedSecret> {
+        let ClientPacket::EncryptionResponse {
+            shared_secret,
+            verify_token: encrypted_verify_token,
+            ..
+        } = packet
+        else {
+            return Err(EncryptionError::UnexpectedPacket)?;
+        };
+
+        self.decrypt(verify_token, shared_secret, encrypted_verify_token)
+    }
+
+    /// Computes the [`server_hash`] of a connection, to check it with `hasJoined`.
+    #[must_use]
+    pub fn server_hash(&self, server_id: &str, shared_secret: &SharedSecret) -> String {
+        server_hash(server_id, &shared_secret.0, &self.public_key_der)
+    }
+}
+
+/// Answers an Encryption Request as a client does: generates a [`SharedSecret`],
+/// and encrypts it and the verify token with the server's public key.
+///
+/// ## Errors
+///
+/// Returns [`EncryptionError::InvalidPublicKey`] if `public_key_der` is not a DER encoded RSA `SubjectPublicKeyInfo`,
+/// or [`EncryptionError::EncryptionFailed`] if the values are too long for the key.
+pub fn respond(
+    public_key_der: &[u8],
+    verify_token: &[u8],
+) -> DataResult<(ClientPacket, SharedSecret)> {
+    let public_key = RsaPublicKey::from_public_key_der(public_key_der)
+        .map_err(|_| EncryptionError::InvalidPublicKey)?;
+    let secret = SharedSecret::generate();
+
+    let mut rng = ThreadRng::default();
+    let mut encrypt = |data: &[u8]| {
+        public_key
+            .encrypt(&mut rng, Pkcs1v15Encrypt, data)
+            .map_err(|_| EncryptionError::EncryptionFailed)
+    };
+    let shared_secret = encrypt(&secret.0)?;
+    let verify_token = encrypt(verify_token)?;
+
+    let packet = ClientPacket::EncryptionResponse {
+        shared_secret_length: VarInt::from_len(shared_secret.len())?,
+        shared_secret,
+        verify_token_length: VarInt::from_len(verify_token.len())?,
+        verify_token,
+    };
+
+    Ok((packet, secret))
+}
+
+/// Same as [`respond`], reading the values from a [`ServerPacket::EncryptionRequest`].
+///
+/// ## Errors
+///
+/// Returns [`EncryptionError::UnexpectedPacket`] if `packet` is not an Encryption Request,
+/// or any error from [`respond`].
+pub fn respond_to_request(packet: &ServerPacket) -> DataResult<(ClientPacket, SharedSecret)> {
+    let ServerPacket::EncryptionRequest {
+        public_key,
+        verify_token,
+        ..
+    } = packet
+    else {
+        return Err(EncryptionError::UnexpectedPacket)?;
+    };
+
+    respond(public_key, verify_token)
+}
+
+/// Computes the server hash sent to the session server by `join` and `hasJoined`.
+///
+/// This is the SHA-1 digest of `server_id`, the shared secret and the DER encoded public key,
+/// formatted like Java's `new BigInteger(digest).toString(16)`: a signed hexadecimal number
+/// without leading zeros, e.g. `-7c9d5b0044c130109a5d7b5fb5c317c02b4e28c1`.
+#[must_use]
+pub fn server_hash(server_id: &str, shared_secret: &[u8], public_key_der: &[u8]) -> String {
+    let mut digest: [u8; 20] = Sha1::new()
+        .chain_update(server_id.as_bytes())
+        .chain_update(shared_secret)
+        .chain_update(public_key_der)
+        .finalize()
+        .into();
+
+    let negative = digest[0] & 0x80 != 0;
+    if negative {
+        // Two's complement, to print the absolute value.
+        let mut carry = true;
+        for byte in digest.iter_mut().rev() {
+            (*byte, carry) = (!*byte).overflowing_add(u8::from(carry));
+        }
+    }
+
+    let mut hex = String::with_capacity(41);
+    if negative {
+        hex.push('-');
+    }
+    let digits = digest
+        .iter()
+        .fold(String::with_capacity(40), |mut digits, byte| {
+            let _ = write!(digits, "{byte:02x}");
+            digits
+        });
+    match digits.trim_start_matches('0') {
+        "" => hex.push('0'),
+        digits => hex.push_str(digits),
+    }
+    hex
+}
+
+/// Same as [`server_hash`], reading the server id and public key from a [`ServerPacket::EncryptionRequest`].
+///
+/// ## Errors
+///
+/// Returns [`EncryptionError::UnexpectedPacket`] if `packet` is not an Encryption Request.
+pub fn request_server_hash(
+    packet: &ServerPacket,
+    shared_secret: &SharedSecret,
+) -> DataResult<String> {
+    let ServerPacket::EncryptionRequest {
+        server_id,
+        public_key,
+        ..
+    } = packet
+    else {
+        return Err(EncryptionError::UnexpectedPacket)?;
+    };
+
+    Ok(server_hash(&server_id.data, &shared_secret.0, public_key))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        data_types::Errors,
+        packet::{ClientPacket, ServerPacket},
+        test_fixtures::KEY,
+    };
+
+    use super::{
+        request_server_hash, respond, respond_to_request, server_hash, EncryptionError, VerifyToken,
+    };
+
+    #[test]
+    fn handshake() {
+        let (request, verify_token) = KEY.request("", true).unwrap();
+        let ServerPacket::EncryptionRequest {
+            public_key_length,
+            public_key,
+            verify_token_length,
+            ..
+        } = &request
+        else {
+            unreachable!()
+        };
+        assert_eq!(public_key, KEY.public_key_der());
+        assert_eq!(i32::from(*public_key_length), 162);
+        assert_eq!(i32::from(*verify_token_length), 4);
+
+        let (response, client_secret) = respond_to_request(&request).unwrap();
+        let ClientPacket::EncryptionResponse {
+            shared_secret_length,
+            ..
+        } = &response
+        else {
+            unreachable!()
+        };
+        assert_eq!(i32::from(*shared_secret_length), 128);
+
+        let server_secret = KEY.decrypt_response(verify_token, &response).unwrap();
+        assert_eq!(server_secret, client_secret);
+
+        assert_eq!(
+            request_server_hash(&request, &client_secret).unwrap(),
+            KEY.server_hash("", &server_secret)
+        );
+    }
+
+    #[test]
+    fn hash() {
+        assert_eq!(
+            server_hash("Notch", &[], &[]),
+            "4ed1f46bbe04bc756bcb17c0c7ce3e4632f06a48"
+        );
+        assert_eq!(
+            server_hash("jeb_", &[], &[]),
+            "-7c9d5b0044c130109a5d7b5fb5c317c02b4e28c1"
+        );
+        assert_eq!(
+            server_hash("simon", &[], &[]),
+            "88e16a1019277b15d58faf0541e11910eb756f6"
+        );
+        assert_eq!(
+            server_hash("", b"Not", b"ch"),
+            server_hash("Notch", &[], &[])
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let (response, _) = respond(KEY.public_key_der(), &[1, 2, 3, 4]).unwrap();
+        assert_eq!(
+            KEY.decrypt_response(VerifyToken([4, 3, 2, 1]), &response),
+            Err(Errors::EncryptionError(EncryptionError::TokenMismatch))
+        );
+
+        let ClientPacket::EncryptionResponse { shared_secret, .. } = &response else {
+            unreachable!()
+        };
+        assert_eq!(
+            KEY.decrypt(VerifyToken([1, 2, 3, 4]), shared_secret, &[0; 128]),
+            Err(Errors::EncryptionError(EncryptionError::DecryptionFailed))
+        );
+
+        let (response, _) = respond(KEY.public_key_der(), &[1, 2, 3, 4]).unwrap();
+        let ClientPacket::EncryptionResponse { verify_token, .. } = &response else {
+            unreachable!()
+        };
+        assert_eq!(
+            KEY.decrypt(VerifyToken([1, 2, 3, 4]), verify_token, verify_token),
+            Err(Errors::EncryptionError(
+                EncryptionError::InvalidSharedSecret
+            ))
+        );
+
+        assert_eq!(
+            respond(&[0x30, 0x00], &[]).map(|_| ()),
+            Err(Errors::EncryptionError(EncryptionError::InvalidPublicKey))
+        );
+    }
+}
@@ -0,0 +1,359 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use quartz_nbt::NbtCompound;
+use thiserror::Error;
+
+use super::{configuration::KnownPack, ClientPacket, ServerPacket};
+use crate::data_types::{
+    registry_data::{RegistryData, RegistryDataForm},
+    registry_data_entry::Entry,
+    DataResult, Identifier,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Error)]
+pub enum KnownPacksError {
+    #[error("The pack is not offered to the client")]
+    UnknownPack,
+    #[error("The packet is not a serverbound Known Packs")]
+    UnexpectedPacket,
+}
+
+/// The Known Packs exchange of the configuration state, since 1.20.5.
+///
+/// The server offers the data packs its registries come from, and the client answers with the ones it also has.
+/// Entries coming from a pack both sides know are then sent without their data,
+/// and the client loads it from its own copy of the pack.
+///
+/// The data of an entry is recorded with its pack, and the entry is only attributed to the pack while it has the same data,
+/// so an entry overridden afterwards, e.g. with [`DefaultRegistries::set`](crate::values::default_registries::DefaultRegistries::set),
+/// is still sent with its data.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct KnownPacks {
+    offered: Vec<KnownPack>,
+    /// The pack of each entry, as an index in `offered`, and its data in that pack, by registry then entry id.
+    origins: BTreeMap<Identifier, BTreeMap<Identifier, (usize, Option<NbtCompound>)>>,
+}
+
+impl KnownPacks {
+    /// Offers `packs`, in the order they are sent to the client.
+    #[must_use]
+    pub fn new(packs: Vec<KnownPack>) -> Self {
+        KnownPacks {
+            offered: packs,
+            origins: BTreeMap::new(),
+        }
+    }
+
+    /// The packs offered to the client.
+    #[must_use]
+    pub fn offered(&self) -> &[KnownPack] {
+        &self.offered
+    }
+
+    /// Creates the clientbound Known Packs packet, offering every pack.
+    #[must_use]
+    pub fn request(&self) -> ServerPacket {
+        ServerPacket::KnownPacks {
+            packs: self.offered.clone(),
+        }
+    }
+
+    /// Records that `entry` of the registry `registry_id` is the one of `pack`, with its current data.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`KnownPacksError::UnknownPack`] if `pack` is not offered.
+    pub fn set_origin(
+        &mut self,
+        registry_id: Identifier,
+        entry: &Entry,
+        pack: &KnownPack,
+    ) -> DataResult<()> {
+        let index = self.index(pack)?;
+
+        self.origins
+            .entry(registry_id)
+            .or_default()
+            .insert(entry.id.clone(), (index, entry.data.clone()));
+        Ok(())
+    }
+
+    /// Records that every entry of `registries` is the one of `pack`, e.g. for vanilla registries and `minecraft:core`.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`KnownPacksError::UnknownPack`] if `pack` is not offered.
+    pub fn set_registries_origin(
+        &mut self,
+        registries: &[RegistryData],
+        pack: &KnownPack,
+    ) -> DataResult<()> {
+        let index = self.index(pack)?;
+
+        for registry in registries {
+            let origins = self
+                .origins
+                .entry(registry.registry_id.clone())
+                .or_default();
+            for entry in &registry.entries {
+                origins.insert(entry.id.clone(), (index, entry.data.clone()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Forgets the pack of an entry, so its data is always sent. Returns whether it had one.
+    pub fn clear_origin(&mut self, registry_id: &Identifier, entry_id: &Identifier) -> bool {
+        self.origins
+            .get_mut(registry_id)
+            .and_then(|origins| origins.remove(entry_id))
+            .is_some()
+    }
+
+    /// Returns the pack of an entry, if it has one.
+    #[must_use]
+    pub fn origin(&self, registry_id: &Identifier, entry_id: &Identifier) -> Option<&KnownPack> {
+        self.origins
+            .get(registry_id)
+            .and_then(|origins| origins.get(entry_id))
+            .map(|&(index, _)| &self.offered[index])
+    }
+
+    /// Returns the packs both sides know, among the ones the client answered with.
+    ///
+    /// The client should only answer with offered packs, but the others are ignored in any case.
+    #[must_use]
+    pub fn accepted(&self, client: &[KnownPack]) -> Vec<KnownPack> {
+        let client = client.iter().collect::<BTreeSet<_>>();
+
+        self.offered
+            .iter()
+            .filter(|pack| client.contains(pack))
+            .cloned()
+            .collect()
+    }
+
+    /// Removes the data of the entries coming from a pack the client also knows,
+    /// for registries sent in the [`EntryList`](RegistryDataForm::EntryList) form.
+    /// Entries whose data changed since their origin was set keep it.
+    ///
+    /// The [`SingleNbt`](RegistryDataForm::SingleNbt) form needs the data of every entry,
+    /// so `registries` are left untouched in that form.
+    ///
+    /// Returns the amount of entries whose data was removed.
+    pub fn strip_known_entries(
+        &self,
+        form: RegistryDataForm,
+        client: &[KnownPack],
+        registries: &mut [RegistryData],
+    ) -> usize {
+        if form == RegistryDataForm::SingleNbt {
+            return 0;
+        }
+
+        let accepted = self
+            .offered
+            .iter()
+            .enumerate()
+            .filter(|(_, pack)| client.contains(pack))
+            .map(|(index, _)| index)
+            .collect::<BTreeSet<_>>();
+
+        let mut stripped = 0;
+        for registry in registries {
+            let Some(origins) = self.origins.get(&registry.registry_id) else {
+                continue;
+            };
+
+            for entry in &mut registry.entries {
+                if entry.data.is_some()
+                    && origins.get(&entry.id).is_some_and(|(index, data)| {
+                        accepted.contains(index) && *data == entry.data
+                    })
+                {
+                    entry.data = None;
+                    stripped += 1;
+                }
+            }
+        }
+        stripped
+    }
+
+    /// Same as [`KnownPacks::strip_known_entries`], reading the client's packs from a [`ClientPacket::KnownPacks`].
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`KnownPacksError::UnexpectedPacket`] if `packet` is not a serverbound Known Packs.
+    pub fn apply_response(
+        &self,
+        form: RegistryDataForm,
+        packet: &ClientPacket,
+        registries: &mut [RegistryData],
+    ) -> DataResult<usize> {
+        let ClientPacket::KnownPacks { packs } = packet else {
+            return Err(KnownPacksError::UnexpectedPacket)?;
+        };
+
+        Ok(self.strip_known_entries(form, packs, registries))
+    }
+
+    fn index(&self, pack: &KnownPack) -> DataResult<usize> {
+        Ok(self
+            .offered
+            .iter()
+            .position(|offered| offered == pack)
+            .ok_or(KnownPacksError::UnknownPack)?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        data_types::{
+            registries::DimensionType,
+            registry_data::{RegistryData, RegistryDataForm},
+            registry_data_entry::Entry,
+            Errors, SerDe,
+        },
+        packet::{configuration::KnownPack, ClientPacket},
+        test_fixtures::id,
+        values::{default_registries::DefaultRegistries, Version},
+    };
+
+    use super::{KnownPacks, KnownPacksError};
+
+    fn data_count(registries: &[RegistryData]) -> usize {
+        registries
+            .iter()
+            .flat_map(|registry| &registry.entries)
+            .filter(|entry| entry.data.is_some())
+            .count()
+    }
+
+    #[test]
+    fn negotiation() {
+        // The core pack the bundled registries come from
+        let core = KnownPack::core("1.20.4").unwrap();
+        let extra = KnownPack::new("example", "extra", "1").unwrap();
+        let mut packs = KnownPacks::new(vec![core.clone(), extra.clone()]);
+
+        let registries = DefaultRegistries::for_version(Version::Release1_20_4)
+            .unwrap()
+            .into_registries();
+        packs.set_registries_origin(&registries, &core).unwrap();
+        assert!(packs.clear_origin(&id("minecraft:dimension_type"), &id("minecraft:overworld")));
+        assert_eq!(
+            packs.origin(&id("minecraft:worldgen/biome"), &id("minecraft:plains")),
+            Some(&core)
+        );
+
+        let total = data_count(&registries);
+
+        // The client doesn't have the core pack of this version
+        let mut sent = registries.clone();
+        let old_core = KnownPack::core("1.20.2").unwrap();
+        assert_eq!(packs.accepted(&[old_core.clone(), extra.clone()]), [extra]);
+        assert_eq!(
+            packs.strip_known_entries(RegistryDataForm::EntryList, &[old_core], &mut sent),
+            0
+        );
+        assert_eq!(data_count(&sent), total);
+
+        let mut sent = registries.clone();
+        let response = ClientPacket::KnownPacks {
+            packs: vec![core.clone()],
+        };
+        assert_eq!(
+            packs
+                .apply_response(RegistryDataForm::EntryList, &response, &mut sent)
+                .unwrap(),
+            total - 1
+        );
+        assert_eq!(data_count(&sent), 1);
+
+        // Each registry is sent in its own packet, the stripped entries without data
+        for registry in &sent {
+            let bytes = registry.encode().unwrap();
+            assert_eq!(&RegistryData::decode(&bytes).unwrap().0, registry);
+        }
+
+        let dimensions = sent
+            .iter()
+            .find(|registry| registry.registry_id == id("minecraft:dimension_type"))
+            .unwrap();
+        let overworld = dimensions
+            .entries
+            .iter()
+            .find(|entry| entry.id == id("minecraft:overworld"))
+            .unwrap();
+        assert!(overworld.data.is_some());
+    }
+
+    #[test]
+    fn single_nbt() {
+        let core = KnownPack::core("1.20.4").unwrap();
+        let mut packs = KnownPacks::new(vec![core.clone()]);
+
+        let registries = DefaultRegistries::for_version(Version::Release1_20_4)
+            .unwrap()
+            .into_registries();
+        packs.set_registries_origin(&registries, &core).unwrap();
+
+        // Every entry keeps its data, since the single NBT form can't be sent without it
+        let mut sent = registries.clone();
+        assert_eq!(
+            packs.strip_known_entries(RegistryDataForm::SingleNbt, &[core], &mut sent),
+            0
+        );
+        assert_eq!(sent, registries);
+        assert!(RegistryData::encode_single_nbt(&sent, Version::Release1_20_4).is_ok());
+    }
+
+    #[test]
+    fn overridden() {
+        let core = KnownPack::core("1.20.4").unwrap();
+        let mut packs = KnownPacks::new(vec![core.clone()]);
+
+        let mut registries = DefaultRegistries::for_version(Version::Release1_20_4).unwrap();
+        packs
+            .set_registries_origin(registries.registries(), &core)
+            .unwrap();
+
+        // Overridden after its origin was set, without clearing it
+        let mut overworld = registries
+            .element::<DimensionType>(&id("minecraft:overworld"))
+            .unwrap()
+            .unwrap();
+        overworld.height = 512;
+        registries
+            .set(id("minecraft:overworld"), &overworld)
+            .unwrap();
+
+        let mut sent = registries.into_registries();
+        let total = data_count(&sent);
+        assert_eq!(
+            packs.strip_known_entries(RegistryDataForm::EntryList, &[core], &mut sent),
+            total - 1
+        );
+
+        let dimensions = sent
+            .iter()
+            .find(|registry| registry.registry_id == id("minecraft:dimension_type"))
+            .unwrap();
+        let sent_overworld = dimensions.entry(&id("minecraft:overworld")).unwrap();
+        assert_eq!(sent_overworld.element(), Ok(Some(overworld)));
+    }
+
+    #[test]
+    fn unknown_pack() {
+        let mut packs = KnownPacks::new(Vec::new());
+        assert_eq!(
+            packs.set_origin(
+                id("minecraft:dimension_type"),
+                &Entry::new(id("minecraft:overworld"), None),
+                &KnownPack::core("1.21").unwrap()
+            ),
+            Err(Errors::KnownPacksError(KnownPacksError::UnknownPack))
+        );
+    }
+}
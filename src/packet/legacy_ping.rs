@@ -0,0 +1,454 @@
+use thiserror::Error;
+
+use crate::data_types::{status::Status, DataResult};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Error)]
+pub enum LegacyPingError {
+    #[error("The connection doesn't start with a legacy ping")]
+    NotLegacy,
+    #[error("The legacy ping or its response is incomplete")]
+    UnexpectedEnd,
+    #[error("The 1.6 ping is not sent on the MC|PingHost channel")]
+    InvalidChannel,
+    #[error("A length in the legacy ping doesn't match its content")]
+    InvalidLength,
+    #[error("The kick packet is not a valid legacy ping response")]
+    InvalidResponse,
+}
+
+/// First byte of every legacy ping, the id of the old Server List Ping packet.
+pub const PING: u8 = 0xFE;
+/// First byte of a legacy ping response, the id of the old Kick packet.
+pub const KICK: u8 = 0xFF;
+
+/// Id of the old Plugin Message packet, which carries the `MC|PingHost` data of 1.6.
+const PLUGIN_MESSAGE: u8 = 0xFA;
+const PING_HOST: &str = "MC|PingHost";
+
+/// What the first bytes of a connection are.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Detection {
+    /// A legacy ping, see [`LegacyPing::decode`].
+    Legacy,
+    /// A modern handshake.
+    Modern,
+    /// Nothing was received yet.
+    Incomplete,
+}
+
+/// Tells a legacy ping from a modern handshake, from the first bytes of a connection.
+///
+/// A handshake of 254 bytes or more also starts with `0xFE`, the first byte of its length [`VarInt`](crate::data_types::VarInt),
+/// so like the Notchian server, the bytes after it are checked when they are available:
+/// a legacy ping can only continue with `0x01`, and a 1.6 one with `0x01 0xFA`.
+/// `0xFE` alone or `0xFE 0x01` are still taken for a legacy ping, since older clients send nothing more.
+#[must_use]
+pub fn detect(data: &[u8]) -> Detection {
+    match data {
+        [] => Detection::Incomplete,
+        [PING] | [PING, 0x01] | [PING, 0x01, PLUGIN_MESSAGE, ..] => Detection::Legacy,
+        _ => Detection::Modern,
+    }
+}
+
+/// A Server List Ping sent by a client from before the Netty rewrite.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LegacyPing {
+    /// Beta 1.8 to 1.3: `0xFE` alone.
+    Beta,
+    /// 1.4 and 1.5: `0xFE 0x01`.
+    V1_4,
+    /// 1.6: `0xFE 0x01`, followed by a `MC|PingHost` plugin message telling which server the client pings.
+    V1_6 {
+        protocol_version: u8,
+        hostname: String,
+        port: i32,
+    },
+}
+
+impl LegacyPing {
+    /// Decodes a legacy ping from everything received so far.
+    ///
+    /// Older clients send less, so `data` must hold every byte the client sent:
+    /// a lone `0xFE` is read as [`LegacyPing::Beta`], even if more bytes might follow.
+    /// Vanilla servers handle this by only reading what is available when the first packet arrives.
+    ///
+    /// Returns the ping and the amount of bytes read.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`LegacyPingError::NotLegacy`] if `data` doesn't start with `0xFE`,
+    /// [`LegacyPingError::UnexpectedEnd`] if the 1.6 plugin message is incomplete,
+    /// [`LegacyPingError::InvalidChannel`] if it is not sent on `MC|PingHost`,
+    /// or [`LegacyPingError::InvalidLength`] if its lengths are inconsistent.
+    pub fn decode(data: &[u8]) -> DataResult<(Self, usize)> {
+        match data {
+            [PING, 0x01, PLUGIN_MESSAGE, ..] => Self::decode_ping_host(data),
+            // Anything after the first bytes of 1.4 and beta pings is ignored, like vanilla does
+            [PING, 0x01, ..] => Ok((LegacyPing::V1_4, 2)),
+            [PING, ..] => Ok((LegacyPing::Beta, 1)),
+            [] => Err(LegacyPingError::UnexpectedEnd)?,
+            _ => Err(LegacyPingError::NotLegacy)?,
+        }
+    }
+
+    fn decode_ping_host(data: &[u8]) -> DataResult<(Self, usize)> {
+        let mut reader = Reader { data, position: 3 };
+
+        if reader.string()? != PING_HOST {
+            return Err(LegacyPingError::InvalidChannel)?;
+        }
+
+        let len = usize::from(reader.u16()?);
+        let start = reader.position;
+        let protocol_version = reader.bytes(1)?[0];
+        let hostname = reader.string()?;
+        let port = i32::from_be_bytes(reader.bytes(4)?.try_into().expect("4 bytes were read"));
+
+        if reader.position - start != len {
+            return Err(LegacyPingError::InvalidLength)?;
+        }
+
+        Ok((
+            LegacyPing::V1_6 {
+                protocol_version,
+                hostname,
+                port,
+            },
+            reader.position,
+        ))
+    }
+
+    /// Encodes the ping, as sent by a legacy client.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`LegacyPingError::InvalidLength`] if the hostname is too long for the plugin message.
+    pub fn encode(&self) -> DataResult<Vec<u8>> {
+        match self {
+            LegacyPing::Beta => Ok(vec![PING]),
+            LegacyPing::V1_4 => Ok(vec![PING, 0x01]),
+            LegacyPing::V1_6 {
+                protocol_version,
+                hostname,
+                port,
+            } => {
+                let mut bytes = vec![PING, 0x01, PLUGIN_MESSAGE];
+                write_string(&mut bytes, PING_HOST)?;
+
+                let mut message = vec![*protocol_version];
+                write_string(&mut message, hostname)?;
+                message.extend_from_slice(&port.to_be_bytes());
+
+                let len =
+                    u16::try_from(message.len()).map_err(|_| LegacyPingError::InvalidLength)?;
+                bytes.extend_from_slice(&len.to_be_bytes());
+                bytes.append(&mut message);
+
+                Ok(bytes)
+            }
+        }
+    }
+}
+
+/// The answer to a [`LegacyPing`], sent in a Kick packet: `0xFF`, then a UTF-16BE string prefixed by its length.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LegacyPingResponse {
+    /// The protocol and name of the server version. Beta responses don't have it.
+    pub version: Option<LegacyVersion>,
+    pub motd: String,
+    pub online: i32,
+    pub max: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LegacyVersion {
+    pub protocol: i32,
+    pub name: String,
+}
+
+impl LegacyPingResponse {
+    /// Encodes the response in the format `ping` understands.
+    ///
+    /// From 1.4 on, the string is `§1\0protocol\0version\0motd\0online\0max`.
+    /// Beta clients expect `motd§online§max`, so `§` is removed from their MOTD.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`LegacyPingError::InvalidLength`] if the string is longer than 65535 UTF-16 code units.
+    pub fn encode(&self, ping: &LegacyPing) -> DataResult<Vec<u8>> {
+        let payload = match (ping, &self.version) {
+            (LegacyPing::Beta, _) => format!(
+                "{}§{}§{}",
+                self.motd.replace('§', ""),
+                self.online,
+                self.max
+            ),
+            (_, version) => {
+                let (protocol, name) = version
+                    .as_ref()
+                    .map_or((0, ""), |version| (version.protocol, version.name.as_str()));
+                format!(
+                    "§1\0{protocol}\0{name}\0{}\0{}\0{}",
+                    self.motd, self.online, self.max
+                )
+            }
+        };
+
+        let mut bytes = vec![KICK];
+        write_string(&mut bytes, &payload)?;
+
+        Ok(bytes)
+    }
+
+    /// Decodes a response in either format.
+    ///
+    /// Returns the response and the amount of bytes read.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`LegacyPingError::InvalidResponse`] if `data` is not a Kick packet with a ping response,
+    /// or [`LegacyPingError::UnexpectedEnd`] if it is incomplete.
+    pub fn decode(data: &[u8]) -> DataResult<(Self, usize)> {
+        if data.first() != Some(&KICK) {
+            return Err(LegacyPingError::InvalidResponse)?;
+        }
+
+        let mut reader = Reader { data, position: 1 };
+        let payload = reader.string()?;
+        let number = |value: &str| value.parse().map_err(|_| LegacyPingError::InvalidResponse);
+
+        let response = if let Some(fields) = payload.strip_prefix("§1\0") {
+            let [protocol, name, motd, online, max] = fields
+                .split('\0')
+                .collect::<Vec<_>>()
+                .try_into()
+                .map_err(|_| LegacyPingError::InvalidResponse)?;
+
+            LegacyPingResponse {
+                version: Some(LegacyVersion {
+                    protocol: number(protocol)?,
+                    name: name.to_owned(),
+                }),
+                motd: motd.to_owned(),
+                online: number(online)?,
+                max: number(max)?,
+            }
+        } else {
+            let mut fields = payload.rsplitn(3, '§');
+            let (Some(max), Some(online), Some(motd)) =
+                (fields.next(), fields.next(), fields.next())
+            else {
+                return Err(LegacyPingError::InvalidResponse)?;
+            };
+
+            LegacyPingResponse {
+                version: None,
+                motd: motd.to_owned(),
+                online: number(online)?,
+                max: number(max)?,
+            }
+        };
+
+        Ok((response, reader.position))
+    }
+}
+
+impl From<&Status> for LegacyPingResponse {
+    /// Uses the version, plain text description and player counts of a modern status.
+    fn from(status: &Status) -> Self {
+        let (online, max) = status
+            .players
+            .as_ref()
+            .map_or((0, 0), |players| (players.online, players.max));
+
+        LegacyPingResponse {
+            version: Some(LegacyVersion {
+                protocol: status.version.protocol,
+                name: status.version.name.clone(),
+            }),
+            motd: status.description_text(),
+            online,
+            max,
+        }
+    }
+}
+
+/// Reads the big-endian values of the legacy protocol.
+struct Reader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl Reader<'_> {
+    fn bytes(&mut self, len: usize) -> DataResult<&[u8]> {
+        let bytes = self
+            .data
+            .get(self.position..self.position + len)
+            .ok_or(LegacyPingError::UnexpectedEnd)?;
+        self.position += len;
+        Ok(bytes)
+    }
+
+    fn u16(&mut self) -> DataResult<u16> {
+        let bytes = self.bytes(2)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    /// Reads a string prefixed by its length in UTF-16 code units.
+    fn string(&mut self) -> DataResult<String> {
+        let len = usize::from(self.u16()?);
+        let units = self
+            .bytes(len * 2)?
+            .chunks_exact(2)
+            .map(|unit| u16::from_be_bytes([unit[0], unit[1]]))
+            .collect::<Vec<_>>();
+
+        Ok(String::from_utf16(&units).map_err(|_| LegacyPingError::InvalidResponse)?)
+    }
+}
+
+/// Writes a string prefixed by its length in UTF-16 code units.
+fn write_string(bytes: &mut Vec<u8>, string: &str) -> DataResult<()> {
+    let units = string.encode_utf16().collect::<Vec<_>>();
+    let len = u16::try_from(units.len()).map_err(|_| LegacyPingError::InvalidLength)?;
+
+    bytes.extend_from_slice(&len.to_be_bytes());
+    for unit in units {
+        bytes.extend_from_slice(&unit.to_be_bytes());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::data_types::{
+        status::{Status, StatusVersion},
+        Errors,
+    };
+
+    use super::{
+        detect, Detection, LegacyPing, LegacyPingError, LegacyPingResponse, LegacyVersion,
+    };
+
+    /// A 1.6.4 client pinging `localhost:25565`.
+    const PING_1_6: [u8; 49] = [
+        0xFE, 0x01, 0xFA, 0x00, 0x0B, 0x00, 0x4D, 0x00, 0x43, 0x00, 0x7C, 0x00, 0x50, 0x00, 0x69,
+        0x00, 0x6E, 0x00, 0x67, 0x00, 0x48, 0x00, 0x6F, 0x00, 0x73, 0x00, 0x74, 0x00, 0x19, 0x4E,
+        0x00, 0x09, 0x00, 0x6C, 0x00, 0x6F, 0x00, 0x63, 0x00, 0x61, 0x00, 0x6C, 0x00, 0x68, 0x00,
+        0x6F, 0x00, 0x73, 0x00,
+    ];
+
+    fn ping_1_6() -> Vec<u8> {
+        let mut bytes = PING_1_6.to_vec();
+        bytes.extend_from_slice(&[0x74, 0x00, 0x00, 0x63, 0xDD]);
+        bytes
+    }
+
+    #[test]
+    fn detection() {
+        assert_eq!(detect(&[]), Detection::Incomplete);
+        assert_eq!(detect(&[0xFE, 0x01]), Detection::Legacy);
+        assert_eq!(detect(&[0x10, 0x00]), Detection::Modern);
+        assert_eq!(detect(&[0xFE]), Detection::Legacy);
+        assert_eq!(detect(&PING_1_6), Detection::Legacy);
+
+        // Handshakes of 382 and 254 bytes
+        assert_eq!(detect(&[0xFE, 0x02, 0x00]), Detection::Modern);
+        assert_eq!(detect(&[0xFE, 0x01, 0x00]), Detection::Modern);
+    }
+
+    #[test]
+    fn decode() {
+        assert_eq!(LegacyPing::decode(&[0xFE]).unwrap(), (LegacyPing::Beta, 1));
+        assert_eq!(
+            LegacyPing::decode(&[0xFE, 0x01]).unwrap(),
+            (LegacyPing::V1_4, 2)
+        );
+
+        let bytes = ping_1_6();
+        let ping = LegacyPing::V1_6 {
+            protocol_version: 78,
+            hostname: "localhost".to_owned(),
+            port: 25565,
+        };
+        assert_eq!(
+            LegacyPing::decode(&bytes).unwrap(),
+            (ping.clone(), bytes.len())
+        );
+        assert_eq!(ping.encode().unwrap(), bytes);
+
+        assert_eq!(
+            LegacyPing::decode(&bytes[..bytes.len() - 1]),
+            Err(Errors::LegacyPingError(LegacyPingError::UnexpectedEnd))
+        );
+        assert_eq!(
+            LegacyPing::decode(&[0x10, 0x00]),
+            Err(Errors::LegacyPingError(LegacyPingError::NotLegacy))
+        );
+
+        let mut wrong_channel = bytes.clone();
+        wrong_channel[8] = b'X';
+        assert_eq!(
+            LegacyPing::decode(&wrong_channel),
+            Err(Errors::LegacyPingError(LegacyPingError::InvalidChannel))
+        );
+
+        let mut wrong_length = bytes;
+        wrong_length[28] = 0x1A;
+        assert_eq!(
+            LegacyPing::decode(&wrong_length),
+            Err(Errors::LegacyPingError(LegacyPingError::InvalidLength))
+        );
+    }
+
+    #[test]
+    fn response() {
+        let response = LegacyPingResponse {
+            version: Some(LegacyVersion {
+                protocol: 127,
+                name: "1.20.4".to_owned(),
+            }),
+            motd: "A §aMinecraft§r Server".to_owned(),
+            online: 3,
+            max: 20,
+        };
+
+        let bytes = response.encode(&LegacyPing::V1_4).unwrap();
+        assert_eq!(&bytes[..3], [0xFF, 0x00, 0x29]);
+        assert_eq!(&bytes[3..9], [0x00, 0xA7, 0x00, 0x31, 0x00, 0x00]);
+        assert_eq!(bytes.len(), 3 + 0x29 * 2);
+        assert_eq!(
+            LegacyPingResponse::decode(&bytes).unwrap(),
+            (response.clone(), bytes.len())
+        );
+
+        let bytes = response.encode(&LegacyPing::Beta).unwrap();
+        let (beta, len) = LegacyPingResponse::decode(&bytes).unwrap();
+        assert_eq!(len, bytes.len());
+        assert_eq!(
+            beta,
+            LegacyPingResponse {
+                version: None,
+                motd: "A aMinecraftr Server".to_owned(),
+                online: 3,
+                max: 20,
+            }
+        );
+
+        assert_eq!(
+            LegacyPingResponse::decode(&[0xFF, 0x00, 0x01, 0x00, 0x41]),
+            Err(Errors::LegacyPingError(LegacyPingError::InvalidResponse))
+        );
+    }
+
+    #[test]
+    fn from_status() {
+        let status = Status::new(StatusVersion::new("1.20.4", 765), 20, 1, "Hello");
+        let response = LegacyPingResponse::from(&status);
+        assert_eq!(response.motd, "Hello");
+        assert_eq!(response.version.unwrap().protocol, 765);
+        assert_eq!((response.online, response.max), (1, 20));
+    }
+}
@@ -1,8 +1,25 @@
+pub mod configuration;
+pub mod cookies;
+pub mod encryption;
+pub mod known_packs;
+pub mod legacy_ping;
+pub mod schema;
+pub mod session;
+
+use self::{
+    configuration::{
+        AddResourcePack, KnownPack, ReportDetails, ResourcePackResult, ServerLink, StoreCookie,
+        Transfer,
+    },
+    legacy_ping::{LegacyPing, LegacyPingResponse},
+};
 use crate::data_types::{
-    self, handshake_next_state::HandshakeNextState, registry_data_entry::Entry, Boolean,
-    Identifier, VarInt,
+    self, client_information::ClientInformation, game_profile::GameProfile,
+    handshake_next_state::HandshakeNextState, registry_data_entry::Entry, tags::TagRegistry,
+    Boolean, DataResult, Identifier, ServerAddress, Username, VarInt,
 };
 
+#[allow(dead_code)]
 pub(crate) struct RawPacket {
     /// Length of Packet ID + Data
     length: VarInt,
@@ -15,15 +32,16 @@ pub enum ClientPacket {
     /// Packet may only be used at connection state: `Handshaking`
     Handshake {
         protocol_version: VarInt,
-        server_address: String,
+        server_address: ServerAddress,
         server_port: u16,
         next_state: HandshakeNextState,
     },
+    /// Sent by clients from before the Netty rewrite instead of a handshake, see [`legacy_ping::detect`].
+    ///
+    /// ---
+    ///
     /// Packet may only be used at connection state: `Handshaking`
-    LegacyServerListPing {
-        /// always `0x01`
-        payload: u8,
-    },
+    LegacyServerListPing { ping: LegacyPing },
     /// The status can only be requested once immediately after the handshake, before any ping. The server won't respond otherwise.
     ///
     /// ---
@@ -38,7 +56,7 @@ pub enum ClientPacket {
     /// Packet may only be used at connection state: `Login`
     LoginStart {
         /// The player's username
-        name: data_types::String,
+        name: Username,
         /// The player's UUID
         player_uuid: uuid::Uuid,
     },
@@ -88,10 +106,103 @@ pub enum ClientPacket {
         /// The payload of the cookie
         payload: Option<Vec<u8>>,
     },
+    /// Sent when the player connects.
+    ///
+    /// ---
+    ///
+    /// Packet may only be used at connection state: `Configuration`
+    ClientInformationAtConfiguration { information: ClientInformation },
+    /// Sent whenever a setting changes.
+    ///
+    /// ---
+    ///
+    /// Packet may only be used at connection state: `Play`
+    ClientInformationAtPlay { information: ClientInformation },
+    /// Mods and plugins can use this to send their data, see [`ServerPacket::PluginMessageAtConfiguration`].
+    ///
+    /// ---
+    ///
+    /// Packet may only be used at connection state: `Configuration`
+    PluginMessageAtConfiguration {
+        /// Name of the [plugin channel](https://wiki.vg/Plugin_channel) used to send the data.
+        channel: data_types::Identifier,
+        /// Any data, depending on the channel. The length of this array must be inferred from the packet length.
+        data: Vec<u8>,
+    },
+    /// Answers [Finish Configuration](ServerPacket::FinishConfiguration).
+    ///
+    /// ---
+    ///
+    /// This packet switches the connection state to play.
+    ///
+    /// ---
+    ///
+    /// Packet may only be used at connection state: `Configuration`
+    AcknowledgeFinishConfiguration,
+    /// Answers a [Keep Alive](ServerPacket::KeepAliveAtConfiguration) with the same payload.
+    ///
+    /// ---
+    ///
+    /// Packet may only be used at connection state: `Configuration`
+    KeepAliveAtConfiguration { payload: i64 },
+    /// Answers a [Ping](ServerPacket::PingAtConfiguration) with the same id.
+    ///
+    /// ---
+    ///
+    /// Packet may only be used at connection state: `Configuration`
+    PongAtConfiguration { id: i32 },
+    /// Packet may only be used at connection state: `Configuration`
+    ResourcePackResponseAtConfiguration {
+        /// The pack from the [Add Resource Pack](ServerPacket::AddResourcePackAtConfiguration)
+        uuid: uuid::Uuid,
+        result: ResourcePackResult,
+    },
+    /// Answers [Known Packs](ServerPacket::KnownPacks) with the packs the client also has.
+    ///
+    /// ---
+    ///
+    /// Packet may only be used at connection state: `Configuration`
+    KnownPacks { packs: Vec<KnownPack> },
+    /// Response to a [Cookie Request](ServerPacket::CookieRequestAtConfiguration) from the server.
+    ///
+    /// ---
+    ///
+    /// The Notchian server only accepts responses of up to 5 kiB in size.
+    ///
+    /// ---
+    ///
+    /// Packet may only be used at connection state: `Configuration`
+    CookieResponseAtConfiguration {
+        /// The identifier of the cookie
+        key: data_types::Identifier,
+        /// The payload of the cookie
+        payload: Option<Vec<u8>>,
+    },
+    /// Response to a [Cookie Request](ServerPacket::CookieRequestAtPlay) from the server.
+    ///
+    /// ---
+    ///
+    /// The Notchian server only accepts responses of up to 5 kiB in size.
+    ///
+    /// ---
+    ///
+    /// Packet may only be used at connection state: `Play`
+    CookieResponseAtPlay {
+        /// The identifier of the cookie
+        key: data_types::Identifier,
+        /// The payload of the cookie
+        payload: Option<Vec<u8>>,
+    },
 }
 
 /// Clientbound Packets
 pub enum ServerPacket {
+    /// Answers a [`ClientPacket::LegacyServerListPing`], then the connection is closed.
+    ///
+    /// ---
+    ///
+    /// Packet may only be used at connection state: `Handshaking`
+    LegacyServerListPingResponse { response: LegacyPingResponse },
     /// Packet may only be used at connection state: `Status`
     StatusResponse {
         /// See [Server List Ping - Status Response](https://wiki.vg/Server_List_Ping#Status_Response)
@@ -124,10 +235,7 @@ pub enum ServerPacket {
     },
     /// Packet may only be used at connection state: `Login`
     LoginSuccess {
-        uuid: uuid::Uuid,
-        username: data_types::String,
-        number_of_properties: VarInt,
-        properties: Vec<data_types::property::Property>,
+        profile: GameProfile,
         strict_error_handling: Boolean,
     },
     /// Enables compression.
@@ -190,7 +298,7 @@ pub enum ServerPacket {
         reason: data_types::String,
     },
     /// Sent by the server to notify the client that the configuration process has finished.
-    /// The client answers with [Acknowledge Finish Configuration](ClientPacket::AcknoledgeFinishConfiguration) whenever it is ready to continue.
+    /// The client answers with [Acknowledge Finish Configuration](ClientPacket::AcknowledgeFinishConfiguration) whenever it is ready to continue.
     ///
     /// ---
     ///
@@ -226,4 +334,78 @@ pub enum ServerPacket {
     },
     /// Packet may only be used at connection state: `Configuration`
     RemoveResourcePackAtConfiguration { uuid: Option<uuid::Uuid> },
+    /// Sends the tags of every registry, replacing the ones the client has.
+    ///
+    /// ---
+    ///
+    /// Packet may only be used at connection state: `Configuration`
+    UpdateTagsAtConfiguration { tags: TagRegistry },
+    /// Packet may only be used at connection state: `Configuration`
+    AddResourcePackAtConfiguration { pack: AddResourcePack },
+    /// Enables experimental features, e.g. `minecraft:vanilla` and `minecraft:bundle`.
+    ///
+    /// ---
+    ///
+    /// Packet may only be used at connection state: `Configuration`
+    FeatureFlags { flags: Vec<Identifier> },
+    /// The data packs of the server. The client answers with the ones it has, see [`ClientPacket::KnownPacks`].
+    ///
+    /// ---
+    ///
+    /// Packet may only be used at connection state: `Configuration`
+    KnownPacks { packs: Vec<KnownPack> },
+    /// Tells the client to connect to another server.
+    ///
+    /// ---
+    ///
+    /// Packet may only be used at connection state: `Configuration`
+    TransferAtConfiguration { transfer: Transfer },
+    /// Packet may only be used at connection state: `Configuration`
+    StoreCookieAtConfiguration { cookie: StoreCookie },
+    /// Up to [`MAX_DETAILS`](configuration::ReportDetail::MAX_DETAILS) details, added to the client's crash reports.
+    ///
+    /// ---
+    ///
+    /// Packet may only be used at connection state: `Configuration`
+    CustomReportDetailsAtConfiguration { details: ReportDetails },
+    /// Packet may only be used at connection state: `Configuration`
+    ServerLinksAtConfiguration { links: Vec<ServerLink> },
+    /// Packet may only be used at connection state: `Play`
+    CookieRequestAtPlay { key: data_types::Identifier },
+    /// Packet may only be used at connection state: `Play`
+    StoreCookieAtPlay { cookie: StoreCookie },
+    /// Tells the client to connect to another server.
+    ///
+    /// ---
+    ///
+    /// Packet may only be used at connection state: `Play`
+    TransferAtPlay { transfer: Transfer },
+    /// Sends the tags of every registry again, e.g. after the data packs are reloaded.
+    ///
+    /// ---
+    ///
+    /// Packet may only be used at connection state: `Play`
+    UpdateTagsAtPlay { tags: TagRegistry },
+}
+
+impl ServerPacket {
+    /// Creates a [`ServerPacket::LoginSuccess`] for `profile`, with strict error handling disabled like vanilla.
+    #[must_use]
+    pub fn login_success(profile: GameProfile) -> Self {
+        ServerPacket::LoginSuccess {
+            profile,
+            strict_error_handling: Boolean::from(false),
+        }
+    }
+
+    /// Creates the [`ServerPacket::LoginSuccess`] an offline mode server sends to `name`,
+    /// see [`GameProfile::offline`].
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`GameProfileError::InvalidUsername`](data_types::game_profile::GameProfileError::InvalidUsername)
+    /// if `name` is not a valid username.
+    pub fn offline_login_success(name: &str) -> DataResult<Self> {
+        Ok(ServerPacket::login_success(GameProfile::offline(name)?))
+    }
 }
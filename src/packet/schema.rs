@@ -0,0 +1,785 @@
+use std::collections::HashMap;
+
+use serde_json::{Map, Value};
+use thiserror::Error;
+
+use crate::{
+    data_types::{
+        descriptor::{
+            CountKind, DiscriminantKind, EnumDescriptor, TypeDescriptor, VariantDescriptor,
+        },
+        Codec, DataResult, DataTypes, VarInt,
+    },
+    values::Version,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Error)]
+pub enum SchemaError {
+    #[error("The schema is not valid JSON")]
+    InvalidJson,
+    #[error("The schema does not have the expected structure")]
+    InvalidStructure,
+    #[error("A type is neither native nor declared in the schema")]
+    UnknownType,
+    #[error("A type refers to itself")]
+    RecursiveType,
+    #[error("A packet or variant id is missing or not a number")]
+    InvalidPacketId,
+    #[error("Two packets have the same id or name")]
+    DuplicatePacket,
+    #[error("No packet with this name or id in the schema")]
+    UnknownPacket,
+    #[error("The packet has bytes left after its last field")]
+    TrailingBytes,
+}
+
+/// The state of a connection, which decides the set of packets that can be sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ConnectionState {
+    Handshaking,
+    Status,
+    Login,
+    Configuration,
+    Play,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Direction {
+    /// From the client to the server, `toServer` in schemas.
+    Serverbound,
+    /// From the server to the client, `toClient` in schemas.
+    Clientbound,
+}
+
+impl ConnectionState {
+    const ALL: [(ConnectionState, &'static str); 5] = [
+        (ConnectionState::Handshaking, "handshaking"),
+        (ConnectionState::Status, "status"),
+        (ConnectionState::Login, "login"),
+        (ConnectionState::Configuration, "configuration"),
+        (ConnectionState::Play, "play"),
+    ];
+}
+
+impl Direction {
+    const ALL: [(Direction, &'static str); 2] = [
+        (Direction::Serverbound, "toServer"),
+        (Direction::Clientbound, "toClient"),
+    ];
+}
+
+/// A packet of a [`ProtocolSchema`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PacketSchema {
+    pub id: i32,
+    pub name: String,
+    /// The fields after the packet id.
+    pub descriptor: TypeDescriptor,
+}
+
+/// The packets of a [`Version`], loaded at runtime from a JSON schema.
+///
+/// The schema declares reusable types, then the packets of each state and direction:
+///
+/// ```json
+/// {
+///     "types": {
+///         "hand": ["mapper", { "type": "varint", "mappings": { "0": "main_hand", "1": "off_hand" } }]
+///     },
+///     "handshaking": {
+///         "toServer": {
+///             "set_protocol": { "id": "0x00", "type": ["container", [
+///                 { "name": "protocolVersion", "type": "varint" },
+///                 { "name": "serverHost", "type": "string" },
+///                 { "name": "serverPort", "type": "u16" },
+///                 { "name": "nextState", "type": "varint" }
+///             ]] }
+///         }
+///     },
+///     "play": { "toServer": { "swing_arm": { "id": 54, "type": ["container", [{ "name": "hand", "type": "hand" }]] } } }
+/// }
+/// ```
+///
+/// Types use the notation of the community `protocol.json` files.
+/// The native types are `void`, `bool`, `i8`, `u8`, `i16`, `u16`, `i32`, `u32`, `i64`, `f32`, `f64`,
+/// `varint`, `varlong`, `string` (or `pstring`), `identifier`, `UUID`, `position`, `angle`,
+/// `nbt` (or `anonymousNbt`), `buffer`, `restBuffer` and `bitset`, and they are composed with:
+///
+/// - `["container", [{ "name": ..., "type": ... }]]`
+/// - `["option", type]`
+/// - `["array", { "countType": "varint" | "i8" | "u8" | "i16" | "i32", "type": ... }]`,
+///   or without `countType` for an array taking the rest of the packet
+/// - `["buffer", { "countType": ... }]` with the same count types, or `["buffer", { "rest": true }]`
+/// - `["mapper", { "type": "varint" | "u8", "mappings": { "0": "name" } }]` for fieldless enums
+/// - `["enum", { "type": "varint" | "u8" | "string", "variants": [{ "id": 0, "name": ..., "type": ... }] }]`
+///   for enums with fields, where `type` defaults to `void` and `id` is only needed for numeric discriminants
+///
+/// Packet and variant ids are numbers, or strings in decimal or `0x` hexadecimal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProtocolSchema {
+    pub version: Version,
+    packets: HashMap<(ConnectionState, Direction), Vec<PacketSchema>>,
+}
+
+impl ProtocolSchema {
+    /// Loads the schema of the packets of `version` from JSON.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`SchemaError::InvalidJson`] if `json` is not valid JSON,
+    /// or any error from [`ProtocolSchema::from_value`].
+    pub fn from_json(json: &str, version: Version) -> DataResult<Self> {
+        let value: Value = serde_json::from_str(json).map_err(|_| SchemaError::InvalidJson)?;
+        Self::from_value(&value, version)
+    }
+
+    /// Loads the schema of the packets of `version` from a parsed JSON value.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`SchemaError::InvalidStructure`] if the schema or a type is malformed,
+    /// [`SchemaError::UnknownType`] or [`SchemaError::RecursiveType`] if a type can't be resolved,
+    /// [`SchemaError::InvalidPacketId`] if a packet id is not a number,
+    /// or [`SchemaError::DuplicatePacket`] if two packets of a state and direction have the same id.
+    pub fn from_value(value: &Value, version: Version) -> DataResult<Self> {
+        let schema = value.as_object().ok_or(SchemaError::InvalidStructure)?;
+        let empty = Map::new();
+        let types = match schema.get("types") {
+            Some(types) => types.as_object().ok_or(SchemaError::InvalidStructure)?,
+            None => &empty,
+        };
+        let resolver = Resolver {
+            types,
+            stack: Vec::new(),
+        };
+
+        let mut packets = HashMap::new();
+
+        for (state, state_name) in ConnectionState::ALL {
+            let Some(state_schema) = schema.get(state_name) else {
+                continue;
+            };
+            let state_schema = state_schema
+                .as_object()
+                .ok_or(SchemaError::InvalidStructure)?;
+
+            for (direction, direction_name) in Direction::ALL {
+                let Some(direction_schema) = state_schema.get(direction_name) else {
+                    continue;
+                };
+                let direction_schema = direction_schema
+                    .as_object()
+                    .ok_or(SchemaError::InvalidStructure)?;
+
+                let mut list: Vec<PacketSchema> = Vec::with_capacity(direction_schema.len());
+                for (name, packet) in direction_schema {
+                    let id = parse_id(packet.get("id").ok_or(SchemaError::InvalidStructure)?)?;
+                    let descriptor = match packet.get("type") {
+                        Some(kind) => resolver.clone().resolve(kind)?,
+                        None => TypeDescriptor::Container(Vec::new()),
+                    };
+
+                    if list.iter().any(|packet| packet.id == id) {
+                        return Err(SchemaError::DuplicatePacket)?;
+                    }
+
+                    list.push(PacketSchema {
+                        id,
+                        name: name.clone(),
+                        descriptor,
+                    });
+                }
+
+                packets.insert((state, direction), list);
+            }
+        }
+
+        Ok(ProtocolSchema { version, packets })
+    }
+
+    fn packets(&self, state: ConnectionState, direction: Direction) -> &[PacketSchema] {
+        self.packets
+            .get(&(state, direction))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns the packet called `name`.
+    #[must_use]
+    pub fn packet(
+        &self,
+        state: ConnectionState,
+        direction: Direction,
+        name: &str,
+    ) -> Option<&PacketSchema> {
+        self.packets(state, direction)
+            .iter()
+            .find(|packet| packet.name == name)
+    }
+
+    /// Returns the packet with the id `id`.
+    #[must_use]
+    pub fn packet_by_id(
+        &self,
+        state: ConnectionState,
+        direction: Direction,
+        id: i32,
+    ) -> Option<&PacketSchema> {
+        self.packets(state, direction)
+            .iter()
+            .find(|packet| packet.id == id)
+    }
+
+    /// Encodes the packet called `name` with the fields in `value`, prefixed by its id as a [`VarInt`].
+    ///
+    /// The packet length and compression are not included.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`SchemaError::UnknownPacket`] if there is no packet called `name`,
+    /// or any error from [`TypeDescriptor::encode`].
+    pub fn encode(
+        &self,
+        state: ConnectionState,
+        direction: Direction,
+        name: &str,
+        value: &DataTypes,
+    ) -> DataResult<Vec<u8>> {
+        let packet = self
+            .packet(state, direction, name)
+            .ok_or(SchemaError::UnknownPacket)?;
+
+        let mut bytes = Vec::new();
+        VarInt::new(packet.id).write(&mut bytes)?;
+        bytes.append(&mut packet.descriptor.encode(value, self.version)?);
+
+        Ok(bytes)
+    }
+
+    /// Decodes a packet, starting with its id as a [`VarInt`], without its length.
+    ///
+    /// Returns the packet schema, to get its name, and its fields.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`SchemaError::UnknownPacket`] if no packet has the id,
+    /// [`SchemaError::TrailingBytes`] if the fields don't take the whole packet,
+    /// or any error from [`TypeDescriptor::decode`].
+    pub fn decode(
+        &self,
+        state: ConnectionState,
+        direction: Direction,
+        data: &[u8],
+    ) -> DataResult<(&PacketSchema, DataTypes)> {
+        let (id, position) = VarInt::read(data)?;
+        let packet = self
+            .packet_by_id(state, direction, id.value())
+            .ok_or(SchemaError::UnknownPacket)?;
+
+        let (value, len) = packet.descriptor.decode(&data[position..], self.version)?;
+
+        if position + len != data.len() {
+            return Err(SchemaError::TrailingBytes)?;
+        }
+
+        Ok((packet, value))
+    }
+}
+
+/// Packet ids are numbers, or strings in decimal or `0x` hexadecimal.
+fn parse_id(value: &Value) -> DataResult<i32> {
+    let id = match value {
+        Value::Number(number) => number.as_i64().and_then(|id| i32::try_from(id).ok()),
+        Value::String(string) => match string.strip_prefix("0x") {
+            Some(hex) => i32::from_str_radix(hex, 16).ok(),
+            None => string.parse().ok(),
+        },
+        _ => None,
+    };
+
+    Ok(id.ok_or(SchemaError::InvalidPacketId)?)
+}
+
+/// Resolves the type notation into [`TypeDescriptor`]s, expanding the declared types.
+#[derive(Clone)]
+struct Resolver<'a> {
+    types: &'a Map<String, Value>,
+    /// The declared types being expanded, to detect cycles.
+    stack: Vec<&'a str>,
+}
+
+impl<'a> Resolver<'a> {
+    fn resolve(&mut self, kind: &'a Value) -> DataResult<TypeDescriptor> {
+        match kind {
+            Value::String(name) => self.named(name),
+            Value::Array(pair) => match pair.as_slice() {
+                [Value::String(name), options] => self.composed(name, options),
+                _ => Err(SchemaError::InvalidStructure)?,
+            },
+            _ => Err(SchemaError::InvalidStructure)?,
+        }
+    }
+
+    fn named(&mut self, name: &'a str) -> DataResult<TypeDescriptor> {
+        let native = match name {
+            "void" => TypeDescriptor::Void,
+            "bool" => TypeDescriptor::Boolean,
+            "i8" => TypeDescriptor::Byte,
+            "u8" => TypeDescriptor::UnsignedByte,
+            "i16" => TypeDescriptor::Short,
+            "u16" => TypeDescriptor::UnsignedShort,
+            "i32" => TypeDescriptor::Int,
+            "u32" => TypeDescriptor::UnsignedInt,
+            "i64" => TypeDescriptor::Long,
+            "f32" => TypeDescriptor::Float,
+            "f64" => TypeDescriptor::Double,
+            "varint" => TypeDescriptor::VarInt,
+            "varlong" => TypeDescriptor::VarLong,
+            "string" | "pstring" => TypeDescriptor::String,
+            "identifier" => TypeDescriptor::Identifier,
+            "UUID" => TypeDescriptor::UUID,
+            "position" => TypeDescriptor::Position,
+            "angle" => TypeDescriptor::Angle,
+            "nbt" | "anonymousNbt" => TypeDescriptor::NBTTag,
+            "buffer" => TypeDescriptor::ByteArray(CountKind::VarInt),
+            "restBuffer" => TypeDescriptor::RestBuffer,
+            "bitset" => TypeDescriptor::BitSet,
+            _ => {
+                let declared = self.types.get(name).ok_or(SchemaError::UnknownType)?;
+
+                if self.stack.contains(&name) {
+                    return Err(SchemaError::RecursiveType)?;
+                }
+
+                self.stack.push(name);
+                let descriptor = self.resolve(declared)?;
+                self.stack.pop();
+
+                return Ok(descriptor);
+            }
+        };
+
+        Ok(native)
+    }
+
+    fn composed(&mut self, name: &str, options: &'a Value) -> DataResult<TypeDescriptor> {
+        match name {
+            "container" => {
+                let fields = options.as_array().ok_or(SchemaError::InvalidStructure)?;
+
+                let fields = fields
+                    .iter()
+                    .map(|field| {
+                        let name = field
+                            .get("name")
+                            .and_then(Value::as_str)
+                            .ok_or(SchemaError::InvalidStructure)?;
+                        let kind = field.get("type").ok_or(SchemaError::InvalidStructure)?;
+                        Ok((name.to_owned(), self.resolve(kind)?))
+                    })
+                    .collect::<DataResult<_>>()?;
+
+                Ok(TypeDescriptor::Container(fields))
+            }
+            "option" => Ok(TypeDescriptor::Optional(Box::new(self.resolve(options)?))),
+            "array" => {
+                let kind = options.get("type").ok_or(SchemaError::InvalidStructure)?;
+                let inner = Box::new(self.resolve(kind)?);
+
+                match count_kind(options)? {
+                    Some(count) => Ok(TypeDescriptor::Array(count, inner)),
+                    None => Ok(TypeDescriptor::InferredArray(inner)),
+                }
+            }
+            "buffer" => match count_kind(options)? {
+                Some(count) => Ok(TypeDescriptor::ByteArray(count)),
+                None if options.get("rest").and_then(Value::as_bool) == Some(true) => {
+                    Ok(TypeDescriptor::RestBuffer)
+                }
+                None => Err(SchemaError::UnknownType)?,
+            },
+            "mapper" => {
+                let discriminant = discriminant_kind(options)?;
+                if discriminant == DiscriminantKind::String {
+                    return Err(SchemaError::UnknownType)?;
+                }
+
+                let mappings = options
+                    .get("mappings")
+                    .and_then(Value::as_object)
+                    .ok_or(SchemaError::InvalidStructure)?;
+
+                let variants = mappings
+                    .iter()
+                    .map(|(id, name)| {
+                        Ok(VariantDescriptor {
+                            id: parse_id(&Value::String(id.clone()))?,
+                            name: name
+                                .as_str()
+                                .ok_or(SchemaError::InvalidStructure)?
+                                .to_owned(),
+                            fields: TypeDescriptor::Void,
+                        })
+                    })
+                    .collect::<DataResult<_>>()?;
+
+                Ok(TypeDescriptor::Enum(EnumDescriptor {
+                    discriminant,
+                    variants,
+                }))
+            }
+            "enum" => {
+                let discriminant = discriminant_kind(options)?;
+                let variants = options
+                    .get("variants")
+                    .and_then(Value::as_array)
+                    .ok_or(SchemaError::InvalidStructure)?;
+
+                let variants = variants
+                    .iter()
+                    .map(|variant| {
+                        let name = variant
+                            .get("name")
+                            .and_then(Value::as_str)
+                            .ok_or(SchemaError::InvalidStructure)?;
+                        // String discriminated variants are sent by name
+                        let id = match variant.get("id") {
+                            Some(id) => parse_id(id)?,
+                            None if discriminant == DiscriminantKind::String => 0,
+                            None => return Err(SchemaError::InvalidPacketId)?,
+                        };
+                        let fields = match variant.get("type") {
+                            Some(kind) => self.resolve(kind)?,
+                            None => TypeDescriptor::Void,
+                        };
+
+                        Ok(VariantDescriptor {
+                            id,
+                            name: name.to_owned(),
+                            fields,
+                        })
+                    })
+                    .collect::<DataResult<_>>()?;
+
+                Ok(TypeDescriptor::Enum(EnumDescriptor {
+                    discriminant,
+                    variants,
+                }))
+            }
+            _ => Err(SchemaError::UnknownType)?,
+        }
+    }
+}
+
+fn discriminant_kind(options: &Value) -> DataResult<DiscriminantKind> {
+    match options.get("type").and_then(Value::as_str) {
+        Some("varint") => Ok(DiscriminantKind::VarInt),
+        Some("u8") => Ok(DiscriminantKind::Byte),
+        Some("string") => Ok(DiscriminantKind::String),
+        Some(_) => Err(SchemaError::UnknownType)?,
+        None => Err(SchemaError::InvalidStructure)?,
+    }
+}
+
+/// Reads the `countType` of an array or a buffer, `None` if there is no length prefix.
+fn count_kind(options: &Value) -> DataResult<Option<CountKind>> {
+    // A count given by a number or by another field is not supported
+    if options.get("count").is_some() {
+        return Err(SchemaError::UnknownType)?;
+    }
+
+    match options.get("countType").map(Value::as_str) {
+        Some(Some("varint")) => Ok(Some(CountKind::VarInt)),
+        Some(Some("i8")) => Ok(Some(CountKind::Byte)),
+        Some(Some("u8")) => Ok(Some(CountKind::UnsignedByte)),
+        Some(Some("i16")) => Ok(Some(CountKind::Short)),
+        Some(Some("i32")) => Ok(Some(CountKind::Int)),
+        Some(_) => Err(SchemaError::UnknownType)?,
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use crate::{
+        data_types::{DataTypes, Errors, GenericError, Identifier, VarInt},
+        values::Version,
+    };
+
+    use super::{ConnectionState, Direction, ProtocolSchema, SchemaError};
+
+    const SCHEMA: &str = r#"{
+        "types": {
+            "hand": ["mapper", { "type": "varint", "mappings": { "0": "main_hand", "1": "off_hand" } }],
+            "vec3": ["container", [
+                { "name": "x", "type": "f64" },
+                { "name": "y", "type": "f64" },
+                { "name": "z", "type": "f64" }
+            ]]
+        },
+        "handshaking": {
+            "toServer": {
+                "set_protocol": { "id": "0x00", "type": ["container", [
+                    { "name": "protocolVersion", "type": "varint" },
+                    { "name": "serverHost", "type": "string" },
+                    { "name": "serverPort", "type": "u16" },
+                    { "name": "nextState", "type": "varint" }
+                ]] }
+            }
+        },
+        "play": {
+            "toServer": {
+                "swing_arm": { "id": 54, "type": ["container", [{ "name": "hand", "type": "hand" }]] },
+                "teleport": { "id": "0x1A", "type": ["container", [
+                    { "name": "to", "type": "vec3" },
+                    { "name": "dimension", "type": ["option", "identifier"] }
+                ]] },
+                "custom_payload": { "id": 16, "type": ["container", [
+                    { "name": "channel", "type": "identifier" },
+                    { "name": "data", "type": "restBuffer" }
+                ]] },
+                "counted": { "id": 17, "type": ["container", [
+                    { "name": "shorts", "type": ["array", { "countType": "i16", "type": "u8" }] },
+                    { "name": "bytes", "type": ["buffer", { "countType": "u8" }] }
+                ]] }
+            },
+            "toClient": {
+                "bundle_delimiter": { "id": 0 },
+                "action": { "id": 1, "type": ["enum", { "type": "u8", "variants": [
+                    { "id": 0, "name": "stop" },
+                    { "id": 1, "name": "wait", "type": "varint" }
+                ] }] }
+            }
+        }
+    }"#;
+
+    fn schema() -> ProtocolSchema {
+        ProtocolSchema::from_json(SCHEMA, Version::Release1_20_4).unwrap()
+    }
+
+    #[test]
+    fn lookup() {
+        let schema = schema();
+        let packet = schema
+            .packet(ConnectionState::Play, Direction::Serverbound, "teleport")
+            .unwrap();
+        assert_eq!(packet.id, 0x1A);
+        assert_eq!(
+            schema
+                .packet_by_id(ConnectionState::Play, Direction::Serverbound, 54)
+                .unwrap()
+                .name,
+            "swing_arm"
+        );
+        assert!(schema
+            .packet(ConnectionState::Play, Direction::Clientbound, "teleport")
+            .is_none());
+        assert!(schema
+            .packet(
+                ConnectionState::Login,
+                Direction::Serverbound,
+                "login_start"
+            )
+            .is_none());
+    }
+
+    #[test]
+    fn round_trip() {
+        let schema = schema();
+        let bytes = [
+            vec![0x00, 0xFD, 0x05, 9],
+            b"localhost".to_vec(),
+            vec![0x63, 0xDD, 0x02],
+        ]
+        .concat();
+
+        let (packet, value) = schema
+            .decode(ConnectionState::Handshaking, Direction::Serverbound, &bytes)
+            .unwrap();
+        assert_eq!(packet.name, "set_protocol");
+        assert_eq!(
+            value.field("protocolVersion"),
+            Some(&DataTypes::VarInt(VarInt::new(765)))
+        );
+        assert_eq!(
+            value.field("serverPort"),
+            Some(&DataTypes::UnsignedShort(25565))
+        );
+        assert_eq!(
+            schema
+                .encode(
+                    ConnectionState::Handshaking,
+                    Direction::Serverbound,
+                    "set_protocol",
+                    &value
+                )
+                .unwrap(),
+            bytes
+        );
+    }
+
+    #[test]
+    fn composed_types() {
+        let schema = schema();
+
+        let swing = DataTypes::Container(vec![(
+            "hand".into(),
+            DataTypes::Enum {
+                variant: "off_hand".into(),
+                value: Box::new(DataTypes::Void),
+            },
+        )]);
+        let bytes = schema
+            .encode(
+                ConnectionState::Play,
+                Direction::Serverbound,
+                "swing_arm",
+                &swing,
+            )
+            .unwrap();
+        assert_eq!(bytes, [54, 1]);
+
+        let teleport = DataTypes::Container(vec![
+            (
+                "to".into(),
+                DataTypes::Container(vec![
+                    ("x".into(), DataTypes::Double(1.0)),
+                    ("y".into(), DataTypes::Double(64.0)),
+                    ("z".into(), DataTypes::Double(-1.0)),
+                ]),
+            ),
+            (
+                "dimension".into(),
+                DataTypes::Optional(Some(Box::new(DataTypes::Identifier(
+                    Identifier::from_str("the_nether").unwrap(),
+                )))),
+            ),
+        ]);
+        let bytes = schema
+            .encode(
+                ConnectionState::Play,
+                Direction::Serverbound,
+                "teleport",
+                &teleport,
+            )
+            .unwrap();
+        let (_, decoded) = schema
+            .decode(ConnectionState::Play, Direction::Serverbound, &bytes)
+            .unwrap();
+        assert_eq!(decoded, teleport);
+
+        let (packet, action) = schema
+            .decode(ConnectionState::Play, Direction::Clientbound, &[1, 1, 20])
+            .unwrap();
+        assert_eq!(packet.name, "action");
+        assert_eq!(
+            action,
+            DataTypes::Enum {
+                variant: "wait".into(),
+                value: Box::new(DataTypes::VarInt(VarInt::new(20)))
+            }
+        );
+
+        let (packet, empty) = schema
+            .decode(ConnectionState::Play, Direction::Clientbound, &[0])
+            .unwrap();
+        assert_eq!(packet.name, "bundle_delimiter");
+        assert_eq!(empty, DataTypes::Container(vec![]));
+    }
+
+    #[test]
+    fn count_types() {
+        let schema = schema();
+        let bytes = [17, 0x00, 0x02, 7, 8, 0x01, 0xAA];
+
+        let (packet, value) = schema
+            .decode(ConnectionState::Play, Direction::Serverbound, &bytes)
+            .unwrap();
+        assert_eq!(packet.name, "counted");
+        assert_eq!(
+            value,
+            DataTypes::Container(vec![
+                (
+                    "shorts".into(),
+                    DataTypes::Array(vec![DataTypes::UnsignedByte(7), DataTypes::UnsignedByte(8)])
+                ),
+                ("bytes".into(), DataTypes::ByteArray(vec![0xAA])),
+            ])
+        );
+        assert_eq!(
+            schema
+                .encode(
+                    ConnectionState::Play,
+                    Direction::Serverbound,
+                    "counted",
+                    &value
+                )
+                .unwrap(),
+            bytes
+        );
+    }
+
+    #[test]
+    fn decode_errors() {
+        let schema = schema();
+        assert_eq!(
+            schema
+                .decode(ConnectionState::Play, Direction::Serverbound, &[0x7F])
+                .unwrap_err(),
+            Errors::SchemaError(SchemaError::UnknownPacket)
+        );
+        assert_eq!(
+            schema
+                .decode(ConnectionState::Play, Direction::Serverbound, &[54, 0, 0])
+                .unwrap_err(),
+            Errors::SchemaError(SchemaError::TrailingBytes)
+        );
+        assert_eq!(
+            schema
+                .decode(ConnectionState::Play, Direction::Serverbound, &[54, 2])
+                .unwrap_err(),
+            Errors::GenericError(GenericError::UnknownVariant)
+        );
+    }
+
+    #[test]
+    fn invalid_schemas() {
+        let load = |json: &str| ProtocolSchema::from_json(json, Version::Release1_20_4);
+
+        assert_eq!(
+            load("{"),
+            Err(Errors::SchemaError(SchemaError::InvalidJson))
+        );
+        assert_eq!(
+            load(r#"{ "play": { "toServer": { "a": { "id": 0, "type": "vec4" } } } }"#),
+            Err(Errors::SchemaError(SchemaError::UnknownType))
+        );
+        assert_eq!(
+            load(
+                r#"{ "types": { "a": ["option", "a"] }, "play": { "toServer": { "a": { "id": 0, "type": "a" } } } }"#
+            ),
+            Err(Errors::SchemaError(SchemaError::RecursiveType))
+        );
+        assert_eq!(
+            load(
+                r#"{ "play": { "toServer": { "a": { "id": 0, "type": ["array", { "count": 3, "type": "u8" }] } } } }"#
+            ),
+            Err(Errors::SchemaError(SchemaError::UnknownType))
+        );
+        assert_eq!(
+            load(
+                r#"{ "play": { "toServer": { "a": { "id": 0, "type": ["buffer", { "countType": "u64" }] } } } }"#
+            ),
+            Err(Errors::SchemaError(SchemaError::UnknownType))
+        );
+        assert_eq!(
+            load(r#"{ "play": { "toServer": { "a": { "id": "zero" } } } }"#),
+            Err(Errors::SchemaError(SchemaError::InvalidPacketId))
+        );
+        assert_eq!(
+            load(r#"{ "play": { "toServer": { "a": { "id": 0 }, "b": { "id": "0x00" } } } }"#),
+            Err(Errors::SchemaError(SchemaError::DuplicatePacket))
+        );
+    }
+}
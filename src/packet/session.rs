@@ -0,0 +1,434 @@
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    str::FromStr,
+    sync::{Mutex, PoisonError},
+};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use uuid::Uuid;
+
+use super::{
+    encryption::{request_server_hash, SharedSecret},
+    ServerPacket,
+};
+use crate::data_types::{game_profile::GameProfile, property::Property, DataResult, Username};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Error)]
+pub enum SessionError {
+    #[error("The player has not joined the server with this server hash")]
+    NotJoined,
+    #[error("The session server refused the join request")]
+    JoinRefused,
+    #[error("The session server returned an invalid profile")]
+    InvalidProfile,
+    #[error("The session server could not be reached")]
+    Unavailable,
+    #[error("The packet is not an Encryption Request")]
+    UnexpectedPacket,
+}
+
+/// The session server, through which clients prove to a server that they own their account.
+///
+/// After the encryption handshake, the client calls [`SessionService::join`] with the
+/// [`server_hash`](super::encryption::server_hash), and the server calls
+/// [`SessionService::has_joined`] with the same hash to get the player's profile.
+pub trait SessionService {
+    /// Returns the profile of `username` if it joined with `server_hash`, or `None` otherwise.
+    ///
+    /// `ip` is the address of the client, which vanilla servers only send when `prevent-proxy-connections` is enabled.
+    ///
+    /// ## Errors
+    ///
+    /// Returns a [`SessionError`] if the session server could not be reached or its response is not valid.
+    fn has_joined(
+        &self,
+        username: &str,
+        server_hash: &str,
+        ip: Option<IpAddr>,
+    ) -> DataResult<Option<GameProfile>>;
+
+    /// Tells the session server the player `profile`, logged in with `access_token`, is joining the server with `server_hash`.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`SessionError::JoinRefused`] if the access token is not valid for `profile`,
+    /// or another [`SessionError`] if the session server could not be reached.
+    fn join(&self, access_token: &str, profile: Uuid, server_hash: &str) -> DataResult<()>;
+}
+
+/// Authenticates a player on the server side of the login, after decrypting its [`SharedSecret`].
+///
+/// When the [`ServerPacket::EncryptionRequest`] doesn't require authentication,
+/// the player gets its offline profile, see [`GameProfile::offline`].
+///
+/// ## Errors
+///
+/// Returns [`SessionError::NotJoined`] if the player didn't join with the server hash of `request`,
+/// [`SessionError::UnexpectedPacket`] if `request` is not an Encryption Request,
+/// or any error from [`SessionService::has_joined`].
+pub fn authenticate(
+    service: &impl SessionService,
+    request: &ServerPacket,
+    shared_secret: &SharedSecret,
+    username: &str,
+    ip: Option<IpAddr>,
+) -> DataResult<GameProfile> {
+    let ServerPacket::EncryptionRequest {
+        should_authenticate,
+        ..
+    } = request
+    else {
+        return Err(SessionError::UnexpectedPacket)?;
+    };
+
+    if !bool::from(should_authenticate.clone()) {
+        return GameProfile::offline(username);
+    }
+
+    let server_hash = request_server_hash(request, shared_secret)?;
+    match service.has_joined(username, &server_hash, ip)? {
+        Some(profile) => Ok(profile),
+        None => Err(SessionError::NotJoined)?,
+    }
+}
+
+/// Joins the server on the client side of the login, before sending the Encryption Response.
+///
+/// Does nothing when the [`ServerPacket::EncryptionRequest`] doesn't require authentication.
+///
+/// ## Errors
+///
+/// Returns [`SessionError::UnexpectedPacket`] if `request` is not an Encryption Request,
+/// or any error from [`SessionService::join`].
+pub fn join(
+    service: &impl SessionService,
+    request: &ServerPacket,
+    shared_secret: &SharedSecret,
+    access_token: &str,
+    profile: Uuid,
+) -> DataResult<()> {
+    let ServerPacket::EncryptionRequest {
+        should_authenticate,
+        ..
+    } = request
+    else {
+        return Err(SessionError::UnexpectedPacket)?;
+    };
+
+    if !bool::from(should_authenticate.clone()) {
+        return Ok(());
+    }
+
+    service.join(
+        access_token,
+        profile,
+        &request_server_hash(request, shared_secret)?,
+    )
+}
+
+/// A profile as returned by `hasJoined`, with the UUID without hyphens.
+#[derive(Debug, Serialize, Deserialize)]
+struct ProfileJson {
+    id: String,
+    name: String,
+    #[serde(default)]
+    properties: Vec<PropertyJson>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PropertyJson {
+    name: String,
+    value: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    signature: Option<String>,
+}
+
+/// Parses a profile as returned by `hasJoined`:
+///
+/// ```json
+/// {
+///     "id": "069a79f444e94726a5befca90e38aaf5",
+///     "name": "Notch",
+///     "properties": [{ "name": "textures", "value": "...", "signature": "..." }]
+/// }
+/// ```
+///
+/// ## Errors
+///
+/// Returns [`SessionError::InvalidProfile`] if `json` is not a valid profile.
+pub fn decode_profile(json: &str) -> DataResult<GameProfile> {
+    let profile: ProfileJson =
+        serde_json::from_str(json).map_err(|_| SessionError::InvalidProfile)?;
+
+    Ok(GameProfile {
+        uuid: Uuid::parse_str(&profile.id).map_err(|_| SessionError::InvalidProfile)?,
+        name: Username::from_str(&profile.name).map_err(|_| SessionError::InvalidProfile)?,
+        properties: profile
+            .properties
+            .iter()
+            .map(|property| {
+                Property::new(
+                    &property.name,
+                    &property.value,
+                    property.signature.as_deref(),
+                )
+                .map_err(|_| SessionError::InvalidProfile)
+            })
+            .collect::<Result<_, _>>()?,
+    })
+}
+
+/// Serializes a profile as returned by `hasJoined`, see [`decode_profile`].
+///
+/// ## Errors
+///
+/// Returns [`SessionError::InvalidProfile`] if the profile can't be serialized.
+pub fn encode_profile(profile: &GameProfile) -> DataResult<String> {
+    let profile = ProfileJson {
+        id: profile.uuid.simple().to_string(),
+        name: profile.name.to_string(),
+        properties: profile
+            .properties
+            .iter()
+            .map(|property| PropertyJson {
+                name: property.name.to_string(),
+                value: property.value.to_string(),
+                signature: property.signature.as_ref().map(ToString::to_string),
+            })
+            .collect(),
+    };
+
+    Ok(serde_json::to_string(&profile).map_err(|_| SessionError::InvalidProfile)?)
+}
+
+/// A [`SessionService`] kept in memory, to test logins without Mojang's session server.
+///
+/// Accounts are added with [`MemorySessionService::add_account`], and their access token
+/// can then join servers like with the real session server.
+#[derive(Debug, Default)]
+pub struct MemorySessionService {
+    accounts: Mutex<HashMap<String, GameProfile>>,
+    joined: Mutex<HashMap<(String, String), GameProfile>>,
+}
+
+impl MemorySessionService {
+    #[must_use]
+    pub fn new() -> Self {
+        MemorySessionService::default()
+    }
+
+    /// Adds an account, that can join servers with `access_token`.
+    pub fn add_account(&self, access_token: &str, profile: GameProfile) {
+        self.accounts
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(access_token.to_owned(), profile);
+    }
+}
+
+impl SessionService for MemorySessionService {
+    fn has_joined(
+        &self,
+        username: &str,
+        server_hash: &str,
+        _ip: Option<IpAddr>,
+    ) -> DataResult<Option<GameProfile>> {
+        Ok(self
+            .joined
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(&(username.to_owned(), server_hash.to_owned()))
+            .cloned())
+    }
+
+    fn join(&self, access_token: &str, profile: Uuid, server_hash: &str) -> DataResult<()> {
+        let account = self
+            .accounts
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(access_token)
+            .filter(|account| account.uuid == profile)
+            .cloned()
+            .ok_or(SessionError::JoinRefused)?;
+
+        self.joined
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert((account.name.to_string(), server_hash.to_owned()), account);
+
+        Ok(())
+    }
+}
+
+/// The [`SessionService`] of Mojang, over HTTP.
+#[cfg(feature = "session-http")]
+#[derive(Debug, Clone)]
+pub struct MojangSessionService {
+    base_url: String,
+}
+
+#[cfg(feature = "session-http")]
+impl MojangSessionService {
+    /// URL of Mojang's session server.
+    pub const BASE_URL: &'static str = "https://sessionserver.mojang.com/session/minecraft";
+
+    #[must_use]
+    pub fn new() -> Self {
+        MojangSessionService::with_base_url(Self::BASE_URL)
+    }
+
+    /// Uses another session server with the same API, e.g. an authlib-injector server.
+    #[must_use]
+    pub fn with_base_url(base_url: &str) -> Self {
+        MojangSessionService {
+            base_url: base_url.trim_end_matches('/').to_owned(),
+        }
+    }
+}
+
+#[cfg(feature = "session-http")]
+impl Default for MojangSessionService {
+    fn default() -> Self {
+        MojangSessionService::new()
+    }
+}
+
+#[cfg(feature = "session-http")]
+impl SessionService for MojangSessionService {
+    fn has_joined(
+        &self,
+        username: &str,
+        server_hash: &str,
+        ip: Option<IpAddr>,
+    ) -> DataResult<Option<GameProfile>> {
+        let mut request = ureq::get(&format!("{}/hasJoined", self.base_url))
+            .query("username", username)
+            .query("serverId", server_hash);
+        if let Some(ip) = ip {
+            request = request.query("ip", &ip.to_string());
+        }
+
+        let response = request.call().map_err(|_| SessionError::Unavailable)?;
+        if response.status() == 204 {
+            return Ok(None);
+        }
+
+        let json = response
+            .into_string()
+            .map_err(|_| SessionError::InvalidProfile)?;
+        decode_profile(&json).map(Some)
+    }
+
+    fn join(&self, access_token: &str, profile: Uuid, server_hash: &str) -> DataResult<()> {
+        let body = serde_json::json!({
+            "accessToken": access_token,
+            "selectedProfile": profile.simple().to_string(),
+            "serverId": server_hash,
+        });
+
+        match ureq::post(&format!("{}/join", self.base_url))
+            .set("Content-Type", "application/json")
+            .send_string(&body.to_string())
+        {
+            Ok(_) => Ok(()),
+            Err(ureq::Error::Status(..)) => Err(SessionError::JoinRefused)?,
+            Err(ureq::Error::Transport(_)) => Err(SessionError::Unavailable)?,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use uuid::Uuid;
+
+    use crate::{
+        data_types::{game_profile::GameProfile, property::Property, Errors, Username},
+        packet::encryption::respond_to_request,
+        test_fixtures::KEY,
+    };
+
+    use super::{
+        authenticate, decode_profile, encode_profile, join, MemorySessionService, SessionError,
+    };
+
+    fn notch() -> GameProfile {
+        let mut profile = GameProfile::new(
+            Uuid::from_str("069a79f4-44e9-4726-a5be-fca90e38aaf5").unwrap(),
+            Username::from_str("Notch").unwrap(),
+        );
+        profile
+            .properties
+            .push(Property::new("textures", "e30=", Some("c2lnbmF0dXJl")).unwrap());
+        profile
+    }
+
+    #[test]
+    fn login() {
+        let service = MemorySessionService::new();
+        service.add_account("token", notch());
+
+        let (request, verify_token) = KEY.request("", true).unwrap();
+        let (response, client_secret) = respond_to_request(&request).unwrap();
+        join(&service, &request, &client_secret, "token", notch().uuid).unwrap();
+
+        let server_secret = KEY.decrypt_response(verify_token, &response).unwrap();
+        assert_eq!(
+            authenticate(&service, &request, &server_secret, "Notch", None).unwrap(),
+            notch()
+        );
+        assert_eq!(
+            authenticate(&service, &request, &server_secret, "jeb_", None),
+            Err(Errors::SessionError(SessionError::NotJoined))
+        );
+
+        let (other_request, _) = KEY.request("other", true).unwrap();
+        assert_eq!(
+            authenticate(&service, &other_request, &server_secret, "Notch", None),
+            Err(Errors::SessionError(SessionError::NotJoined))
+        );
+        assert_eq!(
+            join(&service, &request, &client_secret, "wrong", notch().uuid),
+            Err(Errors::SessionError(SessionError::JoinRefused))
+        );
+    }
+
+    #[test]
+    fn offline() {
+        let service = MemorySessionService::new();
+
+        let (request, _) = KEY.request("", false).unwrap();
+        let (_, secret) = respond_to_request(&request).unwrap();
+        join(&service, &request, &secret, "token", notch().uuid).unwrap();
+
+        assert_eq!(
+            authenticate(&service, &request, &secret, "Notch", None).unwrap(),
+            GameProfile::offline("Notch").unwrap()
+        );
+    }
+
+    #[test]
+    fn profile_json() {
+        let profile = decode_profile(
+            r#"{
+                "id": "069a79f444e94726a5befca90e38aaf5",
+                "name": "Notch",
+                "properties": [{ "name": "textures", "value": "e30=", "signature": "c2lnbmF0dXJl" }]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(profile, notch());
+        assert_eq!(
+            decode_profile(&encode_profile(&profile).unwrap()).unwrap(),
+            profile
+        );
+
+        assert_eq!(
+            decode_profile(r#"{ "id": "not a uuid", "name": "Notch" }"#),
+            Err(Errors::SessionError(SessionError::InvalidProfile))
+        );
+    }
+}
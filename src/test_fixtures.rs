@@ -0,0 +1,15 @@
+//! Values shared by the tests of several modules.
+
+use std::{str::FromStr, sync::LazyLock};
+
+use crate::{data_types::Identifier, packet::encryption::ServerKey};
+
+/// A server key generated once, since RSA key generation is slow.
+pub(crate) static KEY: LazyLock<ServerKey> = LazyLock::new(|| ServerKey::generate().unwrap());
+
+/// Another key, for the tests that need a key other than [`KEY`].
+pub(crate) static OTHER_KEY: LazyLock<ServerKey> = LazyLock::new(|| ServerKey::generate().unwrap());
+
+pub(crate) fn id(value: &str) -> Identifier {
+    Identifier::from_str(value).unwrap()
+}
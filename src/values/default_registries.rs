@@ -0,0 +1,282 @@
+use std::{str::FromStr, sync::LazyLock};
+
+use quartz_nbt::{snbt, NbtTag};
+
+use super::Version;
+use crate::data_types::{
+    registries::RegistryElement,
+    registry_data::{RegistryData, RegistryDataForm},
+    registry_data_entry::Entry,
+    DataResult, Identifier,
+};
+
+/// Vanilla registries synchronized from 23w31a to 1.20.4, as the compound of the Registry Data packet.
+const VANILLA_1_20_2: &str = include_str!("registries/1_20_2.snbt");
+
+static VANILLA_1_20_2_REGISTRIES: LazyLock<Vec<RegistryData>> = LazyLock::new(|| {
+    let codec = snbt::parse(VANILLA_1_20_2).expect("bundled registries are valid SNBT");
+
+    let mut registries = codec
+        .inner()
+        .values()
+        .map(|registry| match registry {
+            NbtTag::Compound(registry) => RegistryData::from_single_nbt(registry),
+            _ => panic!("bundled registries are compounds"),
+        })
+        .collect::<DataResult<Vec<_>>>()
+        .expect("bundled registries are valid");
+
+    registries.sort_by(|a, b| a.registry_id.cmp(&b.registry_id));
+    registries
+});
+
+/// The registries a vanilla server sends during configuration.
+///
+/// Entries can be overridden with [`DefaultRegistries::set`] before sending them,
+/// either with [`DefaultRegistries::encode`] or one [`RegistryData`] at a time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DefaultRegistries {
+    registries: Vec<RegistryData>,
+}
+
+impl DefaultRegistries {
+    /// Returns the vanilla registries of `version`, or `None` before 23w31a,
+    /// when registries were sent in the Login (play) packet instead.
+    #[must_use]
+    pub fn for_version(version: Version) -> Option<Self> {
+        RegistryDataForm::for_version(version).map(|_| DefaultRegistries {
+            registries: VANILLA_1_20_2_REGISTRIES.clone(),
+        })
+    }
+
+    /// Returns every registry, sorted by id.
+    #[must_use]
+    pub fn registries(&self) -> &[RegistryData] {
+        &self.registries
+    }
+
+    #[must_use]
+    pub fn into_registries(self) -> Vec<RegistryData> {
+        self.registries
+    }
+
+    /// Returns the registry `id`, e.g. `minecraft:worldgen/biome`.
+    #[must_use]
+    pub fn registry(&self, id: &Identifier) -> Option<&RegistryData> {
+        self.registries
+            .iter()
+            .find(|registry| &registry.registry_id == id)
+    }
+
+    /// Returns the element `id` of the registry of `T`.
+    ///
+    /// ## Errors
+    ///
+    /// Returns an [`IdentifierError`](crate::data_types::IdentifierError) if [`RegistryElement::REGISTRY`] is not valid,
+    /// or a [`RegistryDataError`](crate::data_types::registry_data::RegistryDataError) if the element is not a valid `T`.
+    pub fn element<T: RegistryElement>(&self, id: &Identifier) -> DataResult<Option<T>> {
+        let registry_id = Identifier::from_str(T::REGISTRY)?;
+
+        match self
+            .registry(&registry_id)
+            .and_then(|registry| registry.entry(id))
+        {
+            Some(entry) => entry.element(),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns every element of the registry of `T`, ordered by network id.
+    ///
+    /// ## Errors
+    ///
+    /// See [`DefaultRegistries::element`].
+    pub fn elements<T: RegistryElement>(&self) -> DataResult<Vec<(Identifier, T)>> {
+        let registry_id = Identifier::from_str(T::REGISTRY)?;
+
+        let Some(registry) = self.registry(&registry_id) else {
+            return Ok(Vec::new());
+        };
+
+        registry
+            .entries
+            .iter()
+            .filter_map(|entry| {
+                entry
+                    .element()
+                    .transpose()
+                    .map(|element| element.map(|element| (entry.id.clone(), element)))
+            })
+            .collect()
+    }
+
+    /// Sets the element `id` of the registry of `T`.
+    ///
+    /// An existing element keeps its network id, a new one is added at the end of the registry.
+    ///
+    /// ## Errors
+    ///
+    /// Returns an [`IdentifierError`](crate::data_types::IdentifierError) if [`RegistryElement::REGISTRY`] is not valid.
+    pub fn set<T: RegistryElement>(&mut self, id: Identifier, element: &T) -> DataResult<()> {
+        let registry_id = Identifier::from_str(T::REGISTRY)?;
+        let entry = Entry::from_element(id, element);
+
+        let index = self
+            .registries
+            .partition_point(|registry| registry.registry_id < registry_id);
+        if self
+            .registries
+            .get(index)
+            .map(|registry| &registry.registry_id)
+            != Some(&registry_id)
+        {
+            self.registries
+                .insert(index, RegistryData::new(registry_id, Vec::new()));
+        }
+        let registry = &mut self.registries[index];
+
+        match registry.network_id(&entry.id) {
+            Some(index) => registry.entries[index] = entry,
+            None => registry.entries.push(entry),
+        }
+
+        Ok(())
+    }
+
+    /// Removes the element `id` of the registry of `T`, shifting the network ids of the elements after it.
+    ///
+    /// Returns whether the element was present.
+    ///
+    /// ## Errors
+    ///
+    /// Returns an [`IdentifierError`](crate::data_types::IdentifierError) if [`RegistryElement::REGISTRY`] is not valid.
+    pub fn remove<T: RegistryElement>(&mut self, id: &Identifier) -> DataResult<bool> {
+        let registry_id = Identifier::from_str(T::REGISTRY)?;
+
+        let Some(registry) = self
+            .registries
+            .iter_mut()
+            .find(|registry| registry.registry_id == registry_id)
+        else {
+            return Ok(false);
+        };
+
+        Ok(match registry.network_id(id) {
+            Some(index) => {
+                registry.entries.remove(index);
+                true
+            }
+            None => false,
+        })
+    }
+
+    /// Encodes the body of the Registry Data packet for `version`.
+    ///
+    /// ## Errors
+    ///
+    /// See [`RegistryData::encode_single_nbt`].
+    pub fn encode(&self, version: Version) -> DataResult<Vec<u8>> {
+        RegistryData::encode_single_nbt(&self.registries, version)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        data_types::{
+            registries::{Biome, ChatType, DamageType, DimensionType, TrimMaterial, TrimPattern},
+            registry_data::RegistryData,
+        },
+        test_fixtures::id,
+        values::Version,
+    };
+
+    use super::DefaultRegistries;
+
+    #[test]
+    fn for_version() {
+        assert_eq!(DefaultRegistries::for_version(Version::Release1_20_1), None);
+        assert_eq!(
+            DefaultRegistries::for_version(Version::Release1_20_2),
+            DefaultRegistries::for_version(Version::Release1_20_4)
+        );
+    }
+
+    #[test]
+    fn typed() {
+        let registries = DefaultRegistries::for_version(Version::Release1_20_4).unwrap();
+
+        assert_eq!(registries.registries().len(), 6);
+        assert_eq!(registries.elements::<DimensionType>().unwrap().len(), 4);
+        assert_eq!(registries.elements::<Biome>().unwrap().len(), 64);
+        assert_eq!(registries.elements::<ChatType>().unwrap().len(), 7);
+        assert_eq!(registries.elements::<DamageType>().unwrap().len(), 44);
+        assert_eq!(registries.elements::<TrimPattern>().unwrap().len(), 16);
+        assert_eq!(registries.elements::<TrimMaterial>().unwrap().len(), 10);
+
+        let overworld = registries
+            .element::<DimensionType>(&id("minecraft:overworld"))
+            .unwrap()
+            .unwrap();
+        assert_eq!((overworld.min_y, overworld.height), (-64, 384));
+
+        let plains = registries
+            .element::<Biome>(&id("minecraft:plains"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(plains.effects.sky_color, 7_907_327);
+
+        let biomes = registries
+            .registry(&id("minecraft:worldgen/biome"))
+            .unwrap();
+        assert_eq!(biomes.network_id(&id("minecraft:badlands")), Some(0));
+    }
+
+    #[test]
+    fn overrides() {
+        let mut registries = DefaultRegistries::for_version(Version::Release1_20_4).unwrap();
+
+        let mut overworld = registries
+            .element::<DimensionType>(&id("minecraft:overworld"))
+            .unwrap()
+            .unwrap();
+        overworld.height = 512;
+        registries
+            .set(id("minecraft:overworld"), &overworld)
+            .unwrap();
+        registries.set(id("example:flat"), &overworld).unwrap();
+
+        let dimensions = registries
+            .registry(&id("minecraft:dimension_type"))
+            .unwrap();
+        assert_eq!(dimensions.network_id(&id("minecraft:overworld")), Some(0));
+        assert_eq!(dimensions.network_id(&id("example:flat")), Some(4));
+        assert_eq!(
+            registries
+                .element::<DimensionType>(&id("minecraft:overworld"))
+                .unwrap()
+                .unwrap()
+                .height,
+            512
+        );
+
+        assert!(registries
+            .remove::<DimensionType>(&id("minecraft:overworld_caves"))
+            .unwrap());
+        assert!(!registries
+            .remove::<DimensionType>(&id("minecraft:overworld_caves"))
+            .unwrap());
+        assert_eq!(registries.elements::<DimensionType>().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn encode() {
+        let registries = DefaultRegistries::for_version(Version::Release1_20_2).unwrap();
+        let bytes = registries.encode(Version::Release1_20_2).unwrap();
+
+        let (decoded, len) =
+            RegistryData::decode_single_nbt(&bytes, Version::Release1_20_2).unwrap();
+        assert_eq!(len, bytes.len());
+        assert_eq!(decoded, registries.into_registries());
+    }
+}
@@ -1 +1,4 @@
+pub mod default_registries;
 pub mod versions;
+
+pub use versions::*;
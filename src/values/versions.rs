@@ -3,7 +3,7 @@
 use crate::data_types::VarInt;
 
 /// Enum of all versions of the game after the Netty rewrite.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Version {
     /// Snapshot 13w41b (0)
     Snapshot13W41B,